@@ -0,0 +1,83 @@
+#![no_std]
+#![no_main]
+#![allow(clippy::empty_loop)]
+
+use hardware::adc::{convert, AdcScan, AnalogWatchdog, WatchdogChannel};
+
+use defmt::println;
+use defmt_rtt as _;
+use panic_probe as _;
+
+use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs;
+use cortex_m::singleton;
+use cortex_m_rt::entry;
+use stm32f1xx_hal::adc::{Adc, SampleTime};
+use stm32f1xx_hal::pac;
+use stm32f1xx_hal::prelude::{
+    _fugit_RateExtU32, _stm32_hal_dma_DmaExt, _stm32_hal_flash_FlashExt, _stm32_hal_gpio_GpioExt,
+};
+use stm32f1xx_hal::rcc::RccExt;
+use stm32f1xx_hal::timer::SysTimerExt;
+
+/// 阈值窗口下限，单位：mV
+const LOW_MV: u32 = 1000;
+/// 阈值窗口上限，单位：mV
+const HIGH_MV: u32 = 2300;
+
+#[entry]
+fn main() -> ! {
+    // 获取对外设的访问对象
+    let mut cp = cortex_m::Peripherals::take().unwrap();
+    let dp = pac::Peripherals::take().unwrap();
+
+    let mut flash = dp.FLASH.constrain();
+    let rcc = dp.RCC.constrain();
+    let dma_ch1 = dp.DMA1.split().1;
+
+    let mut gpioa = dp.GPIOA.split();
+    let mut gpioc = dp.GPIOC.split();
+
+    // ADC 时钟不能超过 14MHz，预分频器会取最接近的值
+    let clocks = rcc.cfgr.adcclk(12.MHz()).freeze(&mut flash.acr);
+
+    // 具有自定义精度的阻塞延迟函数
+    let mut delay = cp.SYST.delay(&clocks);
+
+    // 报警指示灯，低电平点亮
+    let mut led = gpioc.pc13.into_push_pull_output(&mut gpioc.crh);
+    led.set_high();
+
+    // 电位器模拟输入
+    let _pa0 = gpioa.pa0.into_analog(&mut gpioa.crl);
+
+    println!("load adc...");
+    let adc1 = Adc::adc1(dp.ADC1, clocks);
+    let buffer = singleton!(: [[u16; 1]; 4] = [[0; 1]; 4]).unwrap();
+    let mut scan = AdcScan::new(adc1, dma_ch1, [0], SampleTime::T_55, buffer);
+    scan.start();
+
+    // 阈值为原始值，按默认供电电压换算
+    let low = convert::to_raw(LOW_MV, convert::DEFAULT_VDDA_MV);
+    let high = convert::to_raw(HIGH_MV, convert::DEFAULT_VDDA_MV);
+    let mut watchdog = AnalogWatchdog::new(WatchdogChannel::Single(0), low, high);
+
+    println!("loop ...");
+    loop {
+        // 睡眠等待越界，转换及 DMA 在睡眠中继续运行
+        watchdog.sleep_until_triggered(&mut cp.SCB);
+        led.set_low();
+        println!("out of window: {:?}mV", scan.millivolts(0));
+
+        // 等待电压回到窗口内
+        loop {
+            let raw = scan.raw(0);
+            if raw >= low && raw <= high {
+                break;
+            }
+            delay.delay_ms(100_u16);
+        }
+        println!("back in window: {:?}mV", scan.millivolts(0));
+        led.set_high();
+        AnalogWatchdog::clear();
+    }
+}
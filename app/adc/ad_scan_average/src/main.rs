@@ -0,0 +1,87 @@
+#![no_std]
+#![no_main]
+#![allow(clippy::empty_loop)]
+
+use hardware::adc::{AdcScan, CHANNEL_TEMPERATURE, CHANNEL_VREFINT};
+use hardware::oled;
+
+use defmt::println;
+use defmt_rtt as _;
+use panic_probe as _;
+
+use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs;
+use cortex_m::singleton;
+use cortex_m_rt::entry;
+use stm32f1xx_hal::adc::{Adc, SampleTime};
+use stm32f1xx_hal::pac;
+use stm32f1xx_hal::prelude::{
+    _fugit_RateExtU32, _stm32_hal_dma_DmaExt, _stm32_hal_flash_FlashExt, _stm32_hal_gpio_GpioExt,
+};
+use stm32f1xx_hal::rcc::RccExt;
+use stm32f1xx_hal::timer::SysTimerExt;
+
+#[entry]
+fn main() -> ! {
+    // 获取对外设的访问对象
+    let cp = cortex_m::Peripherals::take().unwrap();
+    let dp = pac::Peripherals::take().unwrap();
+
+    let mut flash = dp.FLASH.constrain();
+    let rcc = dp.RCC.constrain();
+    let dma_ch1 = dp.DMA1.split().1;
+
+    let mut gpioa = dp.GPIOA.split();
+    let mut gpiob = dp.GPIOB.split();
+
+    // ADC 时钟不能超过 14MHz，预分频器会取最接近的值
+    let clocks = rcc.cfgr.adcclk(12.MHz()).freeze(&mut flash.acr);
+
+    // 具有自定义精度的阻塞延迟函数
+    let mut delay = cp.SYST.delay(&clocks);
+
+    // 初始化 OLED 显示屏
+    println!("load oled...");
+    let mut oled = oled::simple::init_oled(gpiob.pb8, gpiob.pb9, &mut gpiob.crh);
+
+    // 模拟输入
+    let _pa0 = gpioa.pa0.into_analog(&mut gpioa.crl);
+    let _pa1 = gpioa.pa1.into_analog(&mut gpioa.crl);
+    let _pa2 = gpioa.pa2.into_analog(&mut gpioa.crl);
+    let _pa3 = gpioa.pa3.into_analog(&mut gpioa.crl);
+
+    // 连续扫描，16 组求平均
+    println!("load adc...");
+    let adc1 = Adc::adc1(dp.ADC1, clocks);
+    let buffer = singleton!(: [[u16; 6]; 16] = [[0; 6]; 16]).unwrap();
+    let channels = [0, 1, 2, 3, CHANNEL_VREFINT, CHANNEL_TEMPERATURE];
+    let mut scan = AdcScan::new(adc1, dma_ch1, channels, SampleTime::T_55, buffer);
+    scan.calibrate();
+
+    oled.show_string(1, 1, "AD0:    AD1:");
+    oled.show_string(2, 1, "AD2:    AD3:");
+    oled.show_string(3, 1, "VDD:    mV");
+    oled.show_string(4, 1, "Temp:");
+    println!("loop ...");
+    loop {
+        let vdda = scan.vdda_mv();
+        let temperature = scan.temperature().unwrap();
+        println!(
+            "mv=[{:?}, {:?}, {:?}, {:?}] vdda={:?} temperature={:?}",
+            scan.millivolts(0),
+            scan.millivolts(1),
+            scan.millivolts(2),
+            scan.millivolts(3),
+            vdda,
+            temperature
+        );
+
+        oled.show_num(1, 5, scan.millivolts(0), 4);
+        oled.show_num(1, 13, scan.millivolts(1), 4);
+        oled.show_num(2, 5, scan.millivolts(2), 4);
+        oled.show_num(2, 13, scan.millivolts(3), 4);
+        oled.show_num(3, 5, vdda, 4);
+        oled.show_signed_num(4, 6, temperature as i32, 3);
+
+        delay.delay_ms(500_u16);
+    }
+}
@@ -0,0 +1,121 @@
+#![no_std]
+#![no_main]
+#![allow(clippy::empty_loop)]
+
+use core::cell::RefCell;
+
+use hardware::adc::AdcSampler;
+
+use defmt::println;
+use defmt_rtt as _;
+use panic_probe as _;
+
+use cortex_m::asm::wfi;
+use cortex_m::interrupt::Mutex;
+use cortex_m::peripheral::NVIC;
+use cortex_m::singleton;
+use cortex_m_rt::entry;
+use heapless::Deque;
+use stm32f1xx_hal::adc::{Adc, SampleTime};
+use stm32f1xx_hal::dma::Half;
+use stm32f1xx_hal::pac::{self, interrupt};
+use stm32f1xx_hal::prelude::{
+    _fugit_RateExtU32, _stm32_hal_dma_DmaExt, _stm32_hal_flash_FlashExt, _stm32_hal_gpio_GpioExt,
+};
+use stm32f1xx_hal::rcc::RccExt;
+
+/// 每个数据块的采样数
+const BLOCK_LEN: usize = 500;
+/// 采样率，单位：Hz
+const RATE_HZ: u32 = 10_000;
+
+/// 数据块统计结果
+#[derive(Debug, Clone, Copy, defmt::Format)]
+struct BlockStats {
+    half: u8,
+    min: u16,
+    max: u16,
+    mean: u16,
+}
+
+static G_SAMPLER: Mutex<RefCell<Option<AdcSampler<1, BLOCK_LEN>>>> = Mutex::new(RefCell::new(None));
+static G_QUEUE: Mutex<RefCell<Deque<BlockStats, 8>>> = Mutex::new(RefCell::new(Deque::new()));
+
+#[entry]
+fn main() -> ! {
+    // 获取对外设的访问对象
+    let dp = pac::Peripherals::take().unwrap();
+
+    let mut flash = dp.FLASH.constrain();
+    let rcc = dp.RCC.constrain();
+    let dma_ch1 = dp.DMA1.split().1;
+
+    let mut gpioa = dp.GPIOA.split();
+
+    // ADC 时钟不能超过 14MHz
+    let clocks = rcc
+        .cfgr
+        .sysclk(72.MHz())
+        .pclk1(36.MHz())
+        .adcclk(12.MHz())
+        .freeze(&mut flash.acr);
+
+    // 模拟输入
+    let _pa0 = gpioa.pa0.into_analog(&mut gpioa.crl);
+
+    println!("load adc...");
+    let adc1 = Adc::adc1(dp.ADC1, clocks);
+    let buffer = singleton!(: [[u16; BLOCK_LEN]; 2] = [[0; BLOCK_LEN]; 2]).unwrap();
+    let mut sampler = AdcSampler::new(
+        adc1,
+        dma_ch1,
+        dp.TIM3,
+        &clocks,
+        [0],
+        SampleTime::T_28,
+        buffer,
+    );
+    sampler.start(RATE_HZ);
+    println!("rate={:?}Hz", sampler.rate_hz());
+
+    cortex_m::interrupt::free(|cs| G_SAMPLER.borrow(cs).replace(Some(sampler)));
+    unsafe {
+        NVIC::unmask(interrupt::DMA1_CHANNEL1);
+    }
+
+    println!("loop ...");
+    loop {
+        let stats = cortex_m::interrupt::free(|cs| G_QUEUE.borrow(cs).borrow_mut().pop_front());
+        match stats {
+            Some(stats) => println!("{:?}", stats),
+            None => wfi(),
+        }
+    }
+}
+
+/// 数据块写满
+#[interrupt]
+fn DMA1_CHANNEL1() {
+    cortex_m::interrupt::free(|cs| {
+        let mut sampler = G_SAMPLER.borrow(cs).borrow_mut();
+        let sampler = sampler.as_mut().unwrap();
+        sampler.on_interrupt(|half, block| {
+            let mut min = u16::MAX;
+            let mut max = 0;
+            let mut sum = 0_u32;
+            for &value in block.iter() {
+                min = min.min(value);
+                max = max.max(value);
+                sum += value as u32;
+            }
+            let stats = BlockStats {
+                half: if half == Half::First { 0 } else { 1 },
+                min,
+                max,
+                mean: (sum / BLOCK_LEN as u32) as u16,
+            };
+            // 队列已满时丢弃
+            G_QUEUE.borrow(cs).borrow_mut().push_back(stats).ok();
+        });
+    });
+}
@@ -0,0 +1,100 @@
+#![no_std]
+#![no_main]
+#![allow(clippy::empty_loop)]
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use hardware::dma::{bench, Channel, MemDma};
+
+use defmt::println;
+use defmt_rtt as _;
+use panic_probe as _;
+
+use cortex_m::asm::wfi;
+use cortex_m::peripheral::NVIC;
+use cortex_m::singleton;
+use cortex_m_rt::entry;
+use stm32f1xx_hal::dma::dma1;
+use stm32f1xx_hal::pac::{self, interrupt};
+use stm32f1xx_hal::prelude::{_fugit_RateExtU32, _stm32_hal_dma_DmaExt, _stm32_hal_flash_FlashExt};
+use stm32f1xx_hal::rcc::RccExt;
+
+/// 填充值，传输期间被 DMA 重复读取
+static FILL_VALUE: u32 = 0xA5A5_A5A5;
+/// 传输完成标志
+static G_DONE: AtomicBool = AtomicBool::new(false);
+
+#[entry]
+fn main() -> ! {
+    // 获取对外设的访问对象
+    let mut cp = cortex_m::Peripherals::take().unwrap();
+    let dp = pac::Peripherals::take().unwrap();
+
+    let mut flash = dp.FLASH.constrain();
+    let rcc = dp.RCC.constrain();
+    let dma_ch1 = dp.DMA1.split().1;
+
+    let _clocks = rcc.cfgr.sysclk(72.MHz()).freeze(&mut flash.acr);
+
+    let mut dma = MemDma::new(dma_ch1);
+
+    // 不同数据量下的耗时对比
+    let src = singleton!(: [u32; 1024] = [0x1234_5678; 1024]).unwrap();
+    let dst = singleton!(: [u32; 1024] = [0; 1024]).unwrap();
+    for len in [4, 16, 64, 256, 1024] {
+        let result = bench::compare(
+            &mut dma,
+            &mut cp.DCB,
+            &mut cp.DWT,
+            &src[..len],
+            &mut dst[..len],
+        )
+        .unwrap();
+        println!(
+            "len={:?} dma={:?} cpu={:?} speedup={:?}",
+            result.len,
+            result.dma_cycles,
+            result.cpu_cycles,
+            result.speedup()
+        );
+    }
+
+    // 后台填充，传输完成中断置位标志
+    dma.listen();
+    unsafe {
+        NVIC::unmask(interrupt::DMA1_CHANNEL1);
+    }
+    let transfer = dma.fill(&FILL_VALUE, dst);
+    while !G_DONE.load(Ordering::Acquire) {
+        wfi();
+    }
+    let (mut dma, result, _, dst) = transfer.wait();
+    println!("fill {:?} dst[0]={:x}", result, dst[0]);
+    dma.unlisten();
+
+    // 后台复制，完成前无法访问 src 与 dst
+    let mut transfer = dma.copy(src, dst);
+    let mut polls = 0_u32;
+    while !transfer.is_done() {
+        polls += 1;
+    }
+    let (_dma, result, src, dst) = transfer.wait();
+    println!(
+        "copy {:?} polls={:?} equal={:?}",
+        result,
+        polls,
+        src[..] == dst[..]
+    );
+
+    println!("loop ...");
+    loop {
+        wfi();
+    }
+}
+
+/// 传输完成
+#[interrupt]
+fn DMA1_CHANNEL1() {
+    dma1::C1::clear_flags();
+    G_DONE.store(true, Ordering::Release);
+}
@@ -2,6 +2,7 @@
 #![no_main]
 #![allow(clippy::empty_loop)]
 
+use hardware::dma::MemDma;
 use hardware::oled;
 
 use defmt::println;
@@ -31,7 +32,7 @@ fn main() -> ! {
     let mut flash: flash::Parts = dp.FLASH.constrain();
     let rcc: rcc::Rcc = dp.RCC.constrain();
     let syst = cp.SYST;
-    let mut dma_ch1 = dp.DMA1.split().1;
+    let dma_ch1 = dp.DMA1.split().1;
 
     let mut gpiob: gpiob::Parts = dp.GPIOB.split();
 
@@ -54,41 +55,13 @@ fn main() -> ! {
     // Flash 到 SRAM 转运, 使用 const
     // const data_a: [u8; 4] = [1, 2, 3, 4];
 
-    // 定义u8类型的数组
-    let data_b: [u8; 4] = [0, 0, 0, 0];
-
-    // 关联的外围设备地址
-    // inc指示地址是否在每次字节传输后递增
-    dma_ch1.set_peripheral_address(data_a.as_ptr() as u32, true);
-    // address where from/to data will be read/write
-    // inc指示地址是否在每次字节传输后递增
-    // 数组间的转运需要自增
-    dma_ch1.set_memory_address(data_b.as_ptr() as u32, true);
-    // 要传输的字节数
-    // 注意：这里要乘以源数据的字节宽度
-    #[allow(clippy::identity_op)]
-    dma_ch1.set_transfer_length(data_b.len() * 1);
-
-    // 数据传输方向
-    dma_ch1.ch().cr.modify(|_, w| w.dir().from_peripheral());
-
-    dma_ch1.ch().cr.modify(|_, w| {
-        w.mem2mem()
-            .enabled()
-            .pl()
-            .medium()
-            // .msize()
-            // .bits8()
-            // .psize()
-            // .bits32()
-            // .circ()
-            // .clear_bit()
-            .dir()
-            .from_peripheral()
-    });
+    // 定义可变u8类型的数组
+    let mut data_b: [u8; 4] = [0, 0, 0, 0];
 
-    // 启动DMA传输
-    dma_ch1.start();
+    // 源为外设地址、目标为存储器地址，MEM2MEM 模式下立即开始转运
+    // 数据宽度由数组元素类型决定，阻塞等待转运完成后返回
+    let mut dma = MemDma::new(dma_ch1);
+    dma.copy_blocking(&data_a, &mut data_b).unwrap();
 
     oled.show_hex_num(1, 1, data_a[0].into(), 2);
     oled.show_hex_num(1, 4, data_a[1].into(), 2);
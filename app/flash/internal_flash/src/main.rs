@@ -2,7 +2,9 @@
 #![no_main]
 #![allow(clippy::empty_loop)]
 
-use hardware::{flash_store::FlashStore, key::get_key_status, oled};
+use hardware::flash_store::FlashStore;
+use hardware::key::{EventKind, KeyTiming, Keys};
+use hardware::oled;
 
 use defmt::println;
 use defmt_rtt as _;
@@ -18,6 +20,9 @@ use stm32f1xx_hal::{
     timer::SysTimerExt,
 };
 
+/// 按键扫描周期，单位：ms
+const KEY_TICK_MS: u16 = 10;
+
 #[entry]
 fn main() -> ! {
     // 获取对外设的访问对象
@@ -44,8 +49,14 @@ fn main() -> ! {
     delay.delay_ms(1000_u32);
 
     // 按键
-    let mut key1 = gpiob.pb1.into_pull_up_input(&mut gpiob.crl);
-    let mut key2 = gpiob.pb11.into_pull_up_input(&mut gpiob.crh);
+    let key1 = gpiob.pb1.into_pull_up_input(&mut gpiob.crl);
+    let key2 = gpiob.pb11.into_pull_up_input(&mut gpiob.crh);
+    let mut keys = Keys::new(
+        [key1.erase(), key2.erase()],
+        true,
+        KEY_TICK_MS,
+        KeyTiming::default(),
+    );
 
     let flash_store = FlashStore::new();
     // 参数存储模块初始化，在上电的时候将闪存的数据加载回Store_Data，实现掉电不丢失
@@ -54,32 +65,45 @@ fn main() -> ! {
     oled.show_string(1, 1, "Flag:");
     oled.show_string(2, 1, "Data:");
 
+    let mut changed = true;
     loop {
-        // 获取按键状态
-        // 按键1按下
-        if get_key_status(&mut key1, &mut delay) {
-            // 变换测试数据
-            flash_store.set_store(1, flash_store.get_store(1) + 1);
-            flash_store.set_store(2, flash_store.get_store(2) + 2);
-            flash_store.set_store(3, flash_store.get_store(3) + 3);
-            flash_store.set_store(4, flash_store.get_store(4) + 4);
-
-            // 将Store_Data的数据备份保存到闪存，实现掉电不丢失
-            flash_store.store_save();
+        keys.tick();
+        while let Some(event) = keys.poll_event() {
+            match (event.key, event.kind) {
+                // 按键1单击
+                (0, EventKind::Click) => {
+                    // 变换测试数据
+                    flash_store.set_store(1, flash_store.get_store(1) + 1);
+                    flash_store.set_store(2, flash_store.get_store(2) + 2);
+                    flash_store.set_store(3, flash_store.get_store(3) + 3);
+                    flash_store.set_store(4, flash_store.get_store(4) + 4);
+
+                    // 将Store_Data的数据备份保存到闪存，实现掉电不丢失
+                    flash_store.store_save();
+                    changed = true;
+                }
+                // 按键2长按，避免误触清除数据
+                (1, EventKind::LongPress) => {
+                    // 将Store_Data的数据全部清0
+                    flash_store.store_clear();
+                    changed = true;
+                }
+                _ => {}
+            }
         }
 
-        // 按键2按下
-        if get_key_status(&mut key2, &mut delay) {
-            // 将Store_Data的数据全部清0
-            flash_store.store_clear();
+        // 数据变化时才刷新显示，保持按键扫描周期稳定
+        if changed {
+            changed = false;
+            // 显示Store_Data的第一位标志位
+            oled.show_hex_num(1, 6, flash_store.get_store(0).into(), 4);
+            // 显示Store_Data的有效存储数据
+            oled.show_hex_num(3, 1, flash_store.get_store(1).into(), 4);
+            oled.show_hex_num(3, 6, flash_store.get_store(2).into(), 4);
+            oled.show_hex_num(4, 1, flash_store.get_store(3).into(), 4);
+            oled.show_hex_num(4, 6, flash_store.get_store(4).into(), 4);
         }
 
-        // 显示Store_Data的第一位标志位
-        oled.show_hex_num(1, 6, flash_store.get_store(0).into(), 4);
-        // 显示Store_Data的有效存储数据
-        oled.show_hex_num(3, 1, flash_store.get_store(1).into(), 4);
-        oled.show_hex_num(3, 6, flash_store.get_store(2).into(), 4);
-        oled.show_hex_num(4, 1, flash_store.get_store(3).into(), 4);
-        oled.show_hex_num(4, 6, flash_store.get_store(4).into(), 4);
+        delay.delay_ms(KEY_TICK_MS);
     }
 }
@@ -4,6 +4,8 @@
 #![no_main]
 #![deny(unsafe_code)]
 
+use hardware::key::{EventKind, KeyTiming, Keys};
+
 use defmt_rtt as _;
 use panic_probe as _;
 
@@ -15,6 +17,9 @@ use stm32f1xx_hal::pac;
 use stm32f1xx_hal::rcc::{self, RccExt};
 use stm32f1xx_hal::timer::{SysDelay, SysTimerExt};
 
+/// 按键扫描周期，单位：ms
+const KEY_TICK_MS: u16 = 10;
+
 #[entry]
 fn main() -> ! {
     // 初始化外设
@@ -26,19 +31,30 @@ fn main() -> ! {
     // LED
     let (mut led1, mut led2) = init_led(gpioa);
     // 按键
-    let (mut key1, mut key11) = init_key(gpiob);
+    let mut keys = init_key(gpiob);
 
-    // 等待计时器触发更新并更改LED的状态
     loop {
-        // 获取按键的值
-        let key_num = get_key_num(&mut key1, &mut key11, &mut delay);
-        // 根据不同的按键点亮不同的灯
-        if key_num == 1 {
-            led1_turn(&mut led1);
-        }
-        if key_num == 11 {
-            led2_turn(&mut led2);
+        // 周期扫描按键，消抖、长按及连发均由状态机计时，不会阻塞主循环
+        keys.tick();
+        while let Some(event) = keys.poll_event() {
+            match (event.key, event.kind) {
+                // 单击翻转对应的灯，长按后继续按住时连发翻转，灯闪烁
+                (0, EventKind::Click | EventKind::Repeat) => led1_turn(&mut led1),
+                (1, EventKind::Click | EventKind::Repeat) => led2_turn(&mut led2),
+                // 双击同时翻转两个灯
+                (_, EventKind::DoubleClick) => {
+                    led1_turn(&mut led1);
+                    led2_turn(&mut led2);
+                }
+                // 长按先熄灭两个灯
+                (_, EventKind::LongPress) => {
+                    led1.set_high();
+                    led2.set_high();
+                }
+                _ => {}
+            }
         }
+        delay.delay_ms(KEY_TICK_MS);
     }
 }
 
@@ -97,47 +113,15 @@ fn init_led(
 /// 按键
 /// 将引脚配置为作为上拉输入引脚操作
 /// 输入模式中速度是没有用的, 无需配置
-fn init_key(
-    mut gpiob: gpiob::Parts,
-) -> (
-    gpio::PB1<gpio::Input<gpio::PullUp>>,
-    gpio::PB11<gpio::Input<gpio::PullUp>>,
-) {
+fn init_key(mut gpiob: gpiob::Parts) -> Keys<gpio::ErasedPin<gpio::Input<gpio::PullUp>>, 2> {
     let key1 = gpiob.pb1.into_pull_up_input(&mut gpiob.crl);
     let key11 = gpiob.pb11.into_pull_up_input(&mut gpiob.crh);
-    (key1, key11)
-}
-
-/// 获取按键的值
-fn get_key_num(
-    key1: &mut gpio::Pin<'B', 1, gpio::Input<gpio::PullUp>>,
-    key11: &mut gpio::Pin<'B', 11, gpio::Input<gpio::PullUp>>,
-    delay: &mut SysDelay,
-) -> i32 {
-    let mut key_num = 0;
-
-    if key1.is_low() {
-        // 按键按下抖动
-        delay.delay_ms(20_u16);
-        // 按着不动, 松手后跳出循环
-        while key1.is_low() {}
-        // 按键松开抖动
-        delay.delay_ms(20_u16);
-
-        key_num = 1;
-    }
-
-    if key11.is_low() {
-        // 按键按下抖动
-        delay.delay_ms(20_u16);
-        // 按着不动, 松手后跳出循环
-        while key11.is_low() {}
-        // 按键松开抖动
-        delay.delay_ms(20_u16);
-
-        key_num = 11;
-    }
-    key_num
+    Keys::new(
+        [key1.erase(), key11.erase()],
+        true,
+        KEY_TICK_MS,
+        KeyTiming::default(),
+    )
 }
 
 /// led1 端口电平翻转
@@ -2,7 +2,9 @@
 #![no_main]
 #![allow(clippy::empty_loop)]
 
-use hardware::mpu6050::mpu6050_hal;
+use hardware::i2c::I2c2Recovery;
+use hardware::mpu6050::conf::{Mpu6050Config, SlaveAddr};
+use hardware::mpu6050::Mpu6050;
 use hardware::oled;
 
 use defmt::println;
@@ -11,7 +13,9 @@ use panic_probe as _;
 
 use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs;
 use cortex_m_rt::entry;
+use stm32f1xx_hal::i2c::{self, BlockingI2c};
 use stm32f1xx_hal::pac;
+use stm32f1xx_hal::prelude::_fugit_RateExtU32;
 use stm32f1xx_hal::prelude::{_stm32_hal_flash_FlashExt, _stm32_hal_gpio_GpioExt};
 use stm32f1xx_hal::rcc::RccExt;
 use stm32f1xx_hal::timer::SysTimerExt;
@@ -42,18 +46,33 @@ fn main() -> ! {
     // MPU6050 初始化
     let mpu_scl = gpiob.pb10.into_alternate_open_drain(&mut gpiob.crh);
     let mpu_sda = gpiob.pb11.into_alternate_open_drain(&mut gpiob.crh);
-    let mut mpu = mpu6050_hal::Mpu6050::new((mpu_scl, mpu_sda), i2c2, clocks);
+    // Mode 不可复制，总线和恢复各构造一份
+    let frequency = 10.kHz();
+    let mode = i2c::Mode::standard(frequency);
+    let i2c = BlockingI2c::i2c2(i2c2, (mpu_scl, mpu_sda), mode, clocks, 1000, 10, 1000, 1000);
+    // 通信出错后自动恢复总线
+    let recovery = I2c2Recovery::new(i2c::Mode::standard(frequency), clocks);
+    let mut mpu = Mpu6050::with_recovery(i2c, SlaveAddr::Ad0Low, recovery);
+    mpu.init(Mpu6050Config::default()).unwrap();
 
-    let id = mpu.get_id();
+    let id = mpu.get_id().unwrap();
     oled.show_string(1, 1, "ID:");
     oled.show_hex_num(1, 4, id as u32, 2);
 
     // 循环读取加速度和角速度数据
     loop {
-        let data = mpu.get_data();
+        let data = match mpu.get_data() {
+            Ok(data) => data,
+            Err(e) => {
+                println!("read mpu6050 failed: {:?}", defmt::Debug2Format(&e));
+                delay.delay_ms(1000_u32);
+                continue;
+            }
+        };
         // 打印读取到的数据
         println!("Accel: ({}, {}, {})", data.acc_x, data.acc_y, data.acc_z);
         println!("Gyro: ({}, {}, {})", data.gyro_x, data.gyro_y, data.gyro_z);
+        println!("Temp: {}", data.temperature());
 
         oled.show_signed_num(2, 1, data.acc_x as i32, 5);
         oled.show_signed_num(3, 1, data.acc_y as i32, 5);
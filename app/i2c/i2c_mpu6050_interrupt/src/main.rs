@@ -0,0 +1,158 @@
+#![no_std]
+#![no_main]
+#![allow(clippy::empty_loop)]
+
+use core::mem::MaybeUninit;
+use core::ptr::addr_of_mut;
+
+use hardware::i2c::I2c2Recovery;
+use hardware::mpu6050::conf::{
+    AccelHpf, DlpfBandwidth, FifoSources, InterruptPinConfig, Interrupts, Mpu6050Config, SlaveAddr,
+};
+use hardware::mpu6050::{AccelGyroData, Mpu6050};
+
+use defmt::println;
+use defmt_rtt as _;
+use panic_probe as _;
+
+use cortex_m::peripheral::NVIC;
+use cortex_m_rt::entry;
+use stm32f1xx_hal::gpio::{gpiob, Edge, ExtiPin, Floating, Input};
+use stm32f1xx_hal::i2c::{self, BlockingI2c};
+use stm32f1xx_hal::pac::{self, interrupt};
+use stm32f1xx_hal::prelude::{
+    _fugit_RateExtU32, _stm32_hal_afio_AfioExt, _stm32_hal_flash_FlashExt, _stm32_hal_gpio_GpioExt,
+};
+use stm32f1xx_hal::rcc::RccExt;
+
+static mut MPU_INT: MaybeUninit<gpiob::PB5<Input<Floating>>> = MaybeUninit::uninit();
+
+// MPU6050 中断标志
+static mut MPU_INT_FLAG: bool = false;
+
+#[entry]
+fn main() -> ! {
+    // 获取对外设的访问对象
+    let dp = pac::Peripherals::take().unwrap();
+
+    let mut flash = dp.FLASH.constrain();
+    let rcc = dp.RCC.constrain();
+    let mut afio = dp.AFIO.constrain();
+    let mut exti = dp.EXTI;
+    let i2c2 = dp.I2C2;
+
+    let mut gpiob = dp.GPIOB.split();
+
+    // 冻结系统中所有时钟的配置，并将冻结的频率存储在时钟中
+    let clocks = rcc.cfgr.freeze(&mut flash.acr);
+
+    // MPU6050 初始化
+    let mpu_scl = gpiob.pb10.into_alternate_open_drain(&mut gpiob.crh);
+    let mpu_sda = gpiob.pb11.into_alternate_open_drain(&mut gpiob.crh);
+    // Mode 不可复制，总线和恢复各构造一份
+    let frequency = 100.kHz();
+    let mode = i2c::Mode::standard(frequency);
+    let i2c = BlockingI2c::i2c2(i2c2, (mpu_scl, mpu_sda), mode, clocks, 1000, 10, 1000, 1000);
+    let recovery = I2c2Recovery::new(i2c::Mode::standard(frequency), clocks);
+    let mut mpu = Mpu6050::with_recovery(i2c, SlaveAddr::Ad0Low, recovery);
+
+    // 50Hz 采样率，运动检测需要开启高通滤波器
+    mpu.init(Mpu6050Config {
+        sample_rate_div: 19,
+        dlpf: DlpfBandwidth::Hz44,
+        accel_hpf: AccelHpf::Hz5,
+        ..Mpu6050Config::default()
+    })
+    .unwrap();
+    // 加速度变化超过 100mg 并持续 2ms 判定为运动
+    mpu.set_motion_detection(100, 2).unwrap();
+    mpu.enable_fifo(FifoSources::all()).unwrap();
+    mpu.set_interrupt_pin(InterruptPinConfig {
+        latch: true,
+        ..InterruptPinConfig::default()
+    })
+    .unwrap();
+    mpu.enable_interrupts(Interrupts {
+        data_ready: true,
+        motion: true,
+        ..Interrupts::default()
+    })
+    .unwrap();
+
+    // 配置 INT 引脚为外部中断
+    let mut mpu_int = gpiob.pb5.into_floating_input(&mut gpiob.crl);
+    mpu_int.make_interrupt_source(&mut afio);
+    mpu_int.trigger_on_edge(&mut exti, Edge::Rising);
+    mpu_int.enable_interrupt(&mut exti);
+    unsafe {
+        (*addr_of_mut!(MPU_INT)).write(mpu_int);
+        NVIC::unmask(interrupt::EXTI9_5);
+    }
+
+    let mut samples: [AccelGyroData; 16] = Default::default();
+    loop {
+        // INT 电平锁存且只在上升沿触发，必须先检查标志再休眠，否则处理期间的中断会丢失。
+        // 在临界区内检查并休眠，屏蔽期间挂起的中断同样能唤醒 WFI
+        let ready = cortex_m::interrupt::free(|_| {
+            let ready = take_int_flag();
+            if !ready {
+                cortex_m::asm::wfi();
+            }
+            ready
+        });
+        if !ready {
+            continue;
+        }
+
+        // 读取状态后中断电平被清除
+        let status = match mpu.interrupt_status() {
+            Ok(status) => status,
+            Err(e) => {
+                println!("read mpu6050 failed: {:?}", defmt::Debug2Format(&e));
+                continue;
+            }
+        };
+        if status.motion {
+            println!("motion detected");
+        }
+        if status.fifo_overflow {
+            mpu.reset_fifo().ok();
+            continue;
+        }
+
+        // 累积 8 组数据后批量读取
+        let fifo_count = mpu.fifo_count().unwrap_or_default() as usize;
+        if fifo_count < 8 * FifoSources::all().packet_len() {
+            continue;
+        }
+        let count = mpu.read_fifo(&mut samples).unwrap_or_default();
+        for data in samples[..count].iter() {
+            println!(
+                "Accel: ({}, {}, {}) Gyro: ({}, {}, {})",
+                data.acc_x, data.acc_y, data.acc_z, data.gyro_x, data.gyro_y, data.gyro_z
+            );
+        }
+    }
+}
+
+/// MPU6050 INT 引脚中断
+#[interrupt]
+fn EXTI9_5() {
+    let mpu_int = unsafe { (*addr_of_mut!(MPU_INT)).assume_init_mut() };
+
+    if mpu_int.check_interrupt() {
+        unsafe { MPU_INT_FLAG = true }
+
+        // if we don't clear this bit, the ISR would trigger indefinitely
+        mpu_int.clear_interrupt_pending_bit();
+    }
+}
+
+/// 获取并清除中断标志
+fn take_int_flag() -> bool {
+    cortex_m::interrupt::free(|_| unsafe {
+        let flag = MPU_INT_FLAG;
+        MPU_INT_FLAG = false;
+        flag
+    })
+}
@@ -2,19 +2,23 @@
 #![no_main]
 #![allow(clippy::empty_loop)]
 
-use hardware::{mpu6050::mpu6050_reg, oled};
+use hardware::i2c::{SoftI2c, SoftRecovery};
+use hardware::mpu6050::conf::{Mpu6050Config, SlaveAddr};
+use hardware::mpu6050::Mpu6050;
+use hardware::oled;
 
 use defmt::println;
 use defmt_rtt as _;
 use panic_probe as _;
 
+use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs;
 use cortex_m_rt::entry;
 use stm32f1xx_hal::gpio;
 use stm32f1xx_hal::gpio::OutputSpeed;
 use stm32f1xx_hal::pac;
 use stm32f1xx_hal::prelude::{_stm32_hal_flash_FlashExt, _stm32_hal_gpio_GpioExt};
 use stm32f1xx_hal::rcc::RccExt;
-use stm32f1xx_hal::timer::SysTimerExt;
+use stm32f1xx_hal::timer::{SysTimerExt, TimerExt};
 
 #[entry]
 fn main() -> ! {
@@ -42,19 +46,29 @@ fn main() -> ! {
     let mut mpu_sda = gpiob.pb11.into_open_drain_output(&mut gpiob.crh);
     mpu_scl.set_speed(&mut gpiob.crh, gpio::IOPinSpeed::Mhz50);
     mpu_sda.set_speed(&mut gpiob.crh, gpio::IOPinSpeed::Mhz50);
-    let mut mpu = mpu6050_reg::Mpu6050::new(&mut mpu_scl, &mut mpu_sda, &mut delay);
-    mpu.init_mpu6050();
+    // 软件 I2C 使用 TIM2 延时，SysDelay 可继续用于主循环
+    let i2c = SoftI2c::new(mpu_scl, mpu_sda, dp.TIM2.delay_us(&clocks));
+    let mut mpu = Mpu6050::with_recovery(i2c, SlaveAddr::Ad0Low, SoftRecovery);
+    mpu.init(Mpu6050Config::default()).unwrap();
 
-    let id = mpu.get_id();
+    let id = mpu.get_id().unwrap();
     oled.show_string(1, 1, "ID:");
     oled.show_hex_num(1, 4, id as u32, 2);
 
     loop {
-        let data = mpu.get_data();
+        let data = match mpu.get_data() {
+            Ok(data) => data,
+            Err(e) => {
+                println!("read mpu6050 failed: {:?}", defmt::Debug2Format(&e));
+                delay.delay_ms(1000_u32);
+                continue;
+            }
+        };
 
         // 打印读取到的数据
         println!("Accel: ({}, {}, {})", data.acc_x, data.acc_y, data.acc_z);
         println!("Gyro: ({}, {}, {})", data.gyro_x, data.gyro_y, data.gyro_z);
+        println!("Temp: {}", data.temperature());
 
         oled.show_signed_num(2, 1, data.acc_x as i32, 5);
         oled.show_signed_num(3, 1, data.acc_y as i32, 5);
@@ -0,0 +1,127 @@
+#![no_std]
+#![no_main]
+#![allow(clippy::empty_loop)]
+
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use hardware::oled;
+use hardware::soft_timer::{Mode, SoftTimers, TimerId};
+
+use defmt::println;
+use defmt_rtt as _;
+use panic_probe as _;
+
+use cortex_m::asm::wfi;
+use cortex_m::interrupt::Mutex;
+use cortex_m::peripheral::NVIC;
+use cortex_m_rt::entry;
+use stm32f1xx_hal::pac::{self, interrupt, TIM2};
+use stm32f1xx_hal::prelude::{_fugit_ExtU32, _stm32_hal_flash_FlashExt, _stm32_hal_gpio_GpioExt};
+use stm32f1xx_hal::rcc::RccExt;
+use stm32f1xx_hal::timer::{CounterMs, Event, TimerExt};
+
+/// 硬件定时器的中断周期，单位：ms
+const TICK_MS: u32 = 1;
+
+static G_TIM: Mutex<RefCell<Option<CounterMs<TIM2>>>> = Mutex::new(RefCell::new(None));
+static G_TIMERS: Mutex<RefCell<SoftTimers<4>>> = Mutex::new(RefCell::new(SoftTimers::new()));
+
+/// 秒计数
+static SECONDS: AtomicU32 = AtomicU32::new(0);
+
+#[entry]
+fn main() -> ! {
+    // 获取对外设的访问对象
+    let cp = cortex_m::Peripherals::take().unwrap();
+    let dp = pac::Peripherals::take().unwrap();
+
+    let mut flash = dp.FLASH.constrain();
+    let rcc = dp.RCC.constrain();
+    let mut nvic = cp.NVIC;
+
+    let mut gpiob = dp.GPIOB.split();
+    let mut gpioc = dp.GPIOC.split();
+
+    // 冻结系统中所有时钟的配置，并将冻结的频率存储在时钟中
+    let clocks = rcc.cfgr.freeze(&mut flash.acr);
+
+    let mut led = gpioc.pc13.into_push_pull_output(&mut gpioc.crh);
+
+    // 初始化 OLED 显示屏
+    println!("load oled...");
+    let mut oled = oled::simple::init_oled(gpiob.pb8, gpiob.pb9, &mut gpiob.crh);
+
+    // 软件定时器
+    let (blink, fast, refresh) = cortex_m::interrupt::free(|cs| {
+        let mut timers = G_TIMERS.borrow(cs).borrow_mut();
+        let blink = timers.add("blink", Mode::Periodic, 500).unwrap();
+        let seconds = timers
+            .add_with_callback("seconds", Mode::Periodic, 1000, on_second)
+            .unwrap();
+        let fast = timers.add("fast", Mode::OneShot, 5000).unwrap();
+        let refresh = timers.add("refresh", Mode::Periodic, 100).unwrap();
+        for id in [blink, seconds, fast, refresh] {
+            timers.start(id).unwrap();
+        }
+        (blink, fast, refresh)
+    });
+
+    // 一个硬件定时器驱动所有软件定时器
+    println!("load timer...");
+    let mut timer = dp.TIM2.counter_ms(&clocks);
+    timer.start(TICK_MS.millis()).unwrap();
+    timer.listen(Event::Update);
+    cortex_m::interrupt::free(|cs| G_TIM.borrow(cs).replace(Some(timer)));
+
+    unsafe {
+        nvic.set_priority(interrupt::TIM2, 2);
+        NVIC::unmask(interrupt::TIM2);
+    }
+
+    oled.show_string(1, 1, "Sec:");
+    oled.show_string(2, 1, "Ms:");
+    println!("loop ...");
+    loop {
+        let (toggle, speed_up, redraw, now_ms) = cortex_m::interrupt::free(|cs| {
+            let mut timers = G_TIMERS.borrow(cs).borrow_mut();
+            let toggle = timers.take_event(blink);
+            let speed_up = timers.take_event(fast);
+            let redraw = timers.take_event(refresh);
+            if speed_up {
+                // 单次定时器到期后加快闪烁
+                timers.set_period(blink, 100).unwrap();
+            }
+            (toggle, speed_up, redraw, timers.now_ms())
+        });
+
+        if toggle {
+            led.toggle();
+        }
+        if speed_up {
+            println!("speed up");
+        }
+        if redraw {
+            oled.show_num(1, 5, SECONDS.load(Ordering::Relaxed), 5);
+            oled.show_num(2, 4, now_ms as u32, 8);
+        } else {
+            wfi();
+        }
+    }
+}
+
+/// 秒计数回调，在定时中断中执行
+fn on_second(_id: TimerId) {
+    SECONDS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// 推进软件定时器
+#[interrupt]
+fn TIM2() {
+    cortex_m::interrupt::free(|cs| {
+        if let Some(tim) = G_TIM.borrow(cs).borrow_mut().as_mut() {
+            tim.wait().unwrap();
+        }
+        G_TIMERS.borrow(cs).borrow_mut().tick(TICK_MS);
+    });
+}
@@ -3,23 +3,19 @@
 #![allow(clippy::empty_loop)]
 
 use hardware::oled;
+use hardware::syst::{Deadline, Duration, SysMono};
 
 use defmt::println;
 use defmt_rtt as _;
 use panic_probe as _;
 
-use cortex_m::peripheral::syst::SystClkSource;
-use cortex_m::peripheral::NVIC;
+use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs;
 use cortex_m_rt::{entry, exception};
-use stm32f1xx_hal::pac::{self, interrupt};
+use stm32f1xx_hal::pac;
 use stm32f1xx_hal::prelude::{
     _fugit_RateExtU32, _stm32_hal_flash_FlashExt, _stm32_hal_gpio_GpioExt,
 };
 use stm32f1xx_hal::rcc::RccExt;
-use stm32f1xx_hal::timer::{SysCounterHz, SysEvent, Timer};
-
-// 计数器
-static mut NUM: u32 = 0;
 
 #[entry]
 fn main() -> ! {
@@ -29,8 +25,6 @@ fn main() -> ! {
 
     let mut flash = dp.FLASH.constrain();
     let rcc = dp.RCC.constrain();
-    let mut syst = cp.SYST;
-    let mut nvic = cp.NVIC;
 
     let mut gpiob = dp.GPIOB.split();
 
@@ -42,48 +36,43 @@ fn main() -> ! {
         // 指定的频率必须是外部振荡器的频率
         // .use_hse(8.MHz())
         // 设置系统时钟
-        // .sysclk(72.MHz())
-        // .pclk1(36.MHz())
+        .sysclk(72.MHz())
+        .pclk1(36.MHz())
         .freeze(&mut flash.acr);
 
-    // configures the system timer to trigger a SysTick exception every second
-    syst.set_clock_source(SystClkSource::External);
-    // this is configured for the LM3S6965 which has a default CPU clock of 12 MHz
-    syst.set_reload(12_000_000);
-    syst.clear_current();
-    // syst.enable_counter();
-    syst.enable_interrupt();
-
-    let mut timer: SysCounterHz = Timer::syst_external(syst, &clocks).counter_hz();
-    timer.start(1.Hz()).unwrap();
-    timer.listen(SysEvent::Update);
-
-    unsafe {
-        // Enable interruptions
-        NVIC::unmask(interrupt::TIM2);
-        // 设置中断的优先级
-        nvic.set_priority(interrupt::TIM2, 2);
-        // NVIC::unpend(interrupt::TIM2);
-    }
+    // 系统定时器每 1ms 产生一次中断，作为单调时钟
+    let _mono = SysMono::new(cp.SYST, &clocks);
+    // SysTick 已被占用，使用基于单调时钟的延时
+    let mut delay = SysMono::delay();
 
     // 初始化 OLED 显示屏
     println!("load oled...");
     let mut oled = oled::simple::init_oled(gpiob.pb8, gpiob.pb9, &mut gpiob.crh);
 
     oled.show_string(1, 1, "Num:");
+    oled.show_string(2, 1, "Ms:");
+    oled.show_string(3, 1, "Us:");
+
+    // 每秒计数一次，不受刷新屏幕耗时的影响
+    let mut num = 0_u32;
+    let mut deadline = Deadline::after(Duration::secs(1));
     loop {
-        oled.show_num(1, 5, get_num(), 5);
+        if deadline.is_expired() {
+            deadline.restart(Duration::secs(1));
+            num += 1;
+        }
+
+        let start = SysMono::now();
+        oled.show_num(1, 5, num, 5);
+        oled.show_num(2, 4, SysMono::millis() as u32, 10);
+        let elapsed = SysMono::elapsed(start);
+        oled.show_num(3, 4, elapsed.to_micros() as u32, 10);
+
+        delay.delay_ms(10_u32);
     }
 }
 
 #[exception]
 fn SysTick() {
-    unsafe {
-        NUM += 1;
-    }
-}
-
-/// 获取计数
-fn get_num() -> u32 {
-    unsafe { NUM }
+    SysMono::on_tick();
 }
@@ -0,0 +1,118 @@
+#![no_std]
+#![no_main]
+#![allow(clippy::empty_loop)]
+
+use core::cell::RefCell;
+
+use hardware::buzzer::{PassiveBuzzer, Player};
+
+use defmt::println;
+use defmt_rtt as _;
+use panic_probe as _;
+
+use cortex_m::interrupt::Mutex;
+use cortex_m::peripheral::NVIC;
+use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs;
+use cortex_m_rt::entry;
+use stm32f1xx_hal::gpio::{Alternate, Pin};
+use stm32f1xx_hal::pac::{self, interrupt, TIM2, TIM3};
+use stm32f1xx_hal::prelude::{
+    _fugit_ExtU32, _fugit_RateExtU32, _stm32_hal_afio_AfioExt, _stm32_hal_flash_FlashExt,
+    _stm32_hal_gpio_GpioExt,
+};
+use stm32f1xx_hal::rcc::RccExt;
+use stm32f1xx_hal::timer::{
+    Ch, Channel, CounterMs, Event, PwmExt, PwmHz, SysTimerExt, Tim2NoRemap, TimerExt,
+};
+
+type TBuzzer = PassiveBuzzer<PwmHz<TIM2, Tim2NoRemap, Ch<0>, Pin<'A', 0, Alternate>>>;
+
+/// 播放进度的更新周期，单位：ms
+const TICK_MS: u32 = 10;
+
+/// 铃声
+const MELODY: &str = "Tetris:d=4,o=5,b=160:e6,8b,8c6,8d6,16e6,16d6,8c6,8b,a,8a,8c6,e6,8d6,8c6,b,8b,8c6,d6,e6,c6,a,2a";
+
+static G_TIM: Mutex<RefCell<Option<CounterMs<TIM3>>>> = Mutex::new(RefCell::new(None));
+static G_PLAYER: Mutex<RefCell<Option<Player<TBuzzer, 64>>>> = Mutex::new(RefCell::new(None));
+
+#[entry]
+fn main() -> ! {
+    // 获取对外设的访问对象
+    let cp = cortex_m::Peripherals::take().unwrap();
+    let dp = pac::Peripherals::take().unwrap();
+
+    let mut flash = dp.FLASH.constrain();
+    let rcc = dp.RCC.constrain();
+    let mut afio = dp.AFIO.constrain();
+    let mut nvic = cp.NVIC;
+
+    let mut gpioa = dp.GPIOA.split();
+    let mut gpioc = dp.GPIOC.split();
+
+    // 冻结系统中所有时钟的配置，并将冻结的频率存储在时钟中
+    let clocks = rcc.cfgr.freeze(&mut flash.acr);
+
+    // 具有自定义精度的阻塞延迟函数
+    let mut delay = cp.SYST.delay(&clocks);
+
+    let mut led = gpioc.pc13.into_push_pull_output(&mut gpioc.crh);
+
+    // 无源蜂鸣器
+    println!("load buzzer...");
+    let pa0 = gpioa.pa0.into_alternate_push_pull(&mut gpioa.crl);
+    let pwm = dp
+        .TIM2
+        .pwm_hz::<Tim2NoRemap, _, _>(pa0, &mut afio.mapr, 1.kHz(), &clocks);
+    let mut player = Player::new(PassiveBuzzer::new(pwm, Channel::C1));
+    player.play_rtttl(MELODY).unwrap();
+
+    // 播放定时器
+    println!("load timer...");
+    let mut timer = dp.TIM3.counter_ms(&clocks);
+    timer.start(TICK_MS.millis()).unwrap();
+    timer.listen(Event::Update);
+
+    // 移动到全局存储中
+    cortex_m::interrupt::free(|cs| {
+        G_TIM.borrow(cs).replace(Some(timer));
+        G_PLAYER.borrow(cs).replace(Some(player));
+    });
+
+    unsafe {
+        nvic.set_priority(interrupt::TIM3, 2);
+        NVIC::unmask(interrupt::TIM3);
+    }
+
+    println!("loop ...");
+    loop {
+        // 播放期间主循环照常运行
+        led.toggle();
+        delay.delay_ms(200_u16);
+
+        // 播放结束后停顿一秒重新播放
+        let playing = cortex_m::interrupt::free(|cs| {
+            G_PLAYER.borrow(cs).borrow().as_ref().unwrap().is_playing()
+        });
+        if !playing {
+            delay.delay_ms(1000_u16);
+            cortex_m::interrupt::free(|cs| {
+                let mut player = G_PLAYER.borrow(cs).borrow_mut();
+                player.as_mut().unwrap().play_rtttl(MELODY).unwrap();
+            });
+        }
+    }
+}
+
+/// 推进播放进度
+#[interrupt]
+fn TIM3() {
+    cortex_m::interrupt::free(|cs| {
+        if let Some(tim) = G_TIM.borrow(cs).borrow_mut().as_mut() {
+            tim.wait().unwrap();
+        }
+        if let Some(player) = G_PLAYER.borrow(cs).borrow_mut().as_mut() {
+            player.tick(TICK_MS);
+        }
+    });
+}
@@ -2,6 +2,7 @@
 #![no_main]
 #![allow(clippy::empty_loop)]
 
+use hardware::motor::{Motor, DUTY_MAX};
 use hardware::oled;
 
 use defmt::println;
@@ -10,14 +11,14 @@ use panic_probe as _;
 
 use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs;
 use cortex_m_rt::entry;
-use stm32f1xx_hal::gpio::{self, Alternate, IOPinSpeed, OutputSpeed};
-use stm32f1xx_hal::pac::{self, TIM2};
+use stm32f1xx_hal::gpio::{self, IOPinSpeed, OutputSpeed};
+use stm32f1xx_hal::pac;
 use stm32f1xx_hal::prelude::{
     _fugit_RateExtU32, _stm32_hal_afio_AfioExt, _stm32_hal_flash_FlashExt, _stm32_hal_gpio_GpioExt,
 };
 use stm32f1xx_hal::rcc::RccExt;
-use stm32f1xx_hal::timer::{Ch, PwmHz, SysDelay, SysTimerExt};
 use stm32f1xx_hal::timer::{Channel, PwmExt, Tim2NoRemap};
+use stm32f1xx_hal::timer::{SysDelay, SysTimerExt};
 
 #[entry]
 fn main() -> ! {
@@ -79,6 +80,9 @@ fn main() -> ! {
     let x = pwm.get_period();
     println!("period={:?} Hz", x.raw());
 
+    // 方向引脚与 PWM 通道组成电机驱动
+    let mut motor = Motor::new(ain1, ain2, pwm.split());
+
     // 电机有足够的时间启动和停止
     // 防止电机无法达到稳定状态，或者因为速度变化太快而损坏电机。
     delay.delay_ms(1000_u32);
@@ -94,7 +98,8 @@ fn main() -> ! {
                 speed = -100;
             }
         }
-        set_speed(&mut ain1, &mut ain2, &mut pwm, speed);
+        // 速度 -100~100 换算为占空比
+        motor.set_duty((speed * DUTY_MAX as i32 / 100) as i16);
         oled.show_signed_num(1, 7, speed, 3);
     }
 }
@@ -118,33 +123,3 @@ fn get_key_num(
     }
     key_num
 }
-
-/// 设置直流电机速度
-/// 设置电机的速度和方向，speed为-100到100之间的整数，负数表示反转，正数表示正转，绝对值越大速度越快
-fn set_speed(
-    ain1: &mut gpio::Pin<'A', 4, gpio::Output>,
-    ain2: &mut gpio::Pin<'A', 5, gpio::Output>,
-    pwm: &mut PwmHz<TIM2, Tim2NoRemap, Ch<2>, gpio::Pin<'A', 2, Alternate>>,
-    speed: i32,
-) {
-    let max_duty = pwm.get_max_duty();
-    if speed >= 0 {
-        // 设置正转方向
-        ain1.set_high();
-        ain2.set_low();
-        // 设置占空比
-        pwm.set_duty(
-            Channel::C3,
-            (max_duty as f32 * (speed as f32 / 100.0)) as u16,
-        );
-    } else {
-        // 设置反转方向
-        ain1.set_low();
-        ain2.set_high();
-        // 设置占空比
-        pwm.set_duty(
-            Channel::C3,
-            (max_duty as f32 * (-speed as f32 / 100.0)) as u16,
-        );
-    }
-}
@@ -3,6 +3,7 @@
 #![allow(clippy::empty_loop)]
 
 use hardware::oled;
+use hardware::servo::{ServoConfig, Servos};
 
 use defmt::println;
 use defmt_rtt as _;
@@ -76,6 +77,9 @@ fn main() -> ! {
     let max_duty = pwm.get_max_duty();
     println!("max_duty={:?}", max_duty);
 
+    // 0.5ms~2.5ms 对应 0~180 度，占空比由定时器实际周期换算
+    let mut servos = Servos::new(pwm, [(Channel::C2, ServoConfig::default())]);
+
     let mut angle = 0.0;
     oled.show_string(1, 1, "Angle:");
     oled.show_string(2, 1, "Duty:");
@@ -88,16 +92,10 @@ fn main() -> ! {
         if angle > 180.0 {
             angle = 0.0
         }
-        // 53333.0  20ms
-        // 1333.3   0.5ms  -90度
-        // 3999.9   1.5ms   0度
-        // 5333.3   2ms     45度
-        // 6666.6   2.5ms   90度
-        // 缩放: (6666.6-5333.3)/(90-45) = 29.6
-        let duty = (angle * 29.6 + 1333.3) as u16;
+        servos.set_angle(0, angle);
+        let duty = servos.duty(ServoConfig::default().pulse_us(angle));
         oled.show_num(1, 7, angle as u32, 5);
         oled.show_num(2, 6, duty.into(), 5);
-        pwm.set_duty(Channel::C2, duty);
     }
 }
 
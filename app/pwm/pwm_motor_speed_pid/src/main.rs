@@ -0,0 +1,225 @@
+#![no_std]
+#![no_main]
+#![allow(clippy::empty_loop)]
+
+use core::cell::RefCell;
+use core::fmt::Write;
+
+use hardware::encoder::{EncoderConfig, QeiEncoder};
+use hardware::motor::Motor;
+use hardware::oled;
+use hardware::pid::{PidGains, SpeedLoop, TuneCommand};
+use hardware::serial::LineBuffer;
+
+use defmt::println;
+use defmt_rtt as _;
+use panic_probe as _;
+
+use cortex_m::interrupt::Mutex;
+use cortex_m::peripheral::NVIC;
+use cortex_m_rt::entry;
+use heapless::String;
+use stm32f1xx_hal::gpio::{Input, Output, Pin, PullUp};
+use stm32f1xx_hal::pac::{self, interrupt, TIM2, TIM3, TIM4, USART1};
+use stm32f1xx_hal::prelude::{
+    _fugit_ExtU32, _fugit_RateExtU32, _stm32_hal_afio_AfioExt, _stm32_hal_flash_FlashExt,
+    _stm32_hal_gpio_GpioExt,
+};
+use stm32f1xx_hal::qei::{Qei, QeiOptions};
+use stm32f1xx_hal::rcc::RccExt;
+use stm32f1xx_hal::serial::{self, Rx, Serial, Tx};
+use stm32f1xx_hal::time::U32Ext;
+use stm32f1xx_hal::timer::{
+    Channel, CounterMs, Event, PwmChannel, PwmExt, Tim2NoRemap, Tim3NoRemap, Timer, TimerExt,
+};
+
+type TQei = Qei<TIM3, Tim3NoRemap, (Pin<'A', 6, Input<PullUp>>, Pin<'A', 7, Input<PullUp>>)>;
+type TSpeedLoop =
+    SpeedLoop<QeiEncoder<TQei>, Pin<'A', 4, Output>, Pin<'A', 5, Output>, PwmChannel<TIM2, 2>>;
+
+/// 控制周期，单位：ms
+const PERIOD_MS: u32 = 10;
+
+static G_TIM: Mutex<RefCell<Option<CounterMs<TIM4>>>> = Mutex::new(RefCell::new(None));
+static G_LOOP: Mutex<RefCell<Option<TSpeedLoop>>> = Mutex::new(RefCell::new(None));
+static G_RX: Mutex<RefCell<Option<Rx<USART1>>>> = Mutex::new(RefCell::new(None));
+static G_TX: Mutex<RefCell<Option<Tx<USART1>>>> = Mutex::new(RefCell::new(None));
+static G_LINE: Mutex<RefCell<Option<LineBuffer<32>>>> = Mutex::new(RefCell::new(None));
+
+#[entry]
+fn main() -> ! {
+    // 获取对外设的访问对象
+    let cp = cortex_m::Peripherals::take().unwrap();
+    let dp = pac::Peripherals::take().unwrap();
+
+    let mut flash = dp.FLASH.constrain();
+    let rcc = dp.RCC.constrain();
+    let mut afio = dp.AFIO.constrain();
+    let mut nvic = cp.NVIC;
+
+    let mut gpioa = dp.GPIOA.split();
+    let mut gpiob = dp.GPIOB.split();
+
+    // 冻结系统中所有时钟的配置，并将冻结的频率存储在时钟中
+    let clocks = rcc.cfgr.sysclk(72.MHz()).freeze(&mut flash.acr);
+
+    // 初始化 OLED 显示屏
+    println!("load oled...");
+    let mut oled = oled::simple::init_oled(gpiob.pb8, gpiob.pb9, &mut gpiob.crh);
+
+    // 电机
+    println!("load motor...");
+    let ain1 = gpioa.pa4.into_push_pull_output(&mut gpioa.crl);
+    let ain2 = gpioa.pa5.into_push_pull_output(&mut gpioa.crl);
+    let pwma = gpioa.pa2.into_alternate_push_pull(&mut gpioa.crl);
+    let mut pwm = dp
+        .TIM2
+        .pwm_hz::<Tim2NoRemap, _, _>(pwma, &mut afio.mapr, 10.kHz(), &clocks);
+    pwm.enable(Channel::C3);
+    let motor = Motor::new(ain1, ain2, pwm.split());
+
+    // 编码器
+    println!("load rotary encoder ...");
+    let pa6 = gpioa.pa6.into_pull_up_input(&mut gpioa.crl);
+    let pa7 = gpioa.pa7.into_pull_up_input(&mut gpioa.crl);
+    let qei = Timer::new(dp.TIM3, &clocks).qei((pa6, pa7), &mut afio.mapr, QeiOptions::default());
+    let config = EncoderConfig {
+        sample_period_ms: PERIOD_MS,
+        window: 5,
+        counts_per_rev: 1320,
+    };
+    let encoder = QeiEncoder::new(qei, config);
+
+    // 速度闭环
+    let gains = PidGains {
+        kp: 1.0,
+        ki: 10.0,
+        kd: 0.0,
+    };
+    let mut speed_loop = SpeedLoop::new(encoder, motor, gains, PERIOD_MS as f32 / 1000.0);
+    speed_loop.pid_mut().set_derivative_filter(0.2);
+
+    // 串口
+    println!("load serial...");
+    let tx = gpioa.pa9.into_alternate_push_pull(&mut gpioa.crh);
+    let rx = gpioa.pa10;
+    let (tx, mut rx) = Serial::new(
+        dp.USART1,
+        (tx, rx),
+        &mut afio.mapr,
+        serial::Config::default().baudrate(115200.bps()),
+        &clocks,
+    )
+    .split();
+    rx.listen();
+
+    // 控制定时器
+    println!("load timer...");
+    let mut timer = dp.TIM4.counter_ms(&clocks);
+    timer.start(PERIOD_MS.millis()).unwrap();
+    timer.listen(Event::Update);
+
+    // 移动到全局存储中
+    cortex_m::interrupt::free(|cs| {
+        G_TIM.borrow(cs).replace(Some(timer));
+        G_LOOP.borrow(cs).replace(Some(speed_loop));
+        G_RX.borrow(cs).replace(Some(rx));
+        G_TX.borrow(cs).replace(Some(tx));
+        G_LINE.borrow(cs).replace(Some(LineBuffer::new()));
+    });
+
+    unsafe {
+        // 控制周期优先于串口
+        nvic.set_priority(interrupt::TIM4, 1);
+        nvic.set_priority(interrupt::USART1, 2);
+        NVIC::unmask(interrupt::TIM4);
+        NVIC::unmask(interrupt::USART1);
+    }
+
+    oled.show_string(1, 1, "Tar:");
+    oled.show_string(2, 1, "RPM:");
+    oled.show_string(3, 1, "PWM:");
+    println!("loop ...");
+    loop {
+        let (target, rpm, duty) = cortex_m::interrupt::free(|cs| {
+            let speed_loop = G_LOOP.borrow(cs).borrow();
+            let speed_loop = speed_loop.as_ref().unwrap();
+            (speed_loop.target_rpm(), speed_loop.rpm(), speed_loop.duty())
+        });
+        oled.show_signed_num(1, 5, target as i32, 5);
+        oled.show_signed_num(2, 5, rpm as i32, 5);
+        oled.show_signed_num(3, 5, duty as i32, 5);
+    }
+}
+
+/// 执行一次速度闭环
+#[interrupt]
+fn TIM4() {
+    cortex_m::interrupt::free(|cs| {
+        if let Some(tim) = G_TIM.borrow(cs).borrow_mut().as_mut() {
+            tim.wait().unwrap();
+        }
+        if let Some(speed_loop) = G_LOOP.borrow(cs).borrow_mut().as_mut() {
+            speed_loop.update();
+        }
+    });
+}
+
+/// 接收调参指令
+#[interrupt]
+fn USART1() {
+    // 临界区内只解析指令并更新闭环参数，应答在退出后发送，阻塞发送期间控制周期仍可抢占
+    let reply = cortex_m::interrupt::free(|cs| {
+        let mut rx = G_RX.borrow(cs).borrow_mut();
+        let rx = rx.as_mut().unwrap();
+        let mut line = G_LINE.borrow(cs).borrow_mut();
+        let line = line.as_mut().unwrap();
+
+        // 溢出等错误时读取会清除错误标志，已接收的部分指令不完整，直接丢弃
+        let byte = match rx.read() {
+            Ok(byte) => byte,
+            Err(nb::Error::WouldBlock) => return None,
+            Err(nb::Error::Other(err)) => {
+                println!("usart1 error: {}", defmt::Debug2Format(&err));
+                line.clear();
+                return None;
+            }
+        };
+        if !line.push(byte) {
+            return None;
+        }
+
+        let mut speed_loop = G_LOOP.borrow(cs).borrow_mut();
+        let speed_loop = speed_loop.as_mut().unwrap();
+        let mut reply: String<64> = String::new();
+        match TuneCommand::parse(line.as_str()) {
+            Some(command) => {
+                speed_loop.apply(command);
+                let gains = speed_loop.gains();
+                write!(
+                    reply,
+                    "kp={} ki={} kd={} sp={}\r\n",
+                    gains.kp,
+                    gains.ki,
+                    gains.kd,
+                    speed_loop.target_rpm()
+                )
+                .ok();
+            }
+            None => {
+                reply.push_str("ERROR_COMMAND\r\n").ok();
+            }
+        }
+        line.clear();
+        Some(reply)
+    });
+
+    let Some(reply) = reply else {
+        return;
+    };
+    // 发送端只在本中断中使用，取出后发送再放回
+    if let Some(mut tx) = cortex_m::interrupt::free(|cs| G_TX.borrow(cs).take()) {
+        hardware::serial::send_string(&mut tx, reply.as_str());
+        cortex_m::interrupt::free(|cs| G_TX.borrow(cs).replace(Some(tx)));
+    }
+}
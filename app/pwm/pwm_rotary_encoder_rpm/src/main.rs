@@ -0,0 +1,120 @@
+#![no_std]
+#![no_main]
+#![allow(clippy::empty_loop)]
+
+use core::cell::RefCell;
+
+use hardware::encoder::{EncoderConfig, QeiEncoder};
+use hardware::oled;
+
+use defmt::println;
+use defmt_rtt as _;
+use panic_probe as _;
+
+use cortex_m::interrupt::Mutex;
+use cortex_m::peripheral::NVIC;
+use cortex_m_rt::entry;
+use stm32f1xx_hal::gpio::Input;
+use stm32f1xx_hal::gpio::Pin;
+use stm32f1xx_hal::gpio::PullUp;
+use stm32f1xx_hal::pac;
+use stm32f1xx_hal::pac::{interrupt, TIM2, TIM3};
+use stm32f1xx_hal::prelude::{
+    _fugit_ExtU32, _stm32_hal_afio_AfioExt, _stm32_hal_flash_FlashExt, _stm32_hal_gpio_GpioExt,
+};
+use stm32f1xx_hal::qei::Qei;
+use stm32f1xx_hal::qei::QeiOptions;
+use stm32f1xx_hal::rcc::RccExt;
+use stm32f1xx_hal::timer::CounterMs;
+use stm32f1xx_hal::timer::Event;
+use stm32f1xx_hal::timer::Tim3NoRemap;
+use stm32f1xx_hal::timer::Timer;
+use stm32f1xx_hal::timer::TimerExt;
+
+type TQei = Qei<TIM3, Tim3NoRemap, (Pin<'A', 6, Input<PullUp>>, Pin<'A', 7, Input<PullUp>>)>;
+
+/// 采样周期，单位：ms
+const SAMPLE_PERIOD_MS: u32 = 10;
+
+static G_TIM: Mutex<RefCell<Option<CounterMs<TIM2>>>> = Mutex::new(RefCell::new(None));
+static G_ENCODER: Mutex<RefCell<Option<QeiEncoder<TQei>>>> = Mutex::new(RefCell::new(None));
+
+#[entry]
+fn main() -> ! {
+    // 获取对外设的访问对象
+    let cp = cortex_m::Peripherals::take().unwrap();
+    let dp = pac::Peripherals::take().unwrap();
+
+    let mut flash = dp.FLASH.constrain();
+    let rcc = dp.RCC.constrain();
+    let mut afio = dp.AFIO.constrain();
+    let mut nvic = cp.NVIC;
+    let tim2 = dp.TIM2;
+    let tim3 = dp.TIM3;
+
+    let mut gpioa = dp.GPIOA.split();
+    let mut gpiob = dp.GPIOB.split();
+
+    // 冻结系统中所有时钟的配置，并将冻结的频率存储在时钟中
+    let clocks = rcc.cfgr.freeze(&mut flash.acr);
+
+    // 初始化 OLED 显示屏
+    println!("load oled...");
+    let mut oled = oled::simple::init_oled(gpiob.pb8, gpiob.pb9, &mut gpiob.crh);
+
+    // 旋转编码器
+    // 配置上拉输入
+    println!("load rotary encoder ...");
+    let pa6 = gpioa.pa6.into_pull_up_input(&mut gpioa.crl);
+    let pa7 = gpioa.pa7.into_pull_up_input(&mut gpioa.crl);
+    let qei = Timer::new(tim3, &clocks).qei((pa6, pa7), &mut afio.mapr, QeiOptions::default());
+    // 20 线编码器，编码器接口 4 倍频，100ms 测速窗口
+    let config = EncoderConfig {
+        sample_period_ms: SAMPLE_PERIOD_MS,
+        window: 10,
+        counts_per_rev: 80,
+    };
+    let encoder = QeiEncoder::new(qei, config);
+
+    // 采样定时器
+    println!("load timer...");
+    let mut timer = tim2.counter_ms(&clocks);
+    timer.start(SAMPLE_PERIOD_MS.millis()).unwrap();
+    timer.listen(Event::Update);
+
+    // 移动到全局存储中
+    cortex_m::interrupt::free(|cs| G_TIM.borrow(cs).replace(Some(timer)));
+    cortex_m::interrupt::free(|cs| G_ENCODER.borrow(cs).replace(Some(encoder)));
+
+    unsafe {
+        nvic.set_priority(interrupt::TIM2, 2);
+        NVIC::unmask(interrupt::TIM2);
+    }
+
+    oled.show_string(1, 1, "Pos:");
+    oled.show_string(2, 1, "RPM:");
+    println!("loop ...");
+    loop {
+        let (position, rpm) = cortex_m::interrupt::free(|cs| {
+            let encoder = G_ENCODER.borrow(cs).borrow();
+            let encoder = encoder.as_ref().unwrap();
+            (encoder.position(), encoder.rpm())
+        });
+        println!("position={:?} rpm={:?}", position, rpm);
+        oled.show_signed_num(1, 5, position as i32, 8);
+        oled.show_signed_num(2, 5, rpm as i32, 6);
+    }
+}
+
+/// 周期采样编码器
+#[interrupt]
+fn TIM2() {
+    cortex_m::interrupt::free(|cs| {
+        if let Some(tim) = G_TIM.borrow(cs).borrow_mut().as_mut() {
+            tim.wait().unwrap();
+        }
+        if let Some(encoder) = G_ENCODER.borrow(cs).borrow_mut().as_mut() {
+            encoder.sample();
+        }
+    });
+}
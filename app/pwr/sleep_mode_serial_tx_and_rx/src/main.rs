@@ -2,30 +2,39 @@
 #![no_main]
 #![allow(clippy::empty_loop)]
 
+use core::cell::{Cell, RefCell};
+
+use hardware::clock::{self, Preset};
 use hardware::oled;
+use hardware::power::{LowPower, WakeSource};
 
 use defmt::println;
 use defmt_rtt as _;
 use panic_probe as _;
 
-use cortex_m::asm::wfi;
+use cortex_m::interrupt::Mutex;
+use cortex_m::peripheral::NVIC;
 use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs;
 use cortex_m_rt::entry;
-use nb::block;
-use stm32f1xx_hal::pac;
+use stm32f1xx_hal::pac::{self, interrupt, USART1};
 use stm32f1xx_hal::prelude::_stm32_hal_afio_AfioExt;
 use stm32f1xx_hal::prelude::_stm32_hal_flash_FlashExt;
 use stm32f1xx_hal::prelude::_stm32_hal_gpio_GpioExt;
 use stm32f1xx_hal::rcc::RccExt;
 use stm32f1xx_hal::serial;
-use stm32f1xx_hal::serial::Serial;
+use stm32f1xx_hal::serial::{Rx, Serial};
 use stm32f1xx_hal::time::U32Ext;
 use stm32f1xx_hal::timer::SysTimerExt;
 
+/// 串口接收
+static G_RX: Mutex<RefCell<Option<Rx<USART1>>>> = Mutex::new(RefCell::new(None));
+/// 接收到的数据
+static G_RX_DATA: Mutex<Cell<Option<u8>>> = Mutex::new(Cell::new(None));
+
 #[entry]
 fn main() -> ! {
     // 获取对外设的访问对象
-    let cp = cortex_m::Peripherals::take().unwrap();
+    let mut cp = cortex_m::Peripherals::take().unwrap();
     let dp = pac::Peripherals::take().unwrap();
 
     let mut flash = dp.FLASH.constrain();
@@ -36,8 +45,8 @@ fn main() -> ! {
     let mut gpioa = dp.GPIOA.split();
     let mut gpiob = dp.GPIOB.split();
 
-    // 冻结系统中所有时钟的配置，并将冻结的频率存储在时钟中
-    let clocks = rcc.cfgr.freeze(&mut flash.acr);
+    // 使用 HSI 8MHz 降低功耗
+    let clocks = clock::freeze(rcc, &mut flash.acr, Preset::Hsi8).unwrap();
 
     // 具有自定义精度的阻塞延迟函数
     let mut delay = syst.delay(&clocks);
@@ -60,11 +69,19 @@ fn main() -> ! {
     )
     .split();
 
+    // 接收中断唤醒
+    rx.listen();
+    cortex_m::interrupt::free(|cs| G_RX.borrow(cs).replace(Some(rx)));
+    unsafe {
+        NVIC::unmask(interrupt::USART1);
+    }
+
+    let mut low_power = LowPower::new();
+
     oled.show_string(1, 1, "RxData:");
     println!("loop");
     loop {
-        if rx.is_rx_not_empty() {
-            let w = block!(rx.read()).unwrap();
+        if let Some(w) = cortex_m::interrupt::free(|cs| G_RX_DATA.borrow(cs).take()) {
             hardware::serial::send_byte(&mut tx, w);
             println!("received = {:#?}", w);
             oled.show_hex_num(1, 8, w as u32, 2);
@@ -75,7 +92,21 @@ fn main() -> ! {
         oled.show_string(2, 1, "       ");
         delay.delay_ms(100_u32);
 
-        // wfe(); // 事件唤醒
-        wfi(); // 中断唤醒（推荐）
+        // 进入睡眠模式，串口中断唤醒
+        let source = low_power
+            .sleep(&mut cp.SCB, &[WakeSource::Usart(pac::Interrupt::USART1)])
+            .unwrap();
+        println!("wake up: {:?}", source);
     }
 }
+
+#[interrupt]
+fn USART1() {
+    cortex_m::interrupt::free(|cs| {
+        if let Some(rx) = G_RX.borrow(cs).borrow_mut().as_mut() {
+            if let Ok(w) = rx.read() {
+                G_RX_DATA.borrow(cs).set(Some(w));
+            }
+        }
+    });
+}
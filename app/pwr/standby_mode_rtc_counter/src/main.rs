@@ -3,14 +3,13 @@
 #![allow(clippy::empty_loop)]
 
 use hardware::oled;
+use hardware::power::{self, Boot, LowPower, WakeSource};
 
 use defmt::println;
 use defmt_rtt as _;
 use panic_probe as _;
 
-use cortex_m::asm::wfi;
 use cortex_m_rt::entry;
-use nb::block;
 use stm32f1xx_hal::pac;
 use stm32f1xx_hal::prelude::_embedded_hal_blocking_delay_DelayMs;
 use stm32f1xx_hal::prelude::_stm32_hal_flash_FlashExt;
@@ -19,12 +18,19 @@ use stm32f1xx_hal::rcc::RccExt;
 use stm32f1xx_hal::rtc::Rtc;
 use stm32f1xx_hal::timer::SysTimerExt;
 
+/// 待机时长，单位：秒
+const STANDBY_SECONDS: u32 = 10;
+
 #[entry]
 fn main() -> ! {
     // 获取对外设的访问对象
     let cp = cortex_m::Peripherals::take().unwrap();
     let dp = pac::Peripherals::take().unwrap();
 
+    // 必须在 RTC 闹钟标志被清除前检查
+    let boot = power::check_boot();
+    println!("boot: {:?}", boot);
+
     let mut flash = dp.FLASH.constrain();
     let rcc = dp.RCC.constrain();
     let syst = cp.SYST;
@@ -46,46 +52,45 @@ fn main() -> ! {
     // 设置RTC
     // 启用对备份域的写入
     let mut backup_domain = rcc.bkp.constrain(dp.BKP, &mut pwr);
-    // 启动RTC
+    // 启动RTC，待机模式下 RTC 继续计数
     let mut rtc = Rtc::new(dp.RTC, &mut backup_domain);
-    let alr_value = 10;
-    loop {
-        rtc.set_time(0);
-        rtc.set_alarm(alr_value);
-        block!(rtc.wait_alarm()).unwrap();
+    if boot == Boot::Normal {
         rtc.set_time(0);
-
-        oled.show_string(1, 1, "CNT:");
-        oled.show_string(2, 1, "ALR:");
-        // 获取不到该状态
-        // oled.show_string( 3, 1, "ALRF:");
-
-        let count = rtc.current_time();
-        println!("current_time: {}", count);
-        oled.show_num(1, 6, count, 10);
-        oled.show_num(2, 6, alr_value, 10);
-
-        oled.show_string(4, 1, "running");
-        delay.delay_ms(100_u32);
-        oled.show_string(4, 1, "       ");
-        delay.delay_ms(100_u32);
-
-        oled.show_string(4, 9, "STANDBY");
-        delay.delay_ms(100_u32);
-        oled.show_string(4, 9, "       ");
-        delay.delay_ms(100_u32);
-
-        oled.clear();
-
-        // 当CPU进入深度睡眠时进入待机模式
-        // 清除唤醒标识
-        pwr.cr.modify(|_, w| w.cwuf().set_bit());
-        // 进入待机模式
-        pwr.cr.modify(|_, w| w.pdds().set_bit());
-        // 设置SCR寄存器中的SLEEPDEEP位
-        scb.set_sleepdeep();
-
-        // 请求低功耗模式
-        wfi();
     }
+
+    let count = rtc.current_time();
+    let alr_value = count + STANDBY_SECONDS;
+    rtc.set_alarm(alr_value);
+    println!("current_time: {}", count);
+
+    oled.show_string(1, 1, "CNT:");
+    oled.show_string(2, 1, "ALR:");
+    oled.show_string(3, 1, "WAKE:");
+    oled.show_num(1, 6, count, 10);
+    oled.show_num(2, 6, alr_value, 10);
+    oled.show_string(
+        3,
+        7,
+        match boot {
+            Boot::Normal => "POWER",
+            Boot::Standby(Some(WakeSource::RtcAlarm)) => "ALARM",
+            Boot::Standby(Some(WakeSource::WkupPin)) => "WKUP",
+            Boot::Standby(_) => "RESET",
+        },
+    );
+
+    oled.show_string(4, 1, "running");
+    delay.delay_ms(1000_u32);
+    oled.show_string(4, 1, "STANDBY");
+    delay.delay_ms(500_u32);
+    oled.clear();
+
+    // 进入待机模式，RTC 闹钟或 PA0 上升沿唤醒后从头开始执行
+    let mut low_power = LowPower::new();
+    let err = low_power
+        .standby(&mut scb, &[WakeSource::RtcAlarm, WakeSource::WkupPin])
+        .unwrap_err();
+    println!("standby failed: {:?}", err);
+
+    loop {}
 }
@@ -4,13 +4,14 @@
 
 use core::mem::MaybeUninit;
 
+use hardware::clock::{ClockControl, Preset};
 use hardware::oled;
+use hardware::power::{LowPower, WakeSource};
 
 use defmt::println;
 use defmt_rtt as _;
 use panic_probe as _;
 
-use cortex_m::asm::wfi;
 use cortex_m::peripheral::NVIC;
 use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs;
 use cortex_m_rt::entry;
@@ -19,7 +20,6 @@ use stm32f1xx_hal::pac::{self, interrupt};
 use stm32f1xx_hal::prelude::{
     _stm32_hal_afio_AfioExt, _stm32_hal_flash_FlashExt, _stm32_hal_gpio_GpioExt,
 };
-use stm32f1xx_hal::rcc::RccExt;
 use stm32f1xx_hal::timer::SysTimerExt;
 
 /// 对射式红外传感器
@@ -31,24 +31,22 @@ static mut SENSOR_COUNT: u32 = 0;
 #[entry]
 fn main() -> ! {
     // 获取对外设的访问对象
-    let cp = cortex_m::Peripherals::take().unwrap();
+    let mut cp = cortex_m::Peripherals::take().unwrap();
     let dp = pac::Peripherals::take().unwrap();
 
     let mut flash = dp.FLASH.constrain();
-    let rcc = dp.RCC.constrain();
     let syst = cp.SYST;
     let mut afio = dp.AFIO.constrain();
     let mut exti = dp.EXTI;
     let mut nvic = cp.NVIC;
-    let pwr = dp.PWR;
 
     let mut gpiob = dp.GPIOB.split();
 
-    // 冻结系统中所有时钟的配置，并将冻结的频率存储在时钟中
-    let clocks = rcc.cfgr.freeze(&mut flash.acr);
+    // HSE 经 PLL 倍频到 72MHz，停止模式唤醒后由时钟管理器恢复
+    let mut clock = ClockControl::new(dp.RCC, &mut flash.acr, Preset::Hse72);
 
     // 具有自定义精度的阻塞延迟函数
-    let mut delay = syst.delay(&clocks);
+    let mut delay = syst.delay(clock.clocks());
 
     // 初始化 OLED 显示屏
     println!("load oled...");
@@ -67,6 +65,8 @@ fn main() -> ! {
         nvic.set_priority(interrupt::EXTI15_10, 0x80);
     }
 
+    let mut low_power = LowPower::new();
+
     oled.show_string(1, 1, "Count:");
     loop {
         oled.show_num(1, 7, get_sensor_count(), 5);
@@ -76,20 +76,16 @@ fn main() -> ! {
         oled.show_string(2, 1, "       ");
         delay.delay_ms(100_u32);
 
-        pwr.cr.modify(|_, w| {
-            w
-                // 清除唤醒标识
-                .cwuf()
-                .set_bit()
-                // 进入停止模式
-                .lpds()
-                .set_bit()
-                .pdds()
-                .stop_mode()
-        });
-
-        // 请求低功耗模式
-        wfi();
+        // 进入停止模式，红外传感器外部中断唤醒
+        let source = low_power
+            .stop(
+                &mut cp.SCB,
+                &mut clock,
+                &mut flash.acr,
+                &[WakeSource::Pin(14)],
+            )
+            .unwrap();
+        println!("wake up: {:?}", source);
     }
 }
 
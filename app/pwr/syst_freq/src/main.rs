@@ -2,21 +2,23 @@
 #![no_main]
 #![allow(clippy::empty_loop)]
 
+use hardware::clock::{css, ClockControl, Mco, McoSource, Preset};
 use hardware::oled;
 
 use defmt::println;
 use defmt_rtt as _;
 use panic_probe as _;
 
-use cortex_m_rt::entry;
+use cortex_m_rt::{entry, exception};
 use stm32f1xx_hal::pac;
 use stm32f1xx_hal::prelude::_embedded_hal_blocking_delay_DelayMs;
-use stm32f1xx_hal::prelude::_fugit_RateExtU32;
 use stm32f1xx_hal::prelude::_stm32_hal_flash_FlashExt;
 use stm32f1xx_hal::prelude::_stm32_hal_gpio_GpioExt;
-use stm32f1xx_hal::rcc::RccExt;
 use stm32f1xx_hal::timer::SysTimerExt;
 
+/// 依次切换的时钟预设
+const PRESETS: [Preset; 3] = [Preset::Hse72, Preset::Hse48, Preset::Hsi8];
+
 #[entry]
 fn main() -> ! {
     // 获取对外设的访问对象
@@ -24,18 +26,23 @@ fn main() -> ! {
     let dp = pac::Peripherals::take().unwrap();
 
     let mut flash = dp.FLASH.constrain();
-    let rcc = dp.RCC.constrain();
     let syst = cp.SYST;
 
+    let mut gpioa = dp.GPIOA.split();
     let mut gpiob = dp.GPIOB.split();
 
-    // 冻结系统中所有时钟的配置，并将冻结的频率存储在时钟中
-    let clocks = rcc
-        .cfgr
-        // 设置SYSCLK时钟所需的频率
-        .sysclk(72.MHz())
-        // .sysclk(36.MHz())
-        .freeze(&mut flash.acr);
+    // 按预设配置时钟，HSE 启动失败时使用 HSI
+    let mut clock = ClockControl::new(dp.RCC, &mut flash.acr, Preset::Hse72);
+    if clock.preset() != Preset::Hse72 {
+        println!("HSE timeout, fallback to HSI");
+    }
+    let clocks = *clock.clocks();
+    // HSE 失效时自动切换到 HSI 并进入 NMI
+    css::enable();
+
+    // PA8 输出 HSI，可用示波器验证
+    let pa8 = gpioa.pa8.into_alternate_push_pull(&mut gpioa.crh);
+    let mut mco = Mco::new(pa8, McoSource::Hsi);
 
     // 具有自定义精度的阻塞延迟函数
     let mut delay = syst.delay(&clocks);
@@ -46,10 +53,46 @@ fn main() -> ! {
 
     oled.show_string(1, 1, "SYSCLK:");
     oled.show_num(1, 8, clocks.sysclk().to_Hz(), 8);
+    let mut index = 0;
     loop {
-        oled.show_string(2, 1, "running");
-        delay.delay_ms(500_u32);
-        oled.show_string(2, 1, "       ");
-        delay.delay_ms(500_u32);
+        for _ in 0..5 {
+            oled.show_string(2, 1, "running");
+            delay.delay_ms(500_u32);
+            oled.show_string(2, 1, "       ");
+            delay.delay_ms(500_u32);
+        }
+
+        let preset = if css::take_failure() {
+            println!("HSE failure");
+            Preset::Hsi8
+        } else {
+            index = (index + 1) % PRESETS.len();
+            PRESETS[index]
+        };
+
+        // 切换只修改时钟树，需使用新的时钟重新配置延时
+        let clocks = match clock.switch_sysclk(preset, &mut flash.acr) {
+            Ok(clocks) => clocks,
+            Err(err) => {
+                println!("{:?}", err);
+                continue;
+            }
+        };
+        delay = delay.release().release().delay(&clocks);
+        if preset.uses_hse() {
+            css::enable();
+            mco.set_source(McoSource::Hse);
+        } else {
+            mco.set_source(McoSource::Hsi);
+        }
+
+        println!("{:?} sysclk={:?}", preset, clocks.sysclk().to_Hz());
+        oled.show_num(1, 8, clocks.sysclk().to_Hz(), 8);
     }
 }
+
+/// HSE 失效
+#[exception]
+unsafe fn NonMaskableInt() {
+    css::on_nmi();
+}
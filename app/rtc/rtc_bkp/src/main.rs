@@ -2,6 +2,7 @@
 #![no_main]
 #![allow(clippy::empty_loop)]
 
+use hardware::bkp::{self, BackupData, BackupStore, Words};
 use hardware::oled;
 
 use defmt::println;
@@ -44,17 +45,19 @@ fn main() -> ! {
     // 启用对备份域的写入
     let mut backup_domain = rcc.bkp.constrain(dp.BKP, &mut pwr);
 
-    // 从备份数据寄存器的DR1至DR10寄存器之一读取16位值。
-    // 寄存器自变量是DRx寄存器的一个从零开始的索引：0表示DR1，最多9表示DR10。
-    // 提供9以上的数字会引起恐慌
-    let dr1 = backup_domain.read_data_register_low(0);
-    if dr1 == 0 {
-        // 将16位值写入备份数据寄存器的DR1至DR10寄存器之一。
-        // 寄存器自变量是DRx寄存器的一个从零开始的索引：0表示DR1，最多9表示DR10。
-        // 提供高于9的数字会引起恐慌。
-        backup_domain.write_data_register_low(0, 10);
-        backup_domain.write_data_register_low(1, 20);
-    }
+    // 未使用 rtc 模块的校准及日历，可使用全部 10 个备份寄存器
+    let store = BackupStore::new(bkp::data::ALL_REGISTERS);
+
+    // 读取备份数据，VBAT 掉电或数据损坏时重新初始化
+    let mut data = match store.load::<BackupValues>(&backup_domain) {
+        Ok(data) => data,
+        Err(err) => {
+            println!("backup data invalid: {:?}", err);
+            BackupValues::default()
+        }
+    };
+    data.boots = data.boots.wrapping_add(1);
+    store.store(&mut backup_domain, &data).unwrap();
 
     // 启动RTC
     let mut rtc = Rtc::new(dp.RTC, &mut backup_domain);
@@ -64,14 +67,50 @@ fn main() -> ! {
 
     oled.show_string(1, 1, "R1:");
     oled.show_string(2, 1, "R2:");
+    oled.show_string(3, 1, "Boots:");
 
-    let dr1 = backup_domain.read_data_register_low(0);
-    let dr2 = backup_domain.read_data_register_low(1);
-
-    oled.show_num(1, 4, dr1 as u32, 5);
-    oled.show_num(2, 4, dr2 as u32, 5);
+    oled.show_num(1, 4, data.r1 as u32, 5);
+    oled.show_num(2, 4, data.r2 as u32, 5);
+    oled.show_num(3, 7, data.boots as u32, 5);
 
     loop {
         wfi();
     }
 }
+
+/// 保存在备份寄存器中的数据
+struct BackupValues {
+    r1: u16,
+    r2: u16,
+    /// 上电次数
+    boots: u16,
+}
+
+impl Default for BackupValues {
+    fn default() -> Self {
+        BackupValues {
+            r1: 10,
+            r2: 20,
+            boots: 0,
+        }
+    }
+}
+
+impl BackupData for BackupValues {
+    const VERSION: u8 = 1;
+    const LEN: usize = 3;
+
+    fn encode(&self, words: &mut Words) {
+        words[0] = self.r1;
+        words[1] = self.r2;
+        words[2] = self.boots;
+    }
+
+    fn decode(words: &Words) -> Self {
+        BackupValues {
+            r1: words[0],
+            r2: words[1],
+            boots: words[2],
+        }
+    }
+}
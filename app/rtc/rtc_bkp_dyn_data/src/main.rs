@@ -2,16 +2,19 @@
 #![no_main]
 #![allow(clippy::empty_loop)]
 
+use hardware::bkp::{self, tamper, ActiveLevel, BackupData, BackupStore, Words};
 use hardware::oled;
 
 use defmt::println;
 use defmt_rtt as _;
 use panic_probe as _;
 
+use cortex_m::peripheral::NVIC;
 use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs;
 use cortex_m_rt::entry;
+use stm32f1xx_hal::backup_domain::BackupDomain;
 use stm32f1xx_hal::gpio;
-use stm32f1xx_hal::pac;
+use stm32f1xx_hal::pac::{self, interrupt};
 use stm32f1xx_hal::prelude::_stm32_hal_flash_FlashExt;
 use stm32f1xx_hal::prelude::_stm32_hal_gpio_GpioExt;
 use stm32f1xx_hal::rcc::RccExt;
@@ -49,16 +52,17 @@ fn main() -> ! {
     // 启用对备份域的写入
     let mut backup_domain = rcc.bkp.constrain(dp.BKP, &mut pwr);
 
-    // 从备份数据寄存器的DR1至DR10寄存器之一读取16位值。
-    // 寄存器自变量是DRx寄存器的一个从零开始的索引：0表示DR1，最多9表示DR10。
-    // 提供9以上的数字会引起恐慌
-    let dr1 = backup_domain.read_data_register_low(0);
-    if dr1 == 0 {
-        // 将16位值写入备份数据寄存器的DR1至DR10寄存器之一。
-        // 寄存器自变量是DRx寄存器的一个从零开始的索引：0表示DR1，最多9表示DR10。
-        // 提供高于9的数字会引起恐慌。
-        backup_domain.write_data_register_low(0, 10);
-        backup_domain.write_data_register_low(1, 20);
+    // 未使用 rtc 模块的校准及日历，可使用全部 10 个备份寄存器
+    let store = BackupStore::new(bkp::data::ALL_REGISTERS);
+
+    // 读取备份数据，VBAT 掉电、侵入检测或数据损坏时重新初始化
+    let mut data = load_or_init(&store, &mut backup_domain);
+
+    // 侵入检测，PC13 接地时清除所有备份寄存器
+    tamper::enable(&mut backup_domain, ActiveLevel::Low);
+    tamper::listen(&mut backup_domain);
+    unsafe {
+        NVIC::unmask(interrupt::TAMPER);
     }
 
     // 启动RTC
@@ -70,25 +74,70 @@ fn main() -> ! {
     oled.show_string(1, 1, "R:");
     oled.show_string(2, 1, "W:");
 
-    let mut array_write = [0x1234, 0x5678];
+    oled.show_string(3, 1, "Tamper:");
     loop {
+        // 侵入事件，备份寄存器已被硬件清除
+        if tamper::take_event() {
+            println!("tamper detected");
+            data = load_or_init(&store, &mut backup_domain);
+            oled.show_num(3, 8, tamper::events(), 3);
+        }
+
         // 按键事件
         if get_key_status(&mut key, &mut delay) {
-            array_write[0] += 1;
-            array_write[1] += 1;
-
-            backup_domain.write_data_register_low(0, array_write[0]);
-            backup_domain.write_data_register_low(1, array_write[1]);
+            data.values[0] = data.values[0].wrapping_add(1);
+            data.values[1] = data.values[1].wrapping_add(1);
+            store.store(&mut backup_domain, &data).unwrap();
 
-            oled.show_num(1, 3, array_write[0] as u32, 4);
-            oled.show_num(1, 8, array_write[1] as u32, 4);
+            oled.show_num(1, 3, data.values[0] as u32, 4);
+            oled.show_num(1, 8, data.values[1] as u32, 4);
         }
 
-        let dr1 = backup_domain.read_data_register_low(0);
-        let dr2 = backup_domain.read_data_register_low(1);
+        let stored = store.load::<DynData>(&backup_domain).unwrap_or_default();
+
+        oled.show_num(2, 3, stored.values[0] as u32, 4);
+        oled.show_num(2, 8, stored.values[1] as u32, 4);
+    }
+}
+
+#[interrupt]
+fn TAMPER() {
+    tamper::on_tamper();
+}
+
+/// 保存在备份寄存器中的数据
+#[derive(Default)]
+struct DynData {
+    values: [u16; 2],
+}
+
+impl BackupData for DynData {
+    const VERSION: u8 = 1;
+    const LEN: usize = 2;
+
+    fn encode(&self, words: &mut Words) {
+        words[..2].copy_from_slice(&self.values);
+    }
+
+    fn decode(words: &Words) -> Self {
+        DynData {
+            values: [words[0], words[1]],
+        }
+    }
+}
 
-        oled.show_num(2, 3, dr1 as u32, 4);
-        oled.show_num(2, 8, dr2 as u32, 4);
+/// 读取备份数据，无效时写入初始值
+fn load_or_init(store: &BackupStore, backup_domain: &mut BackupDomain) -> DynData {
+    match store.load(backup_domain) {
+        Ok(data) => data,
+        Err(err) => {
+            println!("backup data invalid: {:?}", err);
+            let data = DynData {
+                values: [0x1234, 0x5678],
+            };
+            store.store(backup_domain, &data).unwrap();
+            data
+        }
     }
 }
 
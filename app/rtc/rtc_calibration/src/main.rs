@@ -0,0 +1,85 @@
+#![no_std]
+#![no_main]
+#![allow(clippy::empty_loop)]
+
+use hardware::oled;
+use hardware::rtc::calibration::{self, Correction};
+
+use defmt::println;
+use defmt_rtt as _;
+use panic_probe as _;
+
+use cortex_m::asm::wfi;
+use cortex_m_rt::entry;
+use stm32f1xx_hal::pac;
+use stm32f1xx_hal::prelude::_fugit_RateExtU32;
+use stm32f1xx_hal::prelude::_stm32_hal_flash_FlashExt;
+use stm32f1xx_hal::prelude::_stm32_hal_gpio_GpioExt;
+use stm32f1xx_hal::rcc::RccExt;
+use stm32f1xx_hal::rtc::Rtc;
+
+/// 测量时长，单位：秒
+const WINDOW_SECONDS: u32 = 10;
+
+#[entry]
+fn main() -> ! {
+    // 获取对外设的访问对象
+    let mut cp = cortex_m::Peripherals::take().unwrap();
+    let dp = pac::Peripherals::take().unwrap();
+
+    let mut flash = dp.FLASH.constrain();
+    let rcc = dp.RCC.constrain();
+    let mut pwr = dp.PWR;
+
+    let mut gpiob = dp.GPIOB.split();
+
+    // 测量基准，系统时钟必须来自 HSE
+    let clocks = rcc
+        .cfgr
+        .use_hse(8.MHz())
+        .sysclk(72.MHz())
+        .pclk1(36.MHz())
+        .freeze(&mut flash.acr);
+
+    // 初始化 OLED 显示屏
+    println!("load oled...");
+    let mut oled = oled::simple::init_oled(gpiob.pb8, gpiob.pb9, &mut gpiob.crh);
+
+    let mut backup_domain = rcc.bkp.constrain(dp.BKP, &mut pwr);
+    let mut rtc = Rtc::new(dp.RTC, &mut backup_domain);
+    println!("stored: {:?}", calibration::load(&backup_domain));
+
+    oled.show_string(1, 1, "Measuring...");
+
+    // 去掉校正后测量原始偏差
+    calibration::apply(&mut backup_domain, Correction::default());
+    let sysclk = clocks.sysclk().raw();
+    let ppm = calibration::measure_ppm(&mut rtc, &mut cp.DCB, &mut cp.DWT, sysclk, WINDOW_SECONDS);
+    let correction = Correction::from_ppm(ppm);
+    println!("drift={:?}ppm {:?}", ppm, correction);
+
+    // 应用并保存，下次启动时由 `calibration::restore` 恢复
+    calibration::apply(&mut backup_domain, correction);
+    calibration::store(&mut backup_domain, correction);
+
+    // 校正后的剩余偏差
+    let residual =
+        calibration::measure_ppm(&mut rtc, &mut cp.DCB, &mut cp.DWT, sysclk, WINDOW_SECONDS);
+    println!("residual={:?}ppm", residual);
+
+    // PC13 输出 512Hz 校准时钟
+    calibration::set_clock_output(&mut backup_domain, true);
+
+    oled.show_string(1, 1, "Drift:      ppm");
+    oled.show_string(2, 1, "PRL-:");
+    oled.show_string(3, 1, "CAL :");
+    oled.show_string(4, 1, "Res :      ppm");
+    oled.show_signed_num(1, 7, ppm as i32, 4);
+    oled.show_num(2, 6, correction.prescaler_decrease as u32, 3);
+    oled.show_num(3, 6, correction.cal as u32, 3);
+    oled.show_signed_num(4, 7, residual as i32, 4);
+
+    loop {
+        wfi();
+    }
+}
@@ -2,22 +2,39 @@
 #![no_main]
 #![allow(clippy::empty_loop)]
 
+use core::cell::RefCell;
+use core::fmt::Write;
+
 use hardware::oled;
+use hardware::rtc::{Alarm, Calendar, TimeCommand, Weekday};
+use hardware::serial::LineBuffer;
 
 use defmt::println;
 use defmt_rtt as _;
 use panic_probe as _;
 
+use cortex_m::interrupt::Mutex;
+use cortex_m::peripheral::NVIC;
 use cortex_m_rt::entry;
-use stm32f1xx_hal::pac;
+use heapless::String;
+use stm32f1xx_hal::gpio::{Output, Pin};
+use stm32f1xx_hal::pac::{self, interrupt, EXTI, USART1};
 use stm32f1xx_hal::prelude::_embedded_hal_blocking_delay_DelayMs;
-use stm32f1xx_hal::prelude::_stm32_hal_flash_FlashExt;
-use stm32f1xx_hal::prelude::_stm32_hal_gpio_GpioExt;
+use stm32f1xx_hal::prelude::{
+    _stm32_hal_afio_AfioExt, _stm32_hal_flash_FlashExt, _stm32_hal_gpio_GpioExt,
+};
 use stm32f1xx_hal::rcc::RccExt;
 use stm32f1xx_hal::rtc::Rtc;
+use stm32f1xx_hal::serial::{self, Rx, Serial, Tx};
+use stm32f1xx_hal::time::U32Ext;
 use stm32f1xx_hal::timer::SysTimerExt;
-use time::macros::datetime;
-use time::OffsetDateTime;
+
+static G_CALENDAR: Mutex<RefCell<Option<Calendar<4>>>> = Mutex::new(RefCell::new(None));
+static G_EXTI: Mutex<RefCell<Option<EXTI>>> = Mutex::new(RefCell::new(None));
+static G_LED: Mutex<RefCell<Option<Pin<'C', 13, Output>>>> = Mutex::new(RefCell::new(None));
+static G_RX: Mutex<RefCell<Option<Rx<USART1>>>> = Mutex::new(RefCell::new(None));
+static G_TX: Mutex<RefCell<Option<Tx<USART1>>>> = Mutex::new(RefCell::new(None));
+static G_LINE: Mutex<RefCell<Option<LineBuffer<40>>>> = Mutex::new(RefCell::new(None));
 
 #[entry]
 fn main() -> ! {
@@ -27,10 +44,14 @@ fn main() -> ! {
 
     let mut flash = dp.FLASH.constrain();
     let rcc = dp.RCC.constrain();
+    let mut afio = dp.AFIO.constrain();
     let syst = cp.SYST;
+    let mut nvic = cp.NVIC;
     let mut pwr = dp.PWR;
 
+    let mut gpioa = dp.GPIOA.split();
     let mut gpiob = dp.GPIOB.split();
+    let mut gpioc = dp.GPIOC.split();
 
     // 冻结系统中所有时钟的配置，并将冻结的频率存储在时钟中
     let clocks = rcc.cfgr.freeze(&mut flash.acr);
@@ -41,48 +62,154 @@ fn main() -> ! {
     // 初始化 OLED 显示屏
     let mut oled = oled::simple::init_oled(gpiob.pb8, gpiob.pb9, &mut gpiob.crh);
 
-    let mut backup_domain = rcc.bkp.constrain(dp.BKP, &mut pwr);
-    let mut rtc = Rtc::new(dp.RTC, &mut backup_domain);
+    let led = gpioc.pc13.into_push_pull_output(&mut gpioc.crh);
 
-    // 设置初始时间
-    if rtc.current_time() == 0 {
-        let dt = datetime!(2023-10-20 12:30:00 UTC);
-        let timestamp = dt.unix_timestamp();
-        rtc.set_time(timestamp as u32);
+    // 日历，时区及有效标记保存在备份寄存器中
+    let mut backup_domain = rcc.bkp.constrain(dp.BKP, &mut pwr);
+    let rtc = Rtc::new(dp.RTC, &mut backup_domain);
+    let mut calendar: Calendar<4> = Calendar::new(rtc, backup_domain);
+    calendar.add_alarm(Alarm::daily(7, 0, 0)).unwrap();
+    let workdays = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+    ];
+    calendar
+        .add_alarm(Alarm::weekly(&workdays, 12, 0, 0))
+        .unwrap();
+    calendar.listen_alarm(&dp.EXTI);
+
+    // 串口校时
+    let tx = gpioa.pa9.into_alternate_push_pull(&mut gpioa.crh);
+    let rx = gpioa.pa10;
+    let (tx, mut rx) = Serial::new(
+        dp.USART1,
+        (tx, rx),
+        &mut afio.mapr,
+        serial::Config::default().baudrate(115200.bps()),
+        &clocks,
+    )
+    .split();
+    rx.listen();
+
+    // 移动到全局存储中
+    cortex_m::interrupt::free(|cs| {
+        G_CALENDAR.borrow(cs).replace(Some(calendar));
+        G_EXTI.borrow(cs).replace(Some(dp.EXTI));
+        G_LED.borrow(cs).replace(Some(led));
+        G_RX.borrow(cs).replace(Some(rx));
+        G_TX.borrow(cs).replace(Some(tx));
+        G_LINE.borrow(cs).replace(Some(LineBuffer::new()));
+    });
+
+    unsafe {
+        nvic.set_priority(interrupt::RTCALARM, 1);
+        nvic.set_priority(interrupt::USART1, 2);
+        NVIC::unmask(interrupt::RTCALARM);
+        NVIC::unmask(interrupt::USART1);
     }
 
     oled.show_string(1, 1, "Date:XXXX-XX-XX");
     oled.show_string(2, 1, "Time:XX:XX:XX");
     oled.show_string(3, 1, "CNT :");
-    // oled.show_string( 4, 1, "DIV :");
     loop {
-        let timestamp = rtc.current_time() as i64;
-        println!("timestamp: {}", timestamp);
-        // 创建 OffsetDateTime 对象，假定时间戳是以秒为单位, UTC
-        // let timestamp = timestamp + 8 * 60 * 60;
-        let datetime = OffsetDateTime::from_unix_timestamp(timestamp).unwrap();
-
-        // 格式化日期时间为字符串
-        let year = datetime.year() as u32;
-        let month = datetime.month() as u32;
-        let day = datetime.day() as u32;
-        let hour = datetime.hour() as u32;
-        let minute = datetime.minute() as u32;
-        let second = datetime.second() as u32;
-        println!(
-            "time: {:?}-{:?}-{:?} {:?}:{:?}:{:?}",
-            year, month, day, hour, minute, second
-        );
-
-        oled.show_num(1, 6, year, 4);
-        oled.show_num(1, 11, month, 2);
-        oled.show_num(1, 14, day, 2);
-        oled.show_num(2, 6, hour, 2);
-        oled.show_num(2, 9, minute, 2);
-        oled.show_num(2, 12, second, 2);
-
-        oled.show_num(3, 6, timestamp as u32, 10);
+        let (valid, now, timestamp) = cortex_m::interrupt::free(|cs| {
+            let calendar = G_CALENDAR.borrow(cs).borrow();
+            let calendar = calendar.as_ref().unwrap();
+            (
+                calendar.is_time_valid(),
+                calendar.now(),
+                calendar.utc_timestamp(),
+            )
+        });
+
+        if valid {
+            oled.show_num(1, 6, now.year as u32, 4);
+            oled.show_num(1, 11, now.month as u32, 2);
+            oled.show_num(1, 14, now.day as u32, 2);
+            oled.show_num(2, 6, now.hour as u32, 2);
+            oled.show_num(2, 9, now.minute as u32, 2);
+            oled.show_num(2, 12, now.second as u32, 2);
+            oled.show_string(4, 1, "        ");
+        } else {
+            oled.show_string(4, 1, "SET TIME");
+        }
+        oled.show_num(3, 6, timestamp, 10);
 
         delay.delay_ms(1000_u32);
     }
 }
+
+/// 闹钟
+#[interrupt]
+fn RTCALARM() {
+    cortex_m::interrupt::free(|cs| {
+        let exti = G_EXTI.borrow(cs).borrow();
+        let mut calendar = G_CALENDAR.borrow(cs).borrow_mut();
+        let fired = calendar.as_mut().unwrap().on_alarm(exti.as_ref().unwrap());
+        if fired != 0 {
+            println!("alarm: {:b}", fired);
+            if let Some(led) = G_LED.borrow(cs).borrow_mut().as_mut() {
+                led.toggle();
+            }
+        }
+    });
+}
+
+/// 接收校时指令
+#[interrupt]
+fn USART1() {
+    // 临界区内只解析指令并更新日历，应答在退出后发送，阻塞发送期间闹钟中断仍可抢占
+    let reply = cortex_m::interrupt::free(|cs| {
+        let mut rx = G_RX.borrow(cs).borrow_mut();
+        let rx = rx.as_mut().unwrap();
+        let mut line = G_LINE.borrow(cs).borrow_mut();
+        let line = line.as_mut().unwrap();
+
+        // 溢出等错误时读取会清除错误标志，已接收的部分指令不完整，直接丢弃
+        let byte = match rx.read() {
+            Ok(byte) => byte,
+            Err(nb::Error::WouldBlock) => return None,
+            Err(nb::Error::Other(err)) => {
+                println!("usart1 error: {}", defmt::Debug2Format(&err));
+                line.clear();
+                return None;
+            }
+        };
+        if !line.push(byte) {
+            return None;
+        }
+
+        let mut calendar = G_CALENDAR.borrow(cs).borrow_mut();
+        let calendar = calendar.as_mut().unwrap();
+        let mut reply: String<64> = String::new();
+        let result = TimeCommand::parse(line.as_str()).map(|command| calendar.apply(command));
+        match result {
+            Some(Ok(())) => {
+                write!(
+                    reply,
+                    "{} UTC{:+}min\r\n",
+                    calendar.now(),
+                    calendar.utc_offset()
+                )
+                .ok();
+            }
+            _ => {
+                reply.push_str("ERROR_COMMAND\r\n").ok();
+            }
+        }
+        line.clear();
+        Some(reply)
+    });
+
+    let Some(reply) = reply else {
+        return;
+    };
+    // 发送端只在本中断中使用，取出后发送再放回
+    if let Some(mut tx) = cortex_m::interrupt::free(|cs| G_TX.borrow(cs).take()) {
+        hardware::serial::send_string(&mut tx, reply.as_str());
+        cortex_m::interrupt::free(|cs| G_TX.borrow(cs).replace(Some(tx)));
+    }
+}
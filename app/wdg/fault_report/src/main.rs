@@ -0,0 +1,136 @@
+#![no_std]
+#![no_main]
+#![allow(clippy::empty_loop)]
+
+use core::fmt::Write;
+use core::panic::PanicInfo;
+
+use hardware::diag::{self, Report, ResetCause};
+use hardware::oled;
+use hardware::watchdog::{Iwdg, IwdgConfig};
+
+use defmt::println;
+use defmt_rtt as _;
+
+use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs;
+use cortex_m_rt::{entry, exception, ExceptionFrame};
+use stm32f1xx_hal::pac;
+use stm32f1xx_hal::prelude::{
+    _stm32_hal_afio_AfioExt, _stm32_hal_flash_FlashExt, _stm32_hal_gpio_GpioExt,
+};
+use stm32f1xx_hal::rcc::RccExt;
+use stm32f1xx_hal::serial::{self, Serial};
+use stm32f1xx_hal::time::U32Ext;
+use stm32f1xx_hal::timer::SysTimerExt;
+
+/// 停止喂狗前的循环次数，每次 100ms
+const FEED_LOOPS: u32 = 100;
+
+#[entry]
+fn main() -> ! {
+    // 获取对外设的访问对象
+    let cp = cortex_m::Peripherals::take().unwrap();
+    let dp = pac::Peripherals::take().unwrap();
+
+    // 复位原因及上次的故障，需在其它代码之前读取
+    let cause = diag::take_reset_cause();
+    let report = diag::report::take();
+
+    let mut flash = dp.FLASH.constrain();
+    let rcc = dp.RCC.constrain();
+    let mut afio = dp.AFIO.constrain();
+    let syst = cp.SYST;
+
+    let mut gpioa = dp.GPIOA.split();
+    let mut gpiob = dp.GPIOB.split();
+
+    // 冻结系统中所有时钟的配置，并将冻结的频率存储在时钟中
+    let clocks = rcc.cfgr.freeze(&mut flash.acr);
+
+    // 具有自定义精度的阻塞延迟函数
+    let mut delay = syst.delay(&clocks);
+
+    // 初始化 OLED 显示屏
+    println!("load oled...");
+    let mut oled = oled::simple::init_oled(gpiob.pb8, gpiob.pb9, &mut gpiob.crh);
+
+    // USART1
+    let tx = gpioa.pa9.into_alternate_push_pull(&mut gpioa.crh);
+    let rx = gpioa.pa10;
+    let (mut tx, _rx) = Serial::new(
+        dp.USART1,
+        (tx, rx),
+        &mut afio.mapr,
+        serial::Config::default().baudrate(115200.bps()),
+        &clocks,
+    )
+    .split();
+
+    // 按键
+    let panic_key = gpiob.pb1.into_pull_up_input(&mut gpiob.crl);
+    let fault_key = gpiob.pb11.into_pull_up_input(&mut gpiob.crh);
+
+    // 报告复位原因及上次的故障
+    println!("reset: {:?}", cause);
+    writeln!(tx, "reset: {:?}", cause).ok();
+    oled.show_string(
+        1,
+        1,
+        match cause {
+            ResetCause::PowerOn => "RST: POWER",
+            ResetCause::Pin => "RST: PIN",
+            ResetCause::Software => "RST: SOFTWARE",
+            ResetCause::Iwdg => "RST: IWDG",
+            ResetCause::Wwdg => "RST: WWDG",
+            ResetCause::LowPower => "RST: LOW POWER",
+            ResetCause::Unknown => "RST: UNKNOWN",
+        },
+    );
+    match &report {
+        Some(report) => {
+            println!("last fault: {:?}", report);
+            writeln!(tx, "last fault: {}", report).ok();
+        }
+        None => println!("no fault"),
+    }
+    match &report {
+        Some(Report::Panic(_)) => oled.show_string(2, 1, "PANIC"),
+        Some(Report::HardFault(frame)) => {
+            oled.show_string(2, 1, "HARDFAULT");
+            oled.show_string(3, 1, "PC:");
+            oled.show_hex_num(3, 4, frame.pc, 8);
+        }
+        None => oled.show_string(2, 1, "NO FAULT"),
+    }
+
+    let mut iwdg = Iwdg::start(dp.IWDG, IwdgConfig::from_ms(1000).unwrap());
+
+    let mut loops = 0;
+    loop {
+        if panic_key.is_low() {
+            panic!("panic key pressed after {} loops", loops);
+        }
+        if fault_key.is_low() {
+            // 未定义指令，UsageFault 未使能时升级为 HardFault
+            cortex_m::asm::udf();
+        }
+
+        // 运行一段时间后停止喂狗，模拟程序卡死
+        if loops < FEED_LOOPS {
+            iwdg.feed();
+            loops += 1;
+            oled.show_num(4, 1, loops, 3);
+        }
+        delay.delay_ms(100_u32);
+    }
+}
+
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    diag::report::panic(info)
+}
+
+#[exception]
+unsafe fn HardFault(frame: &ExceptionFrame) -> ! {
+    diag::report::hard_fault(frame)
+}
@@ -2,7 +2,9 @@
 #![no_main]
 #![allow(clippy::empty_loop)]
 
+use hardware::diag::{self, ResetCause};
 use hardware::oled;
+use hardware::watchdog::{Iwdg, IwdgConfig, Supervisor};
 
 use defmt::println;
 use defmt_rtt as _;
@@ -12,14 +14,11 @@ use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs;
 use cortex_m_rt::entry;
 use stm32f1xx_hal::gpio;
 use stm32f1xx_hal::pac;
-use stm32f1xx_hal::pac::RCC;
-use stm32f1xx_hal::prelude::_fugit_ExtU32;
 use stm32f1xx_hal::prelude::_stm32_hal_flash_FlashExt;
 use stm32f1xx_hal::prelude::_stm32_hal_gpio_GpioExt;
 use stm32f1xx_hal::rcc::RccExt;
 use stm32f1xx_hal::timer::SysDelay;
 use stm32f1xx_hal::timer::SysTimerExt;
-use stm32f1xx_hal::watchdog;
 
 #[entry]
 fn main() -> ! {
@@ -30,7 +29,6 @@ fn main() -> ! {
     let mut flash = dp.FLASH.constrain();
     let rcc = dp.RCC.constrain();
     let syst = cp.SYST;
-    let iwdg = dp.IWDG;
 
     let mut gpiob = dp.GPIOB.split();
 
@@ -47,15 +45,12 @@ fn main() -> ! {
     // 按键
     let mut key = gpiob.pb1.into_pull_up_input(&mut gpiob.crl);
 
-    // 检查是否由于IWDG复位
-    let rcc_b = unsafe { &*RCC::ptr() };
-    if rcc_b.csr.read().iwdgrstf().is_reset() {
+    // 检查是否由于IWDG复位，并清除复位标志
+    if diag::take_reset_cause() == ResetCause::Iwdg {
         oled.show_string(2, 1, "IWDGRST");
         delay.delay_ms(1000_u16);
         // oled.show_string(2, 1, "       ");
         delay.delay_ms(100_u16);
-
-        rcc_b.csr.modify(|_, w| w.iwdgrstf().clear_bit());
     } else {
         oled.show_string(2, 1, "RST");
         delay.delay_ms(500_u16);
@@ -65,24 +60,28 @@ fn main() -> ! {
 
     oled.show_string(1, 1, "IWDG TEST");
 
-    let mut watchdog = watchdog::IndependentWatchdog::new(iwdg);
+    // 以 5 秒的超时时间启动 IWDG
+    let mut iwdg = Iwdg::start(dp.IWDG, IwdgConfig::from_ms(5000).unwrap());
 
-    // 以等于5秒的重新加载值启动IWDG
-    watchdog.start(5.secs());
+    // 按键及显示两个阶段都签到后才喂狗
+    let mut supervisor = Supervisor::<2>::new();
+    let key_task = supervisor.register("key").unwrap();
+    let display_task = supervisor.register("display").unwrap();
 
     loop {
         // 按键事件
         // 按住按键不放，模拟程序卡死的情况
         get_key_status(&mut key, &mut delay);
-
-        // Feed the IWDG to prevent a reset
-        // 开始喂狗，间隔时间不能超过上面的 5s
-        watchdog.feed();
+        supervisor.check_in(key_task);
 
         oled.show_string(3, 1, "FEED");
         delay.delay_ms(200_u32);
         oled.show_string(3, 1, "    ");
         delay.delay_ms(600_u32);
+        supervisor.check_in(display_task);
+
+        // 开始喂狗，间隔时间不能超过上面的 5s
+        supervisor.feed(&mut iwdg);
     }
 }
 
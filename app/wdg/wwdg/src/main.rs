@@ -2,17 +2,20 @@
 #![no_main]
 #![allow(clippy::empty_loop)]
 
+use hardware::diag::{self, ResetCause};
 use hardware::oled;
+use hardware::watchdog::wwdg::{self, Wwdg, WwdgConfig};
 
 use defmt::println;
 use defmt_rtt as _;
 use panic_probe as _;
 
+use cortex_m::peripheral::NVIC;
 use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs;
 use cortex_m_rt::entry;
 use stm32f1xx_hal::gpio;
-use stm32f1xx_hal::pac;
 use stm32f1xx_hal::pac::TIM2;
+use stm32f1xx_hal::pac::{self, interrupt};
 use stm32f1xx_hal::prelude::{
     _fugit_RateExtU32, _stm32_hal_flash_FlashExt, _stm32_hal_gpio_GpioExt,
 };
@@ -28,21 +31,15 @@ fn main() -> ! {
 
     let mut flash = dp.FLASH.constrain();
     let rcc = dp.RCC;
-    let wwdg = &dp.WWDG;
 
     let mut gpiob = dp.GPIOB.split();
 
-    // 启用窗口看门狗时钟
-    rcc.apb1enr.modify(|_, w| w.wwdgen().enabled());
-
-    // 检查WWDG复位标志位
-    if rcc.csr.read().wwdgrstf().is_reset() {
+    // 检查复位原因并清除复位标志
+    let cause = diag::take_reset_cause();
+    if cause == ResetCause::Wwdg {
         println!("WWDGRST..");
-
-        // 清除复位标志
-        rcc.csr.modify(|_, w| w.wwdgrstf().clear_bit());
     } else {
-        println!("RST..");
+        println!("RST.. {:?}", cause);
     }
 
     // 冻结系统中所有时钟的配置，并将冻结的频率存储在时钟中
@@ -61,19 +58,17 @@ fn main() -> ! {
     oled.show_string(1, 1, "WWDG TEST");
     delay.delay_ms(1000_u32);
 
-    // 设置窗口看门狗的预分频值和窗口值
-    wwdg.cfr.modify(|_, w| {
-        // 设置预分频值
-        w.wdgtb()
-            .div8()
-            // 设置窗口值
-            .w()
-            .bits(0x40 + 21) // 30ms
-    });
+    // 超时时间 50ms、窗口时间 30ms，即喂狗间隔需在 30ms~50ms 之间
+    let config = WwdgConfig::from_ms(clocks.pclk1().raw(), 50, 30).unwrap();
+    println!("wwdg config: {:?}", config);
+    let mut wwdg = Wwdg::start(dp.WWDG, config);
 
-    // 使能WWDG并设置初始计数值为 0x40 + 54
-    wwdg.cr
-        .modify(|_, w| w.wdga().enabled().t().bits(0x40 + 54)); // 50ms
+    // 复位前的最后一次日志
+    wwdg::set_early_wakeup_hook(|| println!("WWDG early wakeup, reset soon"));
+    wwdg.listen_early_wakeup();
+    unsafe {
+        NVIC::unmask(interrupt::WWDG);
+    }
 
     println!("loop..");
     loop {
@@ -107,10 +102,15 @@ fn main() -> ! {
 
         // 喂狗
         // 30ms-50ms
-        wwdg.cr.modify(|_, w| w.t().bits(0x40 + 54));
+        wwdg.feed();
     }
 }
 
+#[interrupt]
+fn WWDG() {
+    wwdg::on_early_wakeup();
+}
+
 /// 获取按键的状态
 /// 按键是否按下
 fn get_key_status(
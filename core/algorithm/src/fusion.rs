@@ -0,0 +1,449 @@
+//! 姿态解算
+//! 将加速度和角速度数据融合为横滚角、俯仰角和偏航角。
+//! - 互补滤波: 计算量小，适合平衡车等只关心横滚/俯仰的场景
+//! - Madgwick/Mahony 四元数滤波: 无万向节死锁，适合飞行器
+//!
+//! 没有磁力计时偏航角仅由陀螺仪积分得到，会随时间漂移。
+//! ```rust
+//! use algorithm::fusion::{MadgwickFilter, ScaledData};
+//!
+//! let mut filter = MadgwickFilter::new(0.01, 0.1);
+//! let data = ScaledData {
+//!     acc_z: 1.0,
+//!     ..Default::default()
+//! };
+//! let angles = filter.update(&data);
+//! ```
+use libm::{asinf, atan2f, sqrtf};
+
+/// 标准重力加速度，单位：m/s²
+pub const STANDARD_GRAVITY: f32 = 9.80665;
+
+/// 物理单位的加速度、角速度和温度数据
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ScaledData {
+    /// 加速度，单位：g
+    pub acc_x: f32,
+    pub acc_y: f32,
+    pub acc_z: f32,
+    /// 角速度，单位：°/s
+    pub gyro_x: f32,
+    pub gyro_y: f32,
+    pub gyro_z: f32,
+    /// 芯片温度，单位：°C
+    pub temp: f32,
+}
+
+impl ScaledData {
+    /// 加速度，单位：m/s²
+    pub fn accel_ms2(&self) -> [f32; 3] {
+        [
+            self.acc_x * STANDARD_GRAVITY,
+            self.acc_y * STANDARD_GRAVITY,
+            self.acc_z * STANDARD_GRAVITY,
+        ]
+    }
+}
+
+/// 姿态滤波器
+pub trait AttitudeFilter {
+    /// 输入物理单位数据并更新姿态角
+    fn update(&mut self, data: &ScaledData) -> EulerAngles;
+
+    /// 当前姿态角
+    fn angles(&self) -> EulerAngles;
+
+    /// 重置姿态
+    fn reset(&mut self);
+}
+
+/// 角度转弧度
+const DEG_TO_RAD: f32 = core::f32::consts::PI / 180.0;
+/// 弧度转角度
+const RAD_TO_DEG: f32 = 180.0 / core::f32::consts::PI;
+
+/// 姿态角，单位：°
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct EulerAngles {
+    /// 横滚角，绕 X 轴
+    pub roll: f32,
+    /// 俯仰角，绕 Y 轴
+    pub pitch: f32,
+    /// 偏航角，绕 Z 轴
+    pub yaw: f32,
+}
+
+/// 由加速度计算横滚角和俯仰角，单位：°
+fn accel_angles(data: &ScaledData) -> (f32, f32) {
+    let roll = atan2f(data.acc_y, data.acc_z) * RAD_TO_DEG;
+    let pitch = atan2f(
+        -data.acc_x,
+        sqrtf(data.acc_y * data.acc_y + data.acc_z * data.acc_z),
+    ) * RAD_TO_DEG;
+    (roll, pitch)
+}
+
+/// 加速度是否有效，自由落体时三轴均为 0 无法用于校正
+fn accel_valid(data: &ScaledData) -> bool {
+    data.acc_x != 0.0 || data.acc_y != 0.0 || data.acc_z != 0.0
+}
+
+/// 互补滤波
+/// 短期信任陀螺仪积分，长期信任加速度计算的角度
+pub struct ComplementaryFilter {
+    /// 采样周期，单位：s
+    sample_period: f32,
+    /// 陀螺仪权重，典型值：0.98
+    alpha: f32,
+    angles: EulerAngles,
+    initialized: bool,
+}
+
+impl ComplementaryFilter {
+    /// 创建互补滤波器
+    /// sample_period: 采样周期，单位：s
+    /// alpha: 陀螺仪权重，取值 0~1
+    pub fn new(sample_period: f32, alpha: f32) -> Self {
+        ComplementaryFilter {
+            sample_period,
+            alpha,
+            angles: EulerAngles::default(),
+            initialized: false,
+        }
+    }
+
+    /// 输入物理单位数据并更新姿态角
+    pub fn update(&mut self, data: &ScaledData) -> EulerAngles {
+        let dt = self.sample_period;
+
+        if !accel_valid(data) {
+            self.angles.roll += data.gyro_x * dt;
+            self.angles.pitch += data.gyro_y * dt;
+            self.angles.yaw += data.gyro_z * dt;
+            return self.angles;
+        }
+
+        self.angles.yaw += data.gyro_z * dt;
+
+        let (acc_roll, acc_pitch) = accel_angles(data);
+        if !self.initialized {
+            // 首次采样直接使用加速度计算的角度
+            self.angles.roll = acc_roll;
+            self.angles.pitch = acc_pitch;
+            self.initialized = true;
+            return self.angles;
+        }
+
+        let alpha = self.alpha;
+        self.angles.roll = alpha * (self.angles.roll + data.gyro_x * dt) + (1.0 - alpha) * acc_roll;
+        self.angles.pitch =
+            alpha * (self.angles.pitch + data.gyro_y * dt) + (1.0 - alpha) * acc_pitch;
+        self.angles
+    }
+
+    /// 当前姿态角
+    pub fn angles(&self) -> EulerAngles {
+        self.angles
+    }
+
+    /// 重置姿态
+    pub fn reset(&mut self) {
+        self.angles = EulerAngles::default();
+        self.initialized = false;
+    }
+}
+
+impl AttitudeFilter for ComplementaryFilter {
+    fn update(&mut self, data: &ScaledData) -> EulerAngles {
+        ComplementaryFilter::update(self, data)
+    }
+
+    fn angles(&self) -> EulerAngles {
+        ComplementaryFilter::angles(self)
+    }
+
+    fn reset(&mut self) {
+        ComplementaryFilter::reset(self)
+    }
+}
+
+/// 四元数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Default for Quaternion {
+    /// 单位四元数，表示无旋转
+    fn default() -> Self {
+        Quaternion {
+            w: 1.0,
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    }
+}
+
+impl Quaternion {
+    /// 归一化
+    pub fn normalize(&mut self) {
+        let norm = sqrtf(self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z);
+        if norm == 0.0 {
+            *self = Quaternion::default();
+            return;
+        }
+        self.w /= norm;
+        self.x /= norm;
+        self.y /= norm;
+        self.z /= norm;
+    }
+
+    /// 转换为姿态角，单位：°
+    pub fn to_euler(&self) -> EulerAngles {
+        let (w, x, y, z) = (self.w, self.x, self.y, self.z);
+
+        let roll = atan2f(2.0 * (w * x + y * z), 1.0 - 2.0 * (x * x + y * y));
+        let pitch = asinf((2.0 * (w * y - z * x)).clamp(-1.0, 1.0));
+        let yaw = atan2f(2.0 * (w * z + x * y), 1.0 - 2.0 * (y * y + z * z));
+
+        EulerAngles {
+            roll: roll * RAD_TO_DEG,
+            pitch: pitch * RAD_TO_DEG,
+            yaw: yaw * RAD_TO_DEG,
+        }
+    }
+}
+
+/// 归一化三维向量，长度为 0 时返回 None
+fn normalize3(x: f32, y: f32, z: f32) -> Option<(f32, f32, f32)> {
+    let norm = sqrtf(x * x + y * y + z * z);
+    if norm == 0.0 {
+        return None;
+    }
+    Some((x / norm, y / norm, z / norm))
+}
+
+/// Madgwick 梯度下降四元数滤波
+pub struct MadgwickFilter {
+    /// 采样周期，单位：s
+    sample_period: f32,
+    /// 梯度下降步长，典型值：0.1
+    beta: f32,
+    q: Quaternion,
+}
+
+impl MadgwickFilter {
+    /// 创建 Madgwick 滤波器
+    /// sample_period: 采样周期，单位：s
+    /// beta: 梯度下降步长，越大收敛越快，噪声也越大
+    pub fn new(sample_period: f32, beta: f32) -> Self {
+        MadgwickFilter {
+            sample_period,
+            beta,
+            q: Quaternion::default(),
+        }
+    }
+
+    /// 输入物理单位数据并更新姿态角
+    pub fn update(&mut self, data: &ScaledData) -> EulerAngles {
+        let Quaternion {
+            w: q0,
+            x: q1,
+            y: q2,
+            z: q3,
+        } = self.q;
+        let gx = data.gyro_x * DEG_TO_RAD;
+        let gy = data.gyro_y * DEG_TO_RAD;
+        let gz = data.gyro_z * DEG_TO_RAD;
+
+        // 陀螺仪积分得到的四元数变化率
+        let mut q_dot0 = 0.5 * (-q1 * gx - q2 * gy - q3 * gz);
+        let mut q_dot1 = 0.5 * (q0 * gx + q2 * gz - q3 * gy);
+        let mut q_dot2 = 0.5 * (q0 * gy - q1 * gz + q3 * gx);
+        let mut q_dot3 = 0.5 * (q0 * gz + q1 * gy - q2 * gx);
+
+        // 使用加速度计算梯度下降的校正量
+        if let Some((ax, ay, az)) = normalize3(data.acc_x, data.acc_y, data.acc_z) {
+            let q0q0 = q0 * q0;
+            let q1q1 = q1 * q1;
+            let q2q2 = q2 * q2;
+            let q3q3 = q3 * q3;
+
+            let s0 = 4.0 * q0 * q2q2 + 2.0 * q2 * ax + 4.0 * q0 * q1q1 - 2.0 * q1 * ay;
+            let s1 = 4.0 * q1 * q3q3 - 2.0 * q3 * ax + 4.0 * q0q0 * q1 - 2.0 * q0 * ay - 4.0 * q1
+                + 8.0 * q1 * q1q1
+                + 8.0 * q1 * q2q2
+                + 4.0 * q1 * az;
+            let s2 = 4.0 * q0q0 * q2 + 2.0 * q0 * ax + 4.0 * q2 * q3q3 - 2.0 * q3 * ay - 4.0 * q2
+                + 8.0 * q2 * q1q1
+                + 8.0 * q2 * q2q2
+                + 4.0 * q2 * az;
+            let s3 = 4.0 * q1q1 * q3 - 2.0 * q1 * ax + 4.0 * q2q2 * q3 - 2.0 * q2 * ay;
+
+            let norm = sqrtf(s0 * s0 + s1 * s1 + s2 * s2 + s3 * s3);
+            if norm != 0.0 {
+                q_dot0 -= self.beta * s0 / norm;
+                q_dot1 -= self.beta * s1 / norm;
+                q_dot2 -= self.beta * s2 / norm;
+                q_dot3 -= self.beta * s3 / norm;
+            }
+        }
+
+        let dt = self.sample_period;
+        self.q = Quaternion {
+            w: q0 + q_dot0 * dt,
+            x: q1 + q_dot1 * dt,
+            y: q2 + q_dot2 * dt,
+            z: q3 + q_dot3 * dt,
+        };
+        self.q.normalize();
+        self.q.to_euler()
+    }
+
+    /// 当前姿态四元数
+    pub fn quaternion(&self) -> Quaternion {
+        self.q
+    }
+
+    /// 当前姿态角
+    pub fn angles(&self) -> EulerAngles {
+        self.q.to_euler()
+    }
+
+    /// 重置姿态
+    pub fn reset(&mut self) {
+        self.q = Quaternion::default();
+    }
+}
+
+impl AttitudeFilter for MadgwickFilter {
+    fn update(&mut self, data: &ScaledData) -> EulerAngles {
+        MadgwickFilter::update(self, data)
+    }
+
+    fn angles(&self) -> EulerAngles {
+        MadgwickFilter::angles(self)
+    }
+
+    fn reset(&mut self) {
+        MadgwickFilter::reset(self)
+    }
+}
+
+/// Mahony 互补四元数滤波
+/// 使用 PI 控制器将加速度方向误差反馈到角速度
+pub struct MahonyFilter {
+    /// 采样周期，单位：s
+    sample_period: f32,
+    /// 比例增益，典型值：1.0
+    kp: f32,
+    /// 积分增益，典型值：0.0
+    ki: f32,
+    /// 角速度误差积分，单位：rad/s
+    integral: (f32, f32, f32),
+    q: Quaternion,
+}
+
+impl MahonyFilter {
+    /// 创建 Mahony 滤波器
+    /// sample_period: 采样周期，单位：s
+    /// kp: 比例增益
+    /// ki: 积分增益，用于消除陀螺仪零偏，为 0 时关闭积分
+    pub fn new(sample_period: f32, kp: f32, ki: f32) -> Self {
+        MahonyFilter {
+            sample_period,
+            kp,
+            ki,
+            integral: (0.0, 0.0, 0.0),
+            q: Quaternion::default(),
+        }
+    }
+
+    /// 输入物理单位数据并更新姿态角
+    pub fn update(&mut self, data: &ScaledData) -> EulerAngles {
+        let Quaternion {
+            w: q0,
+            x: q1,
+            y: q2,
+            z: q3,
+        } = self.q;
+        let dt = self.sample_period;
+        let mut gx = data.gyro_x * DEG_TO_RAD;
+        let mut gy = data.gyro_y * DEG_TO_RAD;
+        let mut gz = data.gyro_z * DEG_TO_RAD;
+
+        if let Some((ax, ay, az)) = normalize3(data.acc_x, data.acc_y, data.acc_z) {
+            // 由当前姿态估计的重力方向（的一半）
+            let half_vx = q1 * q3 - q0 * q2;
+            let half_vy = q0 * q1 + q2 * q3;
+            let half_vz = q0 * q0 - 0.5 + q3 * q3;
+
+            // 测量方向与估计方向的叉积即为误差
+            let half_ex = ay * half_vz - az * half_vy;
+            let half_ey = az * half_vx - ax * half_vz;
+            let half_ez = ax * half_vy - ay * half_vx;
+
+            if self.ki > 0.0 {
+                self.integral.0 += 2.0 * self.ki * half_ex * dt;
+                self.integral.1 += 2.0 * self.ki * half_ey * dt;
+                self.integral.2 += 2.0 * self.ki * half_ez * dt;
+                gx += self.integral.0;
+                gy += self.integral.1;
+                gz += self.integral.2;
+            } else {
+                self.integral = (0.0, 0.0, 0.0);
+            }
+
+            gx += 2.0 * self.kp * half_ex;
+            gy += 2.0 * self.kp * half_ey;
+            gz += 2.0 * self.kp * half_ez;
+        }
+
+        // 积分四元数变化率
+        gx *= 0.5 * dt;
+        gy *= 0.5 * dt;
+        gz *= 0.5 * dt;
+        self.q = Quaternion {
+            w: q0 + (-q1 * gx - q2 * gy - q3 * gz),
+            x: q1 + (q0 * gx + q2 * gz - q3 * gy),
+            y: q2 + (q0 * gy - q1 * gz + q3 * gx),
+            z: q3 + (q0 * gz + q1 * gy - q2 * gx),
+        };
+        self.q.normalize();
+        self.q.to_euler()
+    }
+
+    /// 当前姿态四元数
+    pub fn quaternion(&self) -> Quaternion {
+        self.q
+    }
+
+    /// 当前姿态角
+    pub fn angles(&self) -> EulerAngles {
+        self.q.to_euler()
+    }
+
+    /// 重置姿态
+    pub fn reset(&mut self) {
+        self.q = Quaternion::default();
+        self.integral = (0.0, 0.0, 0.0);
+    }
+}
+
+impl AttitudeFilter for MahonyFilter {
+    fn update(&mut self, data: &ScaledData) -> EulerAngles {
+        MahonyFilter::update(self, data)
+    }
+
+    fn angles(&self) -> EulerAngles {
+        MahonyFilter::angles(self)
+    }
+
+    fn reset(&mut self) {
+        MahonyFilter::reset(self)
+    }
+}
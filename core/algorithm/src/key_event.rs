@@ -0,0 +1,158 @@
+//! 按键事件状态机
+//! 输入周期采样的按下状态，经过消抖后输出按下、松开、单击、双击、长按及连发事件。
+//! 不涉及引脚读取，可在主机上使用模拟的采样序列测试。
+//! ```rust
+//! use algorithm::key_event::{EventKind, KeyState, KeyTiming};
+//!
+//! let timing = KeyTiming::default();
+//! let mut state = KeyState::default();
+//!
+//! // 每 10ms 采样一次按键
+//! let raw_pressed = true;
+//! state.update(raw_pressed, 10, &timing, |kind| {
+//!     if kind == EventKind::Click {
+//!         // 单击
+//!     }
+//! });
+//! ```
+
+/// 按键事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// 按下(消抖后)
+    Press,
+    /// 松开(消抖后)
+    Release,
+    /// 单击，开启双击检测时在双击间隔超时后产生
+    Click,
+    /// 双击
+    DoubleClick,
+    /// 长按，按住超过长按时间时产生一次
+    LongPress,
+    /// 连发，长按后按住不放时周期产生
+    Repeat,
+}
+
+/// 按键时间参数，单位：ms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyTiming {
+    /// 消抖时间
+    pub debounce_ms: u16,
+    /// 双击间隔，为 0 时关闭双击检测，松开后立即产生单击事件
+    pub double_click_ms: u16,
+    /// 长按时间，为 0 时关闭长按检测
+    pub long_press_ms: u16,
+    /// 连发间隔，为 0 时关闭连发
+    pub repeat_ms: u16,
+}
+
+impl Default for KeyTiming {
+    fn default() -> Self {
+        KeyTiming {
+            debounce_ms: 20,
+            double_click_ms: 250,
+            long_press_ms: 1000,
+            repeat_ms: 100,
+        }
+    }
+}
+
+/// 单个按键的状态
+#[derive(Debug, Default, Clone, Copy)]
+pub struct KeyState {
+    /// 消抖后的按下状态
+    pressed: bool,
+    /// 电平与稳定状态不一致的持续时间
+    debounce: u16,
+    /// 按住的时间
+    held: u16,
+    /// 松开后等待第二次按下的时间
+    gap: u16,
+    /// 连发计时
+    repeat: u16,
+    /// 已松开一次，等待判断单击或双击
+    click_pending: bool,
+    /// 双击的第二次按下
+    second_press: bool,
+    /// 本次按下已产生长按事件
+    long_fired: bool,
+}
+
+impl KeyState {
+    /// 消抖后的按下状态
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    /// 更新按键状态
+    /// raw_pressed: 当前采样的按下状态
+    /// dt: 距离上次更新的时间，单位：ms
+    /// emit: 事件回调
+    pub fn update<F>(&mut self, raw_pressed: bool, dt: u16, timing: &KeyTiming, mut emit: F)
+    where
+        F: FnMut(EventKind),
+    {
+        // 消抖，电平持续变化超过消抖时间才认为状态改变
+        let mut changed = false;
+        if raw_pressed != self.pressed {
+            self.debounce = self.debounce.saturating_add(dt);
+            if self.debounce >= timing.debounce_ms {
+                self.pressed = raw_pressed;
+                self.debounce = 0;
+                changed = true;
+            }
+        } else {
+            self.debounce = 0;
+        }
+
+        if changed && self.pressed {
+            emit(EventKind::Press);
+            self.held = 0;
+            self.repeat = 0;
+            self.long_fired = false;
+            if self.click_pending {
+                self.click_pending = false;
+                self.second_press = true;
+            }
+        } else if changed {
+            emit(EventKind::Release);
+            if self.long_fired {
+                // 长按后松开不产生单击
+                self.second_press = false;
+            } else if self.second_press {
+                self.second_press = false;
+                emit(EventKind::DoubleClick);
+            } else if timing.double_click_ms == 0 {
+                emit(EventKind::Click);
+            } else {
+                self.click_pending = true;
+                self.gap = 0;
+            }
+        } else if self.pressed {
+            self.held = self.held.saturating_add(dt);
+            if !self.long_fired {
+                if timing.long_press_ms != 0 && self.held >= timing.long_press_ms {
+                    self.long_fired = true;
+                    if self.second_press {
+                        // 第二次按下变为长按，第一次按下仍算单击
+                        self.second_press = false;
+                        emit(EventKind::Click);
+                    }
+                    emit(EventKind::LongPress);
+                }
+            } else if timing.repeat_ms != 0 {
+                self.repeat = self.repeat.saturating_add(dt);
+                if self.repeat >= timing.repeat_ms {
+                    self.repeat = 0;
+                    emit(EventKind::Repeat);
+                }
+            }
+        } else if self.click_pending {
+            self.gap = self.gap.saturating_add(dt);
+            if self.gap >= timing.double_click_ms {
+                self.click_pending = false;
+                emit(EventKind::Click);
+            }
+        }
+    }
+}
@@ -0,0 +1,7 @@
+//! 与外设无关的通用算法
+//! 不依赖具体芯片，可在主机上测试。
+#![no_std]
+
+pub mod fusion;
+pub mod key_event;
+pub mod soft_timer;
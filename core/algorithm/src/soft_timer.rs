@@ -0,0 +1,304 @@
+//! 软件定时器
+//! 由一个硬件定时中断(或主循环中的单调时钟)驱动，管理多个命名的周期及单次定时器。
+//! 到期时调用回调函数，或置位事件标志由主循环查询。
+//! 同一次 `tick` 中多个定时器到期时按到期时间先后处理，时间相同时按添加顺序处理。
+//!
+//! 在固件中定时器组通常放在 `Mutex<RefCell<SoftTimers<N>>>` 中，由定时中断调用 `tick`。
+//! 回调在 `tick` 内执行，此时定时器组已被借用，回调中不能再借用定时器组，
+//! 只能修改原子变量等；需要启停定时器时在主循环中取出事件标志后处理。
+//! ```rust
+//! use core::sync::atomic::{AtomicU32, Ordering};
+//! use algorithm::soft_timer::{Mode, SoftTimers, TimerId};
+//!
+//! static SECONDS: AtomicU32 = AtomicU32::new(0);
+//!
+//! fn on_second(_id: TimerId) {
+//!     SECONDS.fetch_add(1, Ordering::Relaxed);
+//! }
+//!
+//! let mut timers: SoftTimers<4> = SoftTimers::new();
+//! let blink = timers.add("blink", Mode::Periodic, 500).unwrap();
+//! let seconds = timers
+//!     .add_with_callback("seconds", Mode::Periodic, 1000, on_second)
+//!     .unwrap();
+//! timers.start(blink).unwrap();
+//! timers.start(seconds).unwrap();
+//!
+//! // 每 1ms 的定时中断中
+//! timers.tick(1);
+//!
+//! // 主循环中查询事件标志
+//! if timers.take_event(blink) {
+//!     // 翻转 LED
+//! }
+//! ```
+use heapless::Vec;
+
+/// 定时器模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// 到期后停止
+    OneShot,
+    /// 到期后自动重装
+    Periodic,
+}
+
+/// 定时器句柄，即添加顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimerId(u8);
+
+impl TimerId {
+    /// 定时器序号
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// 到期回调，在调用 `tick` 的上下文中执行
+pub type Callback = fn(TimerId);
+
+/// 软件定时器错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// 定时器数量已满
+    Full,
+    /// 名称已存在
+    Duplicate,
+    /// 定时器不存在
+    NotFound,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    name: &'static str,
+    mode: Mode,
+    /// 周期，单位：ms
+    period_ms: u32,
+    /// 距离到期的时间，单位：ms
+    remaining_ms: u32,
+    running: bool,
+    /// 未处理的到期次数
+    pending: u32,
+    callback: Option<Callback>,
+}
+
+/// 软件定时器组
+/// N: 定时器最大数量
+pub struct SoftTimers<const N: usize> {
+    slots: Vec<Slot, N>,
+    /// 累计时间，单位：ms
+    now_ms: u64,
+}
+
+impl<const N: usize> Default for SoftTimers<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> SoftTimers<N> {
+    pub const fn new() -> Self {
+        SoftTimers {
+            slots: Vec::new(),
+            now_ms: 0,
+        }
+    }
+
+    /// 添加定时器，添加后处于停止状态
+    /// period_ms: 周期，最小为 1ms
+    pub fn add(
+        &mut self,
+        name: &'static str,
+        mode: Mode,
+        period_ms: u32,
+    ) -> Result<TimerId, Error> {
+        self.insert(name, mode, period_ms, None)
+    }
+
+    /// 添加带回调的定时器，到期时同时置位事件标志
+    pub fn add_with_callback(
+        &mut self,
+        name: &'static str,
+        mode: Mode,
+        period_ms: u32,
+        callback: Callback,
+    ) -> Result<TimerId, Error> {
+        self.insert(name, mode, period_ms, Some(callback))
+    }
+
+    fn insert(
+        &mut self,
+        name: &'static str,
+        mode: Mode,
+        period_ms: u32,
+        callback: Option<Callback>,
+    ) -> Result<TimerId, Error> {
+        if self.find(name).is_some() {
+            return Err(Error::Duplicate);
+        }
+        if self.slots.len() > u8::MAX as usize {
+            return Err(Error::Full);
+        }
+        let id = TimerId(self.slots.len() as u8);
+        let period_ms = period_ms.max(1);
+        self.slots
+            .push(Slot {
+                name,
+                mode,
+                period_ms,
+                remaining_ms: period_ms,
+                running: false,
+                pending: 0,
+                callback,
+            })
+            .map_err(|_| Error::Full)?;
+        Ok(id)
+    }
+
+    fn slot(&self, id: TimerId) -> Result<&Slot, Error> {
+        self.slots.get(id.index()).ok_or(Error::NotFound)
+    }
+
+    fn slot_mut(&mut self, id: TimerId) -> Result<&mut Slot, Error> {
+        self.slots.get_mut(id.index()).ok_or(Error::NotFound)
+    }
+
+    /// 按名称查找定时器
+    pub fn find(&self, name: &str) -> Option<TimerId> {
+        self.slots
+            .iter()
+            .position(|slot| slot.name == name)
+            .map(|index| TimerId(index as u8))
+    }
+
+    /// 定时器名称
+    pub fn name(&self, id: TimerId) -> Result<&'static str, Error> {
+        Ok(self.slot(id)?.name)
+    }
+
+    /// 定时器数量
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// 是否没有定时器
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// 启动定时器，已运行时不影响剩余时间
+    pub fn start(&mut self, id: TimerId) -> Result<(), Error> {
+        self.slot_mut(id)?.running = true;
+        Ok(())
+    }
+
+    /// 停止定时器，保留剩余时间及未处理的事件
+    pub fn stop(&mut self, id: TimerId) -> Result<(), Error> {
+        self.slot_mut(id)?.running = false;
+        Ok(())
+    }
+
+    /// 重新开始计时并启动，清除未处理的事件
+    pub fn reset(&mut self, id: TimerId) -> Result<(), Error> {
+        let slot = self.slot_mut(id)?;
+        slot.remaining_ms = slot.period_ms;
+        slot.pending = 0;
+        slot.running = true;
+        Ok(())
+    }
+
+    /// 修改周期，从下一次重装开始生效
+    pub fn set_period(&mut self, id: TimerId, period_ms: u32) -> Result<(), Error> {
+        self.slot_mut(id)?.period_ms = period_ms.max(1);
+        Ok(())
+    }
+
+    /// 周期，单位：ms
+    pub fn period(&self, id: TimerId) -> Result<u32, Error> {
+        Ok(self.slot(id)?.period_ms)
+    }
+
+    /// 是否正在运行
+    pub fn is_running(&self, id: TimerId) -> Result<bool, Error> {
+        Ok(self.slot(id)?.running)
+    }
+
+    /// 距离到期的时间，单位：ms
+    pub fn remaining(&self, id: TimerId) -> Result<u32, Error> {
+        Ok(self.slot(id)?.remaining_ms)
+    }
+
+    /// 未处理的到期次数
+    pub fn pending(&self, id: TimerId) -> Result<u32, Error> {
+        Ok(self.slot(id)?.pending)
+    }
+
+    /// 取出一次到期事件，没有事件或定时器不存在时返回 false
+    pub fn take_event(&mut self, id: TimerId) -> bool {
+        match self.slot_mut(id) {
+            Ok(slot) if slot.pending > 0 => {
+                slot.pending -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 累计时间，单位：ms
+    pub fn now_ms(&self) -> u64 {
+        self.now_ms
+    }
+
+    /// 最近一个运行中定时器的剩余时间，可用于计算下一次唤醒时间
+    pub fn next_expiry(&self) -> Option<u32> {
+        self.slots
+            .iter()
+            .filter(|slot| slot.running)
+            .map(|slot| slot.remaining_ms)
+            .min()
+    }
+
+    /// 时间前进 dt_ms，按到期顺序处理定时器，返回到期次数
+    /// dt_ms 大于周期时周期定时器会连续到期多次
+    pub fn tick(&mut self, dt_ms: u32) -> u32 {
+        let mut budget = dt_ms;
+        let mut expired = 0;
+        loop {
+            // 剩余时间最短的定时器，相同时取序号最小的
+            let next = self
+                .slots
+                .iter()
+                .enumerate()
+                .filter(|(_, slot)| slot.running && slot.remaining_ms <= budget)
+                .min_by_key(|(index, slot)| (slot.remaining_ms, *index))
+                .map(|(index, slot)| (index, slot.remaining_ms));
+            let Some((index, step)) = next else {
+                break;
+            };
+
+            self.advance(step);
+            budget -= step;
+
+            let slot = &mut self.slots[index];
+            slot.pending = slot.pending.saturating_add(1);
+            slot.remaining_ms = slot.period_ms;
+            if slot.mode == Mode::OneShot {
+                slot.running = false;
+            }
+            expired += 1;
+            if let Some(callback) = slot.callback {
+                callback(TimerId(index as u8));
+            }
+        }
+        self.advance(budget);
+        expired
+    }
+
+    /// 所有运行中的定时器前进 dt_ms
+    fn advance(&mut self, dt_ms: u32) {
+        self.now_ms += dt_ms as u64;
+        for slot in self.slots.iter_mut().filter(|slot| slot.running) {
+            slot.remaining_ms -= dt_ms.min(slot.remaining_ms);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! 姿态解算测试
+//! 使用按真实姿态生成的采样序列验证滤波器收敛及积分
+use algorithm::fusion::{
+    AttitudeFilter, ComplementaryFilter, MadgwickFilter, MahonyFilter, ScaledData,
+};
+
+/// 采样周期，单位：s
+const DT: f32 = 0.01;
+
+/// 按横滚角、俯仰角生成静止时的加速度，单位：°
+fn still(roll: f32, pitch: f32) -> ScaledData {
+    let (roll, pitch) = (roll.to_radians(), pitch.to_radians());
+    ScaledData {
+        acc_x: -pitch.sin(),
+        acc_y: roll.sin() * pitch.cos(),
+        acc_z: roll.cos() * pitch.cos(),
+        ..Default::default()
+    }
+}
+
+/// 确定性的伪随机噪声，范围 -amplitude~amplitude
+struct Noise(u32);
+
+impl Noise {
+    fn next(&mut self, amplitude: f32) -> f32 {
+        self.0 = self.0.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        ((self.0 >> 8) as f32 / (1 << 24) as f32 * 2.0 - 1.0) * amplitude
+    }
+}
+
+fn filters() -> [(&'static str, Box<dyn AttitudeFilter>); 3] {
+    [
+        (
+            "complementary",
+            Box::new(ComplementaryFilter::new(DT, 0.98)),
+        ),
+        ("madgwick", Box::new(MadgwickFilter::new(DT, 0.1))),
+        ("mahony", Box::new(MahonyFilter::new(DT, 1.0, 0.0))),
+    ]
+}
+
+fn assert_near(name: &str, what: &str, actual: f32, expected: f32, tolerance: f32) {
+    assert!(
+        (actual - expected).abs() < tolerance,
+        "{name}: {what} = {actual}, expected {expected} ± {tolerance}"
+    );
+}
+
+#[test]
+fn level_still_board_converges_to_zero() {
+    for (name, mut filter) in filters() {
+        // 先倾斜放置，再放平
+        for _ in 0..500 {
+            filter.update(&still(30.0, -20.0));
+        }
+        let mut noise = Noise(1);
+        for _ in 0..2000 {
+            let mut data = still(0.0, 0.0);
+            data.acc_x += noise.next(0.02);
+            data.acc_y += noise.next(0.02);
+            data.gyro_x = noise.next(0.5);
+            data.gyro_y = noise.next(0.5);
+            filter.update(&data);
+        }
+
+        let angles = filter.angles();
+        assert_near(name, "roll", angles.roll, 0.0, 1.0);
+        assert_near(name, "pitch", angles.pitch, 0.0, 1.0);
+    }
+}
+
+#[test]
+fn tilted_still_board_converges_to_tilt() {
+    for (name, mut filter) in filters() {
+        for _ in 0..2000 {
+            filter.update(&still(30.0, -20.0));
+        }
+
+        let angles = filter.angles();
+        assert_near(name, "roll", angles.roll, 30.0, 0.5);
+        assert_near(name, "pitch", angles.pitch, -20.0, 0.5);
+    }
+}
+
+#[test]
+fn constant_gyro_rate_integrates_yaw() {
+    for (name, mut filter) in filters() {
+        // 10°/s 持续 3s
+        let data = ScaledData {
+            gyro_z: 10.0,
+            ..still(0.0, 0.0)
+        };
+        for _ in 0..300 {
+            filter.update(&data);
+        }
+
+        let angles = filter.angles();
+        assert_near(name, "yaw", angles.yaw, 30.0, 0.5);
+        assert_near(name, "roll", angles.roll, 0.0, 0.1);
+        assert_near(name, "pitch", angles.pitch, 0.0, 0.1);
+    }
+}
+
+#[test]
+fn mahony_matches_madgwick() {
+    let mut madgwick = MadgwickFilter::new(DT, 0.1);
+    let mut mahony = MahonyFilter::new(DT, 1.0, 0.0);
+    let mut noise = Noise(7);
+
+    // 以 10°/s 绕 X 轴转到 20°，保持后再转回
+    let mut roll = 0.0;
+    for step in 0..1000 {
+        let rate = match step {
+            0..=199 => 10.0,
+            500..=699 => -10.0,
+            _ => 0.0,
+        };
+        roll += rate * DT;
+
+        let mut data = still(roll, 0.0);
+        data.acc_x += noise.next(0.01);
+        data.acc_y += noise.next(0.01);
+        data.gyro_x = rate + noise.next(0.2);
+        data.gyro_y = noise.next(0.2);
+
+        let a = madgwick.update(&data);
+        let b = mahony.update(&data);
+        assert_near("mahony", "roll", b.roll, a.roll, 1.0);
+        assert_near("mahony", "pitch", b.pitch, a.pitch, 1.0);
+        assert_near("madgwick", "roll", a.roll, roll, 1.0);
+    }
+}
@@ -0,0 +1,91 @@
+//! 按键事件状态机测试
+//! 以固定周期输入模拟的按键电平序列，检查输出的事件序列
+use algorithm::key_event::{EventKind, KeyState, KeyTiming};
+
+use EventKind::*;
+
+/// 采样周期，单位：ms
+const TICK_MS: u16 = 10;
+
+/// 按顺序输入电平序列，每段为 (是否按下, 持续时间 ms)，返回产生的事件
+fn run(timing: &KeyTiming, steps: &[(bool, u16)]) -> Vec<EventKind> {
+    let mut state = KeyState::default();
+    let mut events = Vec::new();
+    for &(pressed, ms) in steps {
+        for _ in 0..ms / TICK_MS {
+            state.update(pressed, TICK_MS, timing, |kind| events.push(kind));
+        }
+    }
+    events
+}
+
+/// 关闭连发，只检查单击、双击及长按
+fn no_repeat() -> KeyTiming {
+    KeyTiming {
+        repeat_ms: 0,
+        ..KeyTiming::default()
+    }
+}
+
+#[test]
+fn glitch_shorter_than_debounce_is_ignored() {
+    let events = run(&KeyTiming::default(), &[(true, 10), (false, 500)]);
+    assert!(events.is_empty());
+}
+
+#[test]
+fn click_after_double_click_window() {
+    let events = run(&no_repeat(), &[(true, 100), (false, 400)]);
+    assert_eq!(events, [Press, Release, Click]);
+}
+
+#[test]
+fn click_without_double_click_detection() {
+    let timing = KeyTiming {
+        double_click_ms: 0,
+        ..no_repeat()
+    };
+    // 松开后立即产生单击，不等待双击间隔
+    let events = run(&timing, &[(true, 100), (false, 30)]);
+    assert_eq!(events, [Press, Release, Click]);
+}
+
+#[test]
+fn double_click() {
+    let events = run(
+        &no_repeat(),
+        &[(true, 100), (false, 100), (true, 100), (false, 400)],
+    );
+    assert_eq!(events, [Press, Release, Press, Release, DoubleClick]);
+}
+
+#[test]
+fn second_press_after_window_is_two_clicks() {
+    let events = run(
+        &no_repeat(),
+        &[(true, 100), (false, 400), (true, 100), (false, 400)],
+    );
+    assert_eq!(events, [Press, Release, Click, Press, Release, Click]);
+}
+
+#[test]
+fn long_press_without_click() {
+    let events = run(&no_repeat(), &[(true, 1200), (false, 400)]);
+    assert_eq!(events, [Press, LongPress, Release]);
+}
+
+#[test]
+fn long_press_repeats_while_held() {
+    // 20ms 消抖后开始计时，1020ms 长按，之后每 100ms 连发一次
+    let events = run(&KeyTiming::default(), &[(true, 1350), (false, 400)]);
+    assert_eq!(events, [Press, LongPress, Repeat, Repeat, Repeat, Release]);
+}
+
+#[test]
+fn click_then_long_press_keeps_first_click() {
+    let events = run(
+        &no_repeat(),
+        &[(true, 100), (false, 100), (true, 1200), (false, 400)],
+    );
+    assert_eq!(events, [Press, Release, Press, Click, LongPress, Release]);
+}
@@ -0,0 +1,172 @@
+//! 软件定时器测试
+//! 使用模拟的单调时钟驱动 `tick`，通过回调记录到期顺序
+use std::cell::RefCell;
+
+use algorithm::soft_timer::{Error, Mode, SoftTimers, TimerId};
+
+thread_local! {
+    /// 回调记录的到期顺序
+    static EXPIRED: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(id: TimerId) {
+    EXPIRED.with(|expired| expired.borrow_mut().push(id.index()));
+}
+
+fn take_expired() -> Vec<usize> {
+    EXPIRED.with(|expired| expired.take())
+}
+
+/// 模拟的单调时钟，将时间戳换算为两次 `tick` 之间的间隔
+struct FakeClock {
+    last_ms: u32,
+}
+
+impl FakeClock {
+    fn new() -> Self {
+        FakeClock { last_ms: 0 }
+    }
+
+    /// 时钟走到 now_ms 并驱动定时器，返回到期次数
+    fn advance_to<const N: usize>(&mut self, timers: &mut SoftTimers<N>, now_ms: u32) -> u32 {
+        let dt_ms = now_ms - self.last_ms;
+        self.last_ms = now_ms;
+        timers.tick(dt_ms)
+    }
+}
+
+#[test]
+fn expiry_order_follows_deadline_then_insertion() {
+    let mut timers: SoftTimers<4> = SoftTimers::new();
+    let a = timers
+        .add_with_callback("a", Mode::OneShot, 30, record)
+        .unwrap();
+    let b = timers
+        .add_with_callback("b", Mode::Periodic, 10, record)
+        .unwrap();
+    let c = timers
+        .add_with_callback("c", Mode::OneShot, 30, record)
+        .unwrap();
+    let d = timers
+        .add_with_callback("d", Mode::OneShot, 20, record)
+        .unwrap();
+    for id in [d, c, b, a] {
+        timers.start(id).unwrap();
+    }
+    take_expired();
+
+    let mut clock = FakeClock::new();
+    assert_eq!(clock.advance_to(&mut timers, 30), 6);
+    // 30ms 时 a、b、c 同时到期，按添加顺序处理
+    assert_eq!(take_expired(), [1, 1, 3, 0, 1, 2]);
+    assert_eq!(timers.now_ms(), 30);
+}
+
+#[test]
+fn one_shot_stops_and_periodic_reloads() {
+    let mut timers: SoftTimers<2> = SoftTimers::new();
+    let once = timers.add("once", Mode::OneShot, 10).unwrap();
+    let every = timers.add("every", Mode::Periodic, 10).unwrap();
+    timers.start(once).unwrap();
+    timers.start(every).unwrap();
+
+    let mut clock = FakeClock::new();
+    for now_ms in 1..=10 {
+        clock.advance_to(&mut timers, now_ms);
+    }
+    assert!(timers.take_event(once));
+    assert!(timers.take_event(every));
+    assert_eq!(timers.is_running(once), Ok(false));
+    assert_eq!(timers.is_running(every), Ok(true));
+    assert_eq!(timers.remaining(once), Ok(10));
+    assert_eq!(timers.remaining(every), Ok(10));
+
+    assert_eq!(clock.advance_to(&mut timers, 20), 1);
+    assert!(!timers.take_event(once));
+    assert!(timers.take_event(every));
+}
+
+#[test]
+fn long_tick_expires_periodic_timer_multiple_times() {
+    let mut timers: SoftTimers<1> = SoftTimers::new();
+    let id = timers.add("fast", Mode::Periodic, 10).unwrap();
+    timers.start(id).unwrap();
+
+    let mut clock = FakeClock::new();
+    assert_eq!(clock.advance_to(&mut timers, 35), 3);
+    assert_eq!(timers.pending(id), Ok(3));
+    assert_eq!(timers.remaining(id), Ok(5));
+    for _ in 0..3 {
+        assert!(timers.take_event(id));
+    }
+    assert!(!timers.take_event(id));
+
+    // 单次定时器只到期一次
+    let mut timers: SoftTimers<1> = SoftTimers::new();
+    let id = timers.add("once", Mode::OneShot, 10).unwrap();
+    timers.start(id).unwrap();
+    assert_eq!(timers.tick(35), 1);
+    assert_eq!(timers.pending(id), Ok(1));
+}
+
+#[test]
+fn stop_keeps_and_reset_clears_pending_events() {
+    let mut timers: SoftTimers<1> = SoftTimers::new();
+    let id = timers.add("t", Mode::Periodic, 10).unwrap();
+    timers.start(id).unwrap();
+
+    let mut clock = FakeClock::new();
+    clock.advance_to(&mut timers, 14);
+    timers.stop(id).unwrap();
+    assert_eq!(timers.pending(id), Ok(1));
+    assert_eq!(timers.remaining(id), Ok(6));
+
+    // 停止期间不计时
+    assert_eq!(clock.advance_to(&mut timers, 100), 0);
+    assert_eq!(timers.remaining(id), Ok(6));
+
+    // 启动后继续剩余时间
+    timers.start(id).unwrap();
+    assert_eq!(clock.advance_to(&mut timers, 106), 1);
+    assert_eq!(timers.pending(id), Ok(2));
+
+    timers.reset(id).unwrap();
+    assert_eq!(timers.pending(id), Ok(0));
+    assert_eq!(timers.remaining(id), Ok(10));
+    assert_eq!(timers.is_running(id), Ok(true));
+}
+
+#[test]
+fn next_expiry_tracks_running_timers() {
+    let mut timers: SoftTimers<2> = SoftTimers::new();
+    assert_eq!(timers.next_expiry(), None);
+
+    let slow = timers.add("slow", Mode::Periodic, 50).unwrap();
+    let fast = timers.add("fast", Mode::OneShot, 20).unwrap();
+    assert_eq!(timers.next_expiry(), None);
+
+    timers.start(slow).unwrap();
+    timers.start(fast).unwrap();
+    assert_eq!(timers.next_expiry(), Some(20));
+
+    let mut clock = FakeClock::new();
+    clock.advance_to(&mut timers, 15);
+    assert_eq!(timers.next_expiry(), Some(5));
+
+    // 单次定时器到期后停止，不再参与计算
+    clock.advance_to(&mut timers, 20);
+    assert_eq!(timers.next_expiry(), Some(30));
+
+    timers.stop(slow).unwrap();
+    assert_eq!(timers.next_expiry(), None);
+}
+
+#[test]
+fn names_are_unique_and_capacity_is_checked() {
+    let mut timers: SoftTimers<1> = SoftTimers::new();
+    let id = timers.add("a", Mode::Periodic, 0).unwrap();
+    assert_eq!(timers.period(id), Ok(1));
+    assert_eq!(timers.find("a"), Some(id));
+    assert_eq!(timers.add("a", Mode::Periodic, 10), Err(Error::Duplicate));
+    assert_eq!(timers.add("b", Mode::Periodic, 10), Err(Error::Full));
+}
@@ -0,0 +1,36 @@
+//! ADC 换算
+//! 典型值取自 STM32F103 数据手册，F1 系列没有出厂校准值，精度约 ±1.5°C 以上。
+use super::FULL_SCALE;
+
+/// Vrefint 典型值，单位：mV
+pub const VREFINT_MV: u32 = 1200;
+/// 25°C 时温度传感器的输出电压，单位：mV
+pub const TEMP_V25_MV: f32 = 1430.0;
+/// 温度传感器平均斜率，单位：mV/°C
+pub const TEMP_AVG_SLOPE: f32 = 4.3;
+/// 未测量 Vrefint 时假定的供电电压，单位：mV
+pub const DEFAULT_VDDA_MV: u32 = 3300;
+
+/// 根据 Vrefint 原始值计算供电电压，单位：mV
+pub fn vdda_mv(vrefint_raw: u16) -> u32 {
+    if vrefint_raw == 0 {
+        return DEFAULT_VDDA_MV;
+    }
+    VREFINT_MV * FULL_SCALE / vrefint_raw as u32
+}
+
+/// 原始值换算为电压，单位：mV
+pub fn to_millivolts(raw: u16, vdda_mv: u32) -> u32 {
+    raw as u32 * vdda_mv / FULL_SCALE
+}
+
+/// 电压换算为原始值，用于设置模拟看门狗阈值
+pub fn to_raw(millivolts: u32, vdda_mv: u32) -> u16 {
+    (millivolts * FULL_SCALE / vdda_mv.max(1)).min(FULL_SCALE) as u16
+}
+
+/// 温度传感器原始值换算为温度，单位：°C
+pub fn temperature(raw: u16, vdda_mv: u32) -> f32 {
+    let sense_mv = raw as f32 * vdda_mv as f32 / FULL_SCALE as f32;
+    (TEMP_V25_MV - sense_mv) / TEMP_AVG_SLOPE + 25.0
+}
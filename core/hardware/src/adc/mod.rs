@@ -0,0 +1,26 @@
+//! ADC 模数转换
+//! - `convert`: 原始值换算为电压、供电电压及温度
+//! - `AdcScan`: ADC1 连续扫描，DMA 循环写入多组结果后取平均值(过采样)
+//! - `AdcSampler`: TIM3 触发 ADC1 按固定采样率转换，DMA 双缓冲输出数据块
+//! - `AnalogWatchdog`: 模拟看门狗，转换结果超出阈值窗口时产生中断或唤醒睡眠
+//!
+//! 内部参考电压 Vrefint 与温度传感器分别位于通道 17、16，
+//! 将 Vrefint 加入扫描通道后可根据实际供电电压补偿测量结果。
+pub mod convert;
+pub mod scan;
+pub mod triggered;
+pub mod watchdog;
+
+pub use scan::AdcScan;
+pub use triggered::AdcSampler;
+pub use watchdog::{AnalogWatchdog, WatchdogChannel};
+
+/// 内部温度传感器通道
+pub const CHANNEL_TEMPERATURE: u8 = 16;
+/// 内部参考电压通道
+pub const CHANNEL_VREFINT: u8 = 17;
+/// 12 位 ADC 满量程
+pub const FULL_SCALE: u32 = 4095;
+
+/// ADC 上电稳定时间(tSTAB 1us)，按 72MHz 留有余量，单位：CPU 周期
+pub(crate) const POWER_UP_CYCLES: u32 = 1000;
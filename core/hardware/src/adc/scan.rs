@@ -0,0 +1,203 @@
+//! ADC1 连续扫描
+//! ADC 按通道序列连续扫描，DMA1 通道 1 循环写入 M 组结果，读取时对 M 组求平均。
+//! 引脚需事先配置为模拟输入，通道号与引脚对应关系: PA0~PA7 为 0~7，PB0~PB1 为 8~9。
+//! ```rust
+//! let _pa0 = gpioa.pa0.into_analog(&mut gpioa.crl);
+//! let _pa1 = gpioa.pa1.into_analog(&mut gpioa.crl);
+//! let adc1 = Adc::adc1(dp.ADC1, clocks);
+//! let dma_ch1 = dp.DMA1.split().1;
+//! let buffer = singleton!(: [[u16; 4]; 16] = [[0; 4]; 16]).unwrap();
+//! let channels = [0, 1, CHANNEL_VREFINT, CHANNEL_TEMPERATURE];
+//! let mut scan = AdcScan::new(adc1, dma_ch1, channels, SampleTime::T_55, buffer);
+//! scan.start();
+//!
+//! let pa0_mv = scan.millivolts(0);
+//! let temperature = scan.temperature();
+//! ```
+use super::convert;
+use super::{CHANNEL_TEMPERATURE, CHANNEL_VREFINT, POWER_UP_CYCLES};
+
+use core::sync::atomic::{self, Ordering};
+
+use stm32f1xx_hal::adc::{Adc, ChannelTimeSequence, SampleTime};
+use stm32f1xx_hal::dma::dma1;
+use stm32f1xx_hal::pac::{self, ADC1};
+
+/// ADC1 连续扫描
+/// N: 通道数，最多 16 个
+/// M: 求平均的组数
+pub struct AdcScan<const N: usize, const M: usize> {
+    adc: Adc<ADC1>,
+    dma: dma1::C1,
+    buffer: &'static mut [[u16; N]; M],
+    channels: [u8; N],
+}
+
+impl<const N: usize, const M: usize> AdcScan<N, M> {
+    /// 配置扫描序列及 DMA，创建后需调用 `start` 开始转换
+    /// 内部通道的采样时间固定为最长的 239.5 周期，以满足温度传感器 17.1us 的要求
+    pub fn new(
+        mut adc: Adc<ADC1>,
+        mut dma: dma1::C1,
+        channels: [u8; N],
+        sample_time: SampleTime,
+        buffer: &'static mut [[u16; N]; M],
+    ) -> Self {
+        assert!(N > 0 && N <= 16 && M > 0);
+
+        for &channel in channels.iter() {
+            let time = match channel {
+                CHANNEL_TEMPERATURE | CHANNEL_VREFINT => SampleTime::T_239,
+                _ => sample_time,
+            };
+            adc.set_channel_sample_time(channel, time);
+        }
+        adc.set_regular_sequence(&channels);
+        adc.set_continuous_mode(true);
+        adc.set_discontinuous_mode(None);
+
+        let regs = Self::regs();
+        // 扫描模式，DMA 请求，软件触发，开启内部通道
+        regs.cr1.modify(|_, w| w.scan().set_bit());
+        regs.cr2.modify(|_, w| {
+            w.align()
+                .right()
+                .dma()
+                .set_bit()
+                .exttrig()
+                .set_bit()
+                .extsel()
+                .swstart()
+                .tsvrefe()
+                .set_bit()
+        });
+
+        // 外设到存储器，16 位，存储器地址自增，循环模式
+        dma.set_peripheral_address(&regs.dr as *const _ as u32, false);
+        dma.ch().cr.modify(|_, w| {
+            w.mem2mem()
+                .clear_bit()
+                .pl()
+                .high()
+                .msize()
+                .bits16()
+                .psize()
+                .bits16()
+                .circ()
+                .set_bit()
+                .dir()
+                .clear_bit()
+        });
+
+        AdcScan {
+            adc,
+            dma,
+            buffer,
+            channels,
+        }
+    }
+
+    fn regs() -> &'static pac::adc1::RegisterBlock {
+        unsafe { &*ADC1::ptr() }
+    }
+
+    /// 开始连续扫描，DMA 从缓冲区开头写入
+    pub fn start(&mut self) {
+        self.stop();
+        self.dma
+            .set_memory_address(self.buffer.as_ptr() as u32, true);
+        self.dma.set_transfer_length(N * M);
+        atomic::compiler_fence(Ordering::Release);
+        self.dma.start();
+
+        // 上电后等待稳定再触发转换
+        let regs = Self::regs();
+        regs.cr2.modify(|_, w| w.cont().set_bit().adon().set_bit());
+        cortex_m::asm::delay(POWER_UP_CYCLES);
+        regs.cr2.modify(|_, w| w.swstart().set_bit());
+    }
+
+    /// 停止扫描，ADC 断电以中止当前转换
+    pub fn stop(&mut self) {
+        let regs = Self::regs();
+        regs.cr2
+            .modify(|_, w| w.cont().clear_bit().adon().clear_bit());
+        self.dma.stop();
+        regs.sr
+            .modify(|_, w| w.strt().clear_bit().eoc().clear_bit());
+    }
+
+    /// 自校准，校准期间暂停扫描
+    /// 上电后 `Adc::adc1` 已校准一次，温度变化较大时可重新校准
+    pub fn calibrate(&mut self) {
+        self.stop();
+        // 校准前 ADC 需上电至少 2 个 ADC 时钟周期
+        let regs = Self::regs();
+        regs.cr2.modify(|_, w| w.adon().set_bit());
+        cortex_m::asm::delay(POWER_UP_CYCLES);
+
+        regs.cr2.modify(|_, w| w.rstcal().set_bit());
+        while regs.cr2.read().rstcal().bit_is_set() {}
+        regs.cr2.modify(|_, w| w.cal().set_bit());
+        while regs.cr2.read().cal().bit_is_set() {}
+
+        // 断电后由 `start` 重新上电，避免再次置位 ADON 直接触发转换
+        regs.cr2.modify(|_, w| w.adon().clear_bit());
+        self.start();
+    }
+
+    /// 扫描通道
+    pub fn channels(&self) -> &[u8; N] {
+        &self.channels
+    }
+
+    /// 指定通道在扫描序列中的位置
+    fn position(&self, channel: u8) -> Option<usize> {
+        self.channels.iter().position(|&c| c == channel)
+    }
+
+    /// 平均后的原始值，启动后需等待 M 组扫描完成结果才准确
+    /// index: 通道在扫描序列中的位置，超出扫描通道数时 panic
+    pub fn raw(&self, index: usize) -> u16 {
+        assert!(index < N, "通道位置超出扫描通道数");
+        let base = self.buffer.as_ptr() as *const u16;
+        let mut sum = 0_u32;
+        for group in 0..M {
+            // DMA 在后台写入，需要易失性读取
+            sum += unsafe { core::ptr::read_volatile(base.add(group * N + index)) } as u32;
+        }
+        (sum / M as u32) as u16
+    }
+
+    /// 供电电压，单位：mV
+    /// 扫描通道不包含 Vrefint 时返回默认的 3300mV
+    pub fn vdda_mv(&self) -> u32 {
+        match self.position(CHANNEL_VREFINT) {
+            Some(index) => convert::vdda_mv(self.raw(index)),
+            None => convert::DEFAULT_VDDA_MV,
+        }
+    }
+
+    /// 按供电电压补偿后的电压，单位：mV
+    /// index: 通道在扫描序列中的位置，超出扫描通道数时 panic
+    pub fn millivolts(&self, index: usize) -> u32 {
+        convert::to_millivolts(self.raw(index), self.vdda_mv())
+    }
+
+    /// 芯片温度，单位：°C
+    /// 扫描通道不包含温度传感器时返回 None
+    pub fn temperature(&self) -> Option<f32> {
+        let index = self.position(CHANNEL_TEMPERATURE)?;
+        Some(convert::temperature(self.raw(index), self.vdda_mv()))
+    }
+
+    /// 停止扫描并释放 ADC、DMA 通道及缓冲区
+    pub fn release(mut self) -> (Adc<ADC1>, dma1::C1, &'static mut [[u16; N]; M]) {
+        self.stop();
+        let regs = Self::regs();
+        regs.cr1.modify(|_, w| w.scan().clear_bit());
+        regs.cr2
+            .modify(|_, w| w.dma().clear_bit().tsvrefe().clear_bit());
+        (self.adc, self.dma, self.buffer)
+    }
+}
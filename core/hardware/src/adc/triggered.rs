@@ -0,0 +1,230 @@
+//! 定时器触发的 ADC 采样
+//! TIM3 更新事件作为 TRGO 触发 ADC1 规则组转换，每次触发转换一遍通道序列，采样率即定时器更新频率。
+//! DMA1 通道 1 循环写入两个数据块，半传输、传输完成中断分别表示前、后半块已写满(双缓冲)，
+//! 在 DMA 中断中调用 `on_interrupt` 以回调的方式取得已完成的数据块。
+//! ```rust
+//! static G_SAMPLER: Mutex<RefCell<Option<AdcSampler<1, 256>>>> = Mutex::new(RefCell::new(None));
+//!
+//! let buffer = singleton!(: [[u16; 256]; 2] = [[0; 256]; 2]).unwrap();
+//! let mut sampler = AdcSampler::new(adc1, dma_ch1, dp.TIM3, &clocks, [0], SampleTime::T_28, buffer);
+//! sampler.start(10_000);
+//!
+//! #[interrupt]
+//! fn DMA1_CHANNEL1() {
+//!     cortex_m::interrupt::free(|cs| {
+//!         let mut sampler = G_SAMPLER.borrow(cs).borrow_mut();
+//!         sampler.as_mut().unwrap().on_interrupt(|_half, block| {
+//!             // 在下一个数据块写满前处理完成，或复制到队列中交给主循环处理
+//!         });
+//!     });
+//! }
+//! ```
+use super::POWER_UP_CYCLES;
+
+use core::sync::atomic::{self, Ordering};
+
+use stm32f1xx_hal::adc::{Adc, ChannelTimeSequence, SampleTime};
+use stm32f1xx_hal::dma::{dma1, Half};
+use stm32f1xx_hal::pac::{self, ADC1, TIM3};
+use stm32f1xx_hal::rcc::Clocks;
+use stm32f1xx_hal::timer::Timer;
+
+/// 计算定时器预分频值及自动重装值
+/// clk_hz: 定时器时钟，单位：Hz
+/// rate_hz: 更新频率，单位：Hz
+/// 返回 (PSC, ARR)
+pub fn timer_divider(clk_hz: u32, rate_hz: u32) -> (u16, u16) {
+    let ticks = (clk_hz / rate_hz.max(1)).max(2);
+    let psc = (ticks - 1) / (1 << 16);
+    let arr = ticks / (psc + 1) - 1;
+    (
+        psc.min(u16::MAX as u32) as u16,
+        arr.min(u16::MAX as u32) as u16,
+    )
+}
+
+/// 定时器触发的 ADC 采样
+/// N: 通道数，最多 16 个
+/// B: 每个数据块的采样值个数，需为 N 的整数倍，数据按通道序列交错排列
+pub struct AdcSampler<const N: usize, const B: usize> {
+    adc: Adc<ADC1>,
+    dma: dma1::C1,
+    tim: TIM3,
+    /// 定时器时钟，单位：Hz
+    tim_clk: u32,
+    buffer: &'static mut [[u16; B]; 2],
+    /// 数据块未及时处理的次数
+    overruns: u32,
+}
+
+impl<const N: usize, const B: usize> AdcSampler<N, B> {
+    /// 配置 ADC、DMA 及定时器，创建后需调用 `start` 开始采样
+    /// 采样时间需满足: N 个通道的转换时间小于采样周期
+    pub fn new(
+        mut adc: Adc<ADC1>,
+        mut dma: dma1::C1,
+        tim: TIM3,
+        clocks: &Clocks,
+        channels: [u8; N],
+        sample_time: SampleTime,
+        buffer: &'static mut [[u16; B]; 2],
+    ) -> Self {
+        assert!(N > 0 && N <= 16 && B > 0 && B.is_multiple_of(N));
+
+        // 使能并复位定时器时钟
+        let tim = Timer::new(tim, clocks).release();
+        // 更新事件作为 TRGO 输出
+        tim.cr2.modify(|_, w| w.mms().update());
+
+        for &channel in channels.iter() {
+            adc.set_channel_sample_time(channel, sample_time);
+        }
+        adc.set_regular_sequence(&channels);
+        adc.set_continuous_mode(false);
+        adc.set_discontinuous_mode(None);
+
+        // 每次触发扫描一遍通道序列
+        let regs = Self::adc_regs();
+        regs.cr1.modify(|_, w| w.scan().bit(N > 1));
+        regs.cr2.modify(|_, w| {
+            w.align()
+                .right()
+                .dma()
+                .set_bit()
+                .exttrig()
+                .set_bit()
+                .extsel()
+                .tim3trgo()
+        });
+
+        // 外设到存储器，16 位，循环模式，半传输及传输完成中断
+        dma.set_peripheral_address(&regs.dr as *const _ as u32, false);
+        dma.ch().cr.modify(|_, w| {
+            w.mem2mem()
+                .clear_bit()
+                .pl()
+                .very_high()
+                .msize()
+                .bits16()
+                .psize()
+                .bits16()
+                .circ()
+                .set_bit()
+                .dir()
+                .clear_bit()
+                .htie()
+                .set_bit()
+                .tcie()
+                .set_bit()
+        });
+
+        AdcSampler {
+            adc,
+            dma,
+            tim,
+            tim_clk: clocks.pclk1_tim().raw(),
+            buffer,
+            overruns: 0,
+        }
+    }
+
+    fn adc_regs() -> &'static pac::adc1::RegisterBlock {
+        unsafe { &*ADC1::ptr() }
+    }
+
+    fn dma_regs() -> &'static pac::dma1::RegisterBlock {
+        unsafe { &*pac::DMA1::ptr() }
+    }
+
+    /// 以指定采样率开始采样，单位：Hz
+    /// 采样率为 1Hz~100kHz，实际采样率见 `rate_hz`
+    pub fn start(&mut self, rate_hz: u32) {
+        self.stop();
+
+        let (psc, arr) = timer_divider(self.tim_clk, rate_hz);
+        self.tim.psc.write(|w| w.psc().bits(psc));
+        self.tim.arr.write(|w| w.arr().bits(arr));
+        // 产生更新事件装载预分频值，此时 ADC 尚未上电不会被触发
+        self.tim.egr.write(|w| w.ug().set_bit());
+
+        self.dma
+            .set_memory_address(self.buffer.as_ptr() as u32, true);
+        self.dma.set_transfer_length(B * 2);
+        Self::dma_regs().ifcr.write(|w| w.cgif1().set_bit());
+        atomic::compiler_fence(Ordering::Release);
+        self.dma.start();
+
+        Self::adc_regs().cr2.modify(|_, w| w.adon().set_bit());
+        cortex_m::asm::delay(POWER_UP_CYCLES);
+        self.tim.cr1.modify(|_, w| w.cen().set_bit());
+    }
+
+    /// 停止采样
+    pub fn stop(&mut self) {
+        self.tim.cr1.modify(|_, w| w.cen().clear_bit());
+        Self::adc_regs().cr2.modify(|_, w| w.adon().clear_bit());
+        self.dma.stop();
+    }
+
+    /// 实际采样率，单位：Hz
+    pub fn rate_hz(&self) -> f32 {
+        let psc = self.tim.psc.read().psc().bits() as u32 + 1;
+        let arr = self.tim.arr.read().arr().bits() as u32 + 1;
+        self.tim_clk as f32 / (psc * arr) as f32
+    }
+
+    /// 数据块未及时处理的次数
+    /// 两个标志同时置位说明中断被延误，上一个数据块可能已被覆盖
+    pub fn overruns(&self) -> u32 {
+        self.overruns
+    }
+
+    /// 处理 DMA 中断，数据块写满时调用回调
+    /// 回调需在 DMA 写满另一半之前返回
+    pub fn on_interrupt<F>(&mut self, f: F)
+    where
+        F: FnOnce(Half, &[u16; B]),
+    {
+        let dma = Self::dma_regs();
+        let isr = dma.isr.read();
+        let half_done = isr.htif1().bit_is_set();
+        let full_done = isr.tcif1().bit_is_set();
+        dma.ifcr.write(|w| w.chtif1().set_bit().ctcif1().set_bit());
+
+        let half = match (half_done, full_done) {
+            (true, false) => Half::First,
+            (false, true) => Half::Second,
+            (true, true) => {
+                // 两个数据块均已写满，只有最新的一个尚未被覆盖
+                self.overruns += 1;
+                if self.dma.get_ndtr() as usize > B {
+                    Half::Second
+                } else {
+                    Half::First
+                }
+            }
+            (false, false) => return,
+        };
+
+        atomic::compiler_fence(Ordering::Acquire);
+        let block = match half {
+            Half::First => &self.buffer[0],
+            Half::Second => &self.buffer[1],
+        };
+        f(half, block);
+    }
+
+    /// 停止采样并释放 ADC、DMA 通道、定时器及缓冲区
+    #[allow(clippy::type_complexity)]
+    pub fn release(mut self) -> (Adc<ADC1>, dma1::C1, TIM3, &'static mut [[u16; B]; 2]) {
+        self.stop();
+        self.dma
+            .ch()
+            .cr
+            .modify(|_, w| w.htie().clear_bit().tcie().clear_bit());
+        Self::adc_regs()
+            .cr2
+            .modify(|_, w| w.dma().clear_bit().exttrig().clear_bit().extsel().swstart());
+        (self.adc, self.dma, self.tim, self.buffer)
+    }
+}
@@ -0,0 +1,119 @@
+//! ADC1 模拟看门狗
+//! 规则组转换结果超出上下阈值时置位 AWD 标志，并可产生 ADC1_2 中断。
+//! 需配合连续转换(`AdcScan`)或定时器触发(`AdcSampler`)使用，CPU 无需轮询。
+//! 监控所有通道时硬件不区分是哪个通道越界，可在中断中读取各通道的值判断。
+//! ```rust
+//! let mut watchdog = AnalogWatchdog::new(WatchdogChannel::Single(0), 1000, 3000);
+//! watchdog.listen();
+//! unsafe { NVIC::unmask(interrupt::ADC1_2) };
+//!
+//! #[interrupt]
+//! fn ADC1_2() {
+//!     // 读取数据并处理报警
+//!     AnalogWatchdog::clear();
+//! }
+//! ```
+use cortex_m::peripheral::{NVIC, SCB};
+use stm32f1xx_hal::pac::{self, Interrupt, ADC1};
+
+/// 监控的通道
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchdogChannel {
+    /// 规则组所有通道
+    All,
+    /// 单个通道
+    Single(u8),
+}
+
+/// 模拟看门狗
+pub struct AnalogWatchdog {
+    channel: WatchdogChannel,
+}
+
+impl AnalogWatchdog {
+    /// 配置并启用规则组的模拟看门狗
+    /// low/high: 阈值窗口，为 12 位原始值，结果小于 low 或大于 high 时报警
+    pub fn new(channel: WatchdogChannel, low: u16, high: u16) -> Self {
+        let mut watchdog = AnalogWatchdog { channel };
+        watchdog.set_thresholds(low, high);
+        watchdog.set_channel(channel);
+        Self::clear();
+        Self::regs()
+            .cr1
+            .modify(|_, w| w.awden().set_bit().jawden().clear_bit());
+        watchdog
+    }
+
+    fn regs() -> &'static pac::adc1::RegisterBlock {
+        unsafe { &*ADC1::ptr() }
+    }
+
+    /// 修改阈值窗口
+    pub fn set_thresholds(&mut self, low: u16, high: u16) {
+        let regs = Self::regs();
+        // 寄存器只有 12 位阈值字段
+        regs.ltr
+            .write(|w| unsafe { w.bits(u32::from(low.min(0xFFF))) });
+        regs.htr
+            .write(|w| unsafe { w.bits(u32::from(high.min(0xFFF))) });
+    }
+
+    /// 修改监控的通道
+    pub fn set_channel(&mut self, channel: WatchdogChannel) {
+        self.channel = channel;
+        Self::regs().cr1.modify(|_, w| match channel {
+            WatchdogChannel::All => w.awdsgl().clear_bit(),
+            WatchdogChannel::Single(ch) => unsafe { w.awdsgl().set_bit().awdch().bits(ch) },
+        });
+    }
+
+    /// 监控的通道
+    pub fn channel(&self) -> WatchdogChannel {
+        self.channel
+    }
+
+    /// 使能越界中断
+    pub fn listen(&mut self) {
+        Self::regs().cr1.modify(|_, w| w.awdie().set_bit());
+    }
+
+    /// 关闭越界中断
+    pub fn unlisten(&mut self) {
+        Self::regs().cr1.modify(|_, w| w.awdie().clear_bit());
+    }
+
+    /// 是否发生越界
+    pub fn is_triggered() -> bool {
+        Self::regs().sr.read().awd().bit_is_set()
+    }
+
+    /// 清除越界标志，可在中断中调用
+    pub fn clear() {
+        Self::regs().sr.modify(|_, w| w.awd().clear_bit());
+    }
+
+    /// 进入睡眠模式直到发生越界，不需要中断服务函数
+    /// 使用 SEVONPEND: NVIC 中未使能的中断挂起时也会产生唤醒事件，
+    /// 因此 ADC1_2 中断需保持屏蔽，由 WFE 唤醒后在此处清除挂起状态。
+    pub fn sleep_until_triggered(&mut self, scb: &mut SCB) {
+        self.listen();
+        scb.clear_sleepdeep();
+        unsafe { scb.scr.modify(|scr| scr | SCB_SCR_SEVONPEND) };
+        while !Self::is_triggered() {
+            cortex_m::asm::wfe();
+        }
+        unsafe { scb.scr.modify(|scr| scr & !SCB_SCR_SEVONPEND) };
+        self.unlisten();
+        NVIC::unpend(Interrupt::ADC1_2);
+    }
+
+    /// 关闭模拟看门狗
+    pub fn disable(mut self) {
+        self.unlisten();
+        Self::regs().cr1.modify(|_, w| w.awden().clear_bit());
+        Self::clear();
+    }
+}
+
+/// SCB_SCR 寄存器 SEVONPEND 位
+const SCB_SCR_SEVONPEND: u32 = 1 << 4;
@@ -0,0 +1,212 @@
+//! 备份寄存器结构化存储
+//! 数据区为创建 `BackupStore` 时指定的连续寄存器，布局：
+//! - 第 1 个寄存器: 头部，高字节为标记，低字节为结构体版本
+//! - 第 2 个寄存器: CRC-16/CCITT 校验，覆盖头部及数据
+//! - 其余寄存器: 结构体编码后的数据
+//!
+//! 不使用 `rtc` 模块的校准及日历时可使用全部 DR1~DR10(8 个数据字)，
+//! 否则使用 DR1~DR7(5 个数据字)，DR8~DR10 留给 `rtc` 模块。
+//!
+//! VBAT 掉电、备份域复位或侵入检测后寄存器全部为 0，读取时返回 `Error::Empty`。
+//! ```rust
+//! #[derive(Default)]
+//! struct Settings {
+//!     boots: u16,
+//!     brightness: u8,
+//! }
+//!
+//! impl BackupData for Settings {
+//!     const VERSION: u8 = 1;
+//!     const LEN: usize = 2;
+//!
+//!     fn encode(&self, words: &mut Words) {
+//!         words[0] = self.boots;
+//!         words[1] = self.brightness as u16;
+//!     }
+//!
+//!     fn decode(words: &Words) -> Self {
+//!         Settings {
+//!             boots: words[0],
+//!             brightness: words[1] as u8,
+//!         }
+//!     }
+//! }
+//!
+//! let store = BackupStore::new(data::ALL_REGISTERS);
+//! let mut settings = store.load::<Settings>(&backup_domain).unwrap_or_default();
+//! settings.boots += 1;
+//! store.store(&mut backup_domain, &settings).unwrap();
+//! ```
+use core::ops::Range;
+
+use stm32f1xx_hal::backup_domain::BackupDomain;
+
+use crate::rtc::BKP_RTC_CORRECTION;
+
+/// 备份数据寄存器数量(DR1~DR10)
+pub const DATA_REGISTERS: usize = 10;
+/// 头部及校验占用的寄存器数量
+const OVERHEAD: usize = 2;
+/// 结构体最多可用的 16 位字数量
+pub const MAX_CAPACITY: usize = DATA_REGISTERS - OVERHEAD;
+
+/// 全部数据寄存器 DR1~DR10
+pub const ALL_REGISTERS: Range<usize> = 0..DATA_REGISTERS;
+/// DR1~DR7，DR8~DR10 留给 `rtc` 模块
+pub const WITHOUT_RTC: Range<usize> = 0..BKP_RTC_CORRECTION;
+
+/// 头部标记
+const HEADER_MAGIC: u16 = 0xB5 << 8;
+
+/// 结构体编码后的数据，超出存储容量的字不会保存
+pub type Words = [u16; MAX_CAPACITY];
+
+/// 数据区的完整内容，只使用前 `BackupStore::registers` 个
+pub type Image = [u16; DATA_REGISTERS];
+
+/// 读写错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub enum Error {
+    /// 寄存器全部为 0，VBAT 掉电、侵入检测或从未保存过
+    Empty,
+    /// 标记或校验错误
+    Corrupted,
+    /// 版本不一致，为保存时的版本
+    Version(u8),
+    /// 结构体字数超过数据区容量
+    TooLarge,
+}
+
+/// 可保存到备份寄存器的结构体
+pub trait BackupData: Sized {
+    /// 结构体版本，布局变化时递增，旧版本的数据读取时返回 `Error::Version`
+    const VERSION: u8;
+    /// 编码使用的 16 位字数量，不能超过数据区容量
+    const LEN: usize;
+
+    /// 编码为 16 位字，只使用前 `LEN` 个
+    fn encode(&self, words: &mut Words);
+
+    /// 从 16 位字解码，调用前已通过校验，`LEN` 之后的字为 0
+    fn decode(words: &Words) -> Self;
+}
+
+/// CRC-16/CCITT-FALSE，每个字先高字节后低字节
+pub fn crc16(words: &[u16]) -> u16 {
+    let mut crc = 0xFFFF_u16;
+    for byte in words.iter().flat_map(|word| word.to_be_bytes()) {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// 备份寄存器中的结构体存储
+pub struct BackupStore {
+    /// 起始寄存器，0 对应 DR1
+    first: usize,
+    /// 寄存器数量，含头部及校验
+    registers: usize,
+}
+
+impl BackupStore {
+    /// registers: 使用的数据寄存器，0 对应 DR1，如 `ALL_REGISTERS`、`WITHOUT_RTC`
+    /// 范围需在 DR1~DR10 内，且至少包含头部、校验及 1 个数据字
+    pub fn new(registers: Range<usize>) -> Self {
+        assert!(
+            registers.end <= DATA_REGISTERS && registers.len() > OVERHEAD,
+            "备份寄存器范围无效"
+        );
+        BackupStore {
+            first: registers.start,
+            registers: registers.len(),
+        }
+    }
+
+    /// 结构体可用的 16 位字数量，不含头部及校验
+    pub fn capacity(&self) -> usize {
+        self.registers - OVERHEAD
+    }
+
+    /// 头部及数据的校验值
+    fn checksum(&self, image: &Image) -> u16 {
+        let mut words = [0; DATA_REGISTERS - 1];
+        words[0] = image[0];
+        words[1..self.registers - 1].copy_from_slice(&image[OVERHEAD..self.registers]);
+        crc16(&words[..self.registers - 1])
+    }
+
+    /// 编码为数据区的完整内容
+    pub fn encode<T: BackupData>(&self, data: &T) -> Result<Image, Error> {
+        if T::LEN > self.capacity() {
+            return Err(Error::TooLarge);
+        }
+        let mut words = [0; MAX_CAPACITY];
+        data.encode(&mut words);
+
+        let mut image = [0; DATA_REGISTERS];
+        image[0] = HEADER_MAGIC | T::VERSION as u16;
+        image[OVERHEAD..OVERHEAD + T::LEN].copy_from_slice(&words[..T::LEN]);
+        image[1] = self.checksum(&image);
+        Ok(image)
+    }
+
+    /// 校验并解码数据区的完整内容
+    pub fn decode<T: BackupData>(&self, image: &Image) -> Result<T, Error> {
+        if T::LEN > self.capacity() {
+            return Err(Error::TooLarge);
+        }
+        let image_words = &image[..self.registers];
+        if image_words.iter().all(|&word| word == 0) {
+            return Err(Error::Empty);
+        }
+        if image[0] & 0xFF00 != HEADER_MAGIC || self.checksum(image) != image[1] {
+            return Err(Error::Corrupted);
+        }
+        let version = image[0] as u8;
+        if version != T::VERSION {
+            return Err(Error::Version(version));
+        }
+
+        let mut words = [0; MAX_CAPACITY];
+        words[..T::LEN].copy_from_slice(&image[OVERHEAD..OVERHEAD + T::LEN]);
+        Ok(T::decode(&words))
+    }
+
+    /// 读取数据区
+    pub fn read(&self, bkp: &BackupDomain) -> Image {
+        let mut image = [0; DATA_REGISTERS];
+        for (index, word) in image[..self.registers].iter_mut().enumerate() {
+            *word = bkp.read_data_register_low(self.first + index);
+        }
+        image
+    }
+
+    /// 读取并校验结构体
+    pub fn load<T: BackupData>(&self, bkp: &BackupDomain) -> Result<T, Error> {
+        self.decode(&self.read(bkp))
+    }
+
+    /// 保存结构体
+    /// 侵入事件未清除时写入无效，见 `tamper::on_tamper`
+    pub fn store<T: BackupData>(&self, bkp: &mut BackupDomain, data: &T) -> Result<(), Error> {
+        let image = self.encode(data)?;
+        for (index, word) in image[..self.registers].iter().enumerate() {
+            bkp.write_data_register_low(self.first + index, *word);
+        }
+        Ok(())
+    }
+
+    /// 清空数据区，之后读取返回 `Error::Empty`
+    pub fn clear(&self, bkp: &mut BackupDomain) {
+        for index in 0..self.registers {
+            bkp.write_data_register_low(self.first + index, 0);
+        }
+    }
+}
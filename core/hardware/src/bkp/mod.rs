@@ -0,0 +1,10 @@
+//! 备份寄存器
+//! - `data`: 在指定范围的备份寄存器中保存带版本及校验的结构体，检测 VBAT 掉电或数据损坏
+//! - `tamper`: 侵入检测，引脚出现有效电平时硬件清除所有备份数据寄存器并产生事件
+//!
+//! 使用 `rtc` 模块的校准或日历时，数据区应使用 `data::WITHOUT_RTC`，避开 DR8~DR10。
+pub mod data;
+pub mod tamper;
+
+pub use data::{BackupData, BackupStore, Error, Words};
+pub use tamper::ActiveLevel;
@@ -0,0 +1,99 @@
+//! 侵入检测
+//! 使能后侵入检测引脚 PC13 出现有效电平时，硬件清除 DR1~DR10 并置位 TEF，使能中断时产生 TAMPER 中断。
+//! TEF 置位期间备份数据寄存器保持复位且不能写入，需调用 `on_tamper` 清除。
+//! PC13 与 RTC 校准时钟输出(CCO)共用，不能同时使用；`rtc::Calendar` 的时间有效标记也会被清除。
+//! ```rust
+//! tamper::enable(&mut backup_domain, ActiveLevel::Low);
+//! tamper::listen(&mut backup_domain);
+//! unsafe { NVIC::unmask(interrupt::TAMPER) };
+//!
+//! loop {
+//!     if tamper::take_event() {
+//!         // 重新初始化备份数据
+//!     }
+//! }
+//!
+//! #[interrupt]
+//! fn TAMPER() {
+//!     tamper::on_tamper();
+//! }
+//! ```
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use stm32f1xx_hal::backup_domain::BackupDomain;
+use stm32f1xx_hal::pac;
+
+/// 侵入事件标志
+static TAMPERED: AtomicBool = AtomicBool::new(false);
+/// 侵入事件次数
+static EVENTS: AtomicU32 = AtomicU32::new(0);
+
+/// 引脚有效电平
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActiveLevel {
+    /// 高电平触发，引脚需下拉
+    High,
+    /// 低电平触发，引脚需上拉
+    Low,
+}
+
+fn regs() -> &'static pac::bkp::RegisterBlock {
+    unsafe { &*pac::BKP::ptr() }
+}
+
+/// 使能侵入检测，PC13 不再作为普通 IO
+pub fn enable(_bkp: &mut BackupDomain, level: ActiveLevel) {
+    let bkp = regs();
+    // 先选择有效电平再使能，避免切换电平时误触发
+    bkp.cr
+        .modify(|_, w| w.tpal().bit(level == ActiveLevel::Low));
+    bkp.cr.modify(|_, w| w.tpe().set_bit());
+}
+
+/// 关闭侵入检测
+pub fn disable(_bkp: &mut BackupDomain) {
+    regs().cr.modify(|_, w| w.tpe().clear_bit());
+}
+
+/// 是否已使能
+pub fn is_enabled() -> bool {
+    regs().cr.read().tpe().bit_is_set()
+}
+
+/// 使能侵入中断
+pub fn listen(_bkp: &mut BackupDomain) {
+    regs().csr.modify(|_, w| w.tpie().set_bit());
+}
+
+/// 关闭侵入中断
+pub fn unlisten(_bkp: &mut BackupDomain) {
+    regs().csr.modify(|_, w| w.tpie().clear_bit());
+}
+
+/// 是否发生侵入事件且尚未清除
+pub fn is_triggered() -> bool {
+    regs().csr.read().tef().bit_is_set()
+}
+
+/// 在 TAMPER 中断中或主循环中轮询调用，清除 TEF 及 TIF 并记录事件
+/// 返回是否发生了侵入事件
+pub fn on_tamper() -> bool {
+    let bkp = regs();
+    if bkp.csr.read().tef().bit_is_clear() {
+        return false;
+    }
+    bkp.csr.modify(|_, w| w.cte().set_bit().cti().set_bit());
+    TAMPERED.store(true, Ordering::Release);
+    EVENTS.fetch_add(1, Ordering::Relaxed);
+    true
+}
+
+/// 取出侵入事件标志
+pub fn take_event() -> bool {
+    TAMPERED.swap(false, Ordering::Acquire)
+}
+
+/// 本次上电以来的侵入事件次数
+pub fn events() -> u32 {
+    EVENTS.load(Ordering::Relaxed)
+}
@@ -0,0 +1,262 @@
+//! 蜂鸣器
+//! - 有源蜂鸣器: 内部自带振荡源，只能控制通断，频率参数被忽略
+//! - 无源蜂鸣器: 由定时器 PWM 输出 50% 占空比的方波，频率即音调
+//!
+//! `Player` 维护音符队列，在定时中断中周期调用 `tick` 后台播放，主循环不被阻塞。
+//! ```rust
+//! let pwm = tim2.pwm_hz::<Tim2NoRemap, _, _>(pa0, &mut afio.mapr, 1.kHz(), &clocks);
+//! let mut player: Player<_, 64> = Player::new(PassiveBuzzer::new(pwm, Channel::C1));
+//! player.play_rtttl("Beep:d=8,o=6,b=120:c,e,g,c7").unwrap();
+//!
+//! // 每 10ms 的定时中断中
+//! player.tick(10);
+//! ```
+pub mod rtttl;
+
+pub use rtttl::{Note, Rtttl};
+
+use embedded_hal::digital::v2::OutputPin;
+use embedded_hal::Pwm;
+use heapless::Deque;
+use stm32f1xx_hal::time::Hertz;
+
+/// 音调输出
+pub trait ToneOutput {
+    /// 以指定频率发声，单位：Hz
+    fn play(&mut self, freq_hz: u32);
+    /// 静音
+    fn mute(&mut self);
+}
+
+/// 有源蜂鸣器
+pub struct ActiveBuzzer<P> {
+    pin: P,
+    /// 低电平发声
+    active_low: bool,
+}
+
+impl<P> ActiveBuzzer<P>
+where
+    P: OutputPin,
+    <P as OutputPin>::Error: core::fmt::Debug,
+{
+    /// 创建蜂鸣器，初始为静音
+    pub fn new(pin: P, active_low: bool) -> Self {
+        let mut buzzer = ActiveBuzzer { pin, active_low };
+        buzzer.mute();
+        buzzer
+    }
+
+    /// 释放引脚
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
+
+impl<P> ToneOutput for ActiveBuzzer<P>
+where
+    P: OutputPin,
+    <P as OutputPin>::Error: core::fmt::Debug,
+{
+    fn play(&mut self, _freq_hz: u32) {
+        if self.active_low {
+            self.pin.set_low().unwrap();
+        } else {
+            self.pin.set_high().unwrap();
+        }
+    }
+
+    fn mute(&mut self) {
+        if self.active_low {
+            self.pin.set_high().unwrap();
+        } else {
+            self.pin.set_low().unwrap();
+        }
+    }
+}
+
+/// 无源蜂鸣器
+/// 发声时会修改定时器周期，同一定时器的其他通道会受到影响
+pub struct PassiveBuzzer<PWM>
+where
+    PWM: Pwm,
+{
+    pwm: PWM,
+    channel: PWM::Channel,
+}
+
+impl<PWM> PassiveBuzzer<PWM>
+where
+    PWM: Pwm<Duty = u16, Time = Hertz>,
+    PWM::Channel: Copy,
+{
+    /// 创建蜂鸣器并使能通道，初始为静音
+    pub fn new(pwm: PWM, channel: PWM::Channel) -> Self {
+        let mut buzzer = PassiveBuzzer { pwm, channel };
+        buzzer.pwm.set_duty(channel, 0);
+        buzzer.pwm.enable(channel);
+        buzzer
+    }
+
+    /// 释放定时器
+    pub fn release(mut self) -> PWM {
+        self.pwm.disable(self.channel);
+        self.pwm
+    }
+}
+
+impl<PWM> ToneOutput for PassiveBuzzer<PWM>
+where
+    PWM: Pwm<Duty = u16, Time = Hertz>,
+    PWM::Channel: Copy,
+{
+    fn play(&mut self, freq_hz: u32) {
+        if freq_hz == 0 {
+            self.mute();
+            return;
+        }
+        // 修改周期后最大占空比随之改变
+        self.pwm.set_period(Hertz::from_raw(freq_hz));
+        let duty = self.pwm.get_max_duty() / 2;
+        self.pwm.set_duty(self.channel, duty);
+    }
+
+    fn mute(&mut self) {
+        self.pwm.set_duty(self.channel, 0);
+    }
+}
+
+/// 播放错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// 铃声格式错误
+    Rtttl(rtttl::Error),
+    /// 音符队列已满
+    QueueFull,
+}
+
+impl From<rtttl::Error> for Error {
+    fn from(e: rtttl::Error) -> Self {
+        Error::Rtttl(e)
+    }
+}
+
+/// 后台播放器
+/// N: 音符队列长度
+pub struct Player<T, const N: usize> {
+    output: T,
+    queue: Deque<Note, N>,
+    /// 当前音符剩余时间，单位：ms
+    remaining_ms: u32,
+    /// 当前音符末尾的静音时间，单位：ms
+    note_gap_ms: u32,
+    /// 音符之间的静音间隔，使相同音高的连续音符可以区分，单位：ms
+    gap_ms: u32,
+    /// 正在发声
+    sounding: bool,
+}
+
+impl<T, const N: usize> Player<T, N>
+where
+    T: ToneOutput,
+{
+    /// 创建播放器，默认音符间隔 10ms
+    pub fn new(mut output: T) -> Self {
+        output.mute();
+        Player {
+            output,
+            queue: Deque::new(),
+            remaining_ms: 0,
+            note_gap_ms: 0,
+            gap_ms: 10,
+            sounding: false,
+        }
+    }
+
+    /// 设置音符之间的静音间隔，单位：ms
+    pub fn set_gap(&mut self, gap_ms: u32) {
+        self.gap_ms = gap_ms;
+    }
+
+    /// 添加一个音符，freq_hz 为 0 时表示休止符
+    pub fn tone(&mut self, freq_hz: u32, duration_ms: u32) -> Result<(), Error> {
+        self.queue
+            .push_back(Note {
+                freq_hz,
+                duration_ms,
+            })
+            .map_err(|_| Error::QueueFull)
+    }
+
+    /// 解析铃声并添加到队列
+    /// 铃声格式错误或队列空间不足时不添加任何音符
+    pub fn play_rtttl(&mut self, text: &str) -> Result<(), Error> {
+        let mut count = 0;
+        for note in Rtttl::new(text)? {
+            note?;
+            count += 1;
+        }
+        if count > N - self.queue.len() {
+            return Err(Error::QueueFull);
+        }
+        for note in Rtttl::new(text)?.flatten() {
+            self.queue.push_back(note).ok();
+        }
+        Ok(())
+    }
+
+    /// 停止播放并清空队列
+    pub fn stop(&mut self) {
+        self.queue.clear();
+        self.remaining_ms = 0;
+        self.sounding = false;
+        self.output.mute();
+    }
+
+    /// 是否正在播放
+    pub fn is_playing(&self) -> bool {
+        self.remaining_ms > 0 || !self.queue.is_empty()
+    }
+
+    /// 推进播放进度，在定时中断中周期调用
+    /// dt_ms: 距离上次调用的时间，单位：ms
+    pub fn tick(&mut self, dt_ms: u32) {
+        self.remaining_ms = self.remaining_ms.saturating_sub(dt_ms);
+        if self.remaining_ms == 0 {
+            self.next_note();
+        } else if self.sounding && self.remaining_ms <= self.note_gap_ms {
+            self.output.mute();
+            self.sounding = false;
+        }
+    }
+
+    /// 开始播放下一个音符
+    fn next_note(&mut self) {
+        match self.queue.pop_front() {
+            Some(note) => {
+                self.remaining_ms = note.duration_ms.max(1);
+                // 间隔最多占音符时长的四分之一
+                self.note_gap_ms = self.gap_ms.min(note.duration_ms / 4);
+                if note.freq_hz == 0 {
+                    self.output.mute();
+                    self.sounding = false;
+                } else {
+                    self.output.play(note.freq_hz);
+                    self.sounding = true;
+                }
+            }
+            None => {
+                if self.sounding {
+                    self.output.mute();
+                    self.sounding = false;
+                }
+            }
+        }
+    }
+
+    /// 释放音调输出
+    pub fn release(mut self) -> T {
+        self.output.mute();
+        self.output
+    }
+}
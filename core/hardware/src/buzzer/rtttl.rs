@@ -0,0 +1,192 @@
+//! RTTTL 铃声格式解析
+//! 格式: `名称:d=默认时值,o=默认八度,b=节拍:音符,音符,...`
+//! 音符: `[时值]音名[#][.][八度][.]`，音名为 a~g，`p` 表示休止符，`.` 表示附点(时长 1.5 倍)。
+//! ```text
+//! Tetris:d=4,o=5,b=160:e6,8b,8c6,8d6,16e6,16d6,8c6,8b,a,8a,8c6,e6,8d6,8c6,b,8b,8c6,d6,e6
+//! ```
+
+/// 音符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Note {
+    /// 频率，单位：Hz，为 0 时表示休止符
+    pub freq_hz: u32,
+    /// 时长，单位：ms
+    pub duration_ms: u32,
+}
+
+/// 解析错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// 缺少名称、默认值或音符部分
+    Format,
+    /// 默认值无效
+    Default,
+    /// 音符无效
+    Note,
+}
+
+/// 第 4 八度 C~B 的频率，单位：0.01Hz
+const OCTAVE4_CENTI_HZ: [u32; 12] = [
+    26163, 27718, 29366, 31113, 32963, 34923, 36999, 39200, 41530, 44000, 46616, 49388,
+];
+
+/// 音高对应的频率，单位：Hz
+/// semitone: 0~11 对应 C~B
+pub fn note_freq(semitone: u8, octave: u8) -> u32 {
+    let centi_hz = OCTAVE4_CENTI_HZ[semitone as usize % 12];
+    let centi_hz = if octave >= 4 {
+        centi_hz << (octave - 4)
+    } else {
+        centi_hz >> (4 - octave)
+    };
+    (centi_hz + 50) / 100
+}
+
+/// 将数字字符串解析为整数
+fn parse_num(value: &str) -> Option<u32> {
+    if value.is_empty() {
+        return None;
+    }
+    value.parse().ok()
+}
+
+/// RTTTL 解析器，逐个产生音符
+#[derive(Debug, Clone)]
+pub struct Rtttl<'a> {
+    /// 铃声名称
+    name: &'a str,
+    /// 未解析的音符
+    notes: core::str::Split<'a, char>,
+    default_duration: u32,
+    default_octave: u8,
+    /// 全音符时长，单位：ms
+    whole_note_ms: u32,
+}
+
+impl<'a> Rtttl<'a> {
+    /// 解析名称及默认值
+    pub fn new(text: &'a str) -> Result<Self, Error> {
+        let mut sections = text.trim().splitn(3, ':');
+        let name = sections.next().ok_or(Error::Format)?;
+        let defaults = sections.next().ok_or(Error::Format)?;
+        let notes = sections.next().ok_or(Error::Format)?;
+
+        let mut default_duration = 4;
+        let mut default_octave = 6;
+        let mut bpm = 63;
+        for item in defaults.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (key, value) = item.split_once('=').ok_or(Error::Default)?;
+            let value = parse_num(value.trim()).ok_or(Error::Default)?;
+            match key.trim() {
+                "d" | "D" => default_duration = value,
+                "o" | "O" => default_octave = u8::try_from(value).map_err(|_| Error::Default)?,
+                "b" | "B" => bpm = value,
+                _ => return Err(Error::Default),
+            }
+        }
+        if default_duration == 0 || bpm == 0 || default_octave > 8 {
+            return Err(Error::Default);
+        }
+
+        Ok(Rtttl {
+            name,
+            notes: notes.split(','),
+            default_duration,
+            default_octave,
+            whole_note_ms: 60_000 * 4 / bpm,
+        })
+    }
+
+    /// 铃声名称
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// 解析单个音符
+    fn parse_note(&self, text: &str) -> Result<Note, Error> {
+        let text = text.trim();
+        let bytes = text.as_bytes();
+        let mut pos = 0;
+
+        // 时值
+        while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+            pos += 1;
+        }
+        let duration = match pos {
+            0 => self.default_duration,
+            _ => parse_num(&text[..pos]).ok_or(Error::Note)?,
+        };
+        if duration == 0 {
+            return Err(Error::Note);
+        }
+
+        // 音名
+        let semitone = match bytes.get(pos).map(u8::to_ascii_lowercase) {
+            Some(b'c') => Some(0),
+            Some(b'd') => Some(2),
+            Some(b'e') => Some(4),
+            Some(b'f') => Some(5),
+            Some(b'g') => Some(7),
+            Some(b'a') => Some(9),
+            Some(b'b') | Some(b'h') => Some(11),
+            Some(b'p') => None,
+            _ => return Err(Error::Note),
+        };
+        pos += 1;
+
+        let mut sharp = false;
+        if bytes.get(pos) == Some(&b'#') {
+            sharp = true;
+            pos += 1;
+        }
+        let mut dotted = false;
+        if bytes.get(pos) == Some(&b'.') {
+            dotted = true;
+            pos += 1;
+        }
+
+        // 八度
+        let mut octave = self.default_octave;
+        if let Some(digit) = bytes.get(pos).filter(|b| b.is_ascii_digit()) {
+            octave = digit - b'0';
+            pos += 1;
+        }
+        if bytes.get(pos) == Some(&b'.') {
+            dotted = true;
+            pos += 1;
+        }
+        if pos != bytes.len() || octave > 8 {
+            return Err(Error::Note);
+        }
+
+        let mut duration_ms = self.whole_note_ms / duration;
+        if dotted {
+            duration_ms += duration_ms / 2;
+        }
+        let freq_hz = match semitone {
+            Some(semitone) => {
+                // B# 为下一个八度的 C
+                let semitone = semitone + sharp as u8;
+                note_freq(semitone % 12, octave + semitone / 12)
+            }
+            None => 0,
+        };
+        Ok(Note {
+            freq_hz,
+            duration_ms,
+        })
+    }
+}
+
+impl<'a> Iterator for Rtttl<'a> {
+    type Item = Result<Note, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let text = self.notes.next()?;
+            if !text.trim().is_empty() {
+                return Some(self.parse_note(text));
+            }
+        }
+    }
+}
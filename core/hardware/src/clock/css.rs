@@ -0,0 +1,68 @@
+//! 时钟安全系统(CSS)
+//! 使能后若 HSE 失效，硬件关闭 HSE 及 PLL，系统时钟自动切换到 HSI 并产生 NMI。
+//! NMI 中必须清除 CSSF 标志，否则会反复进入中断。
+//! 主循环检测到失效后调用 `ClockControl::switch_sysclk(Preset::Hsi8, ..)` 取得新的 `Clocks` 重新配置外设。
+//! ```rust
+//! css::enable();
+//!
+//! loop {
+//!     if css::take_failure() {
+//!         let clocks = clock.switch_sysclk(Preset::Hsi8, &mut flash.acr).unwrap();
+//!     }
+//! }
+//!
+//! #[exception]
+//! unsafe fn NonMaskableInt() {
+//!     css::on_nmi();
+//! }
+//! ```
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use stm32f1xx_hal::pac;
+
+/// HSE 失效标志
+static FAILED: AtomicBool = AtomicBool::new(false);
+/// HSE 失效次数
+static FAILURES: AtomicU32 = AtomicU32::new(0);
+
+fn regs() -> &'static pac::rcc::RegisterBlock {
+    unsafe { &*pac::RCC::ptr() }
+}
+
+/// 使能时钟安全系统，HSE 关闭时不生效
+pub fn enable() {
+    regs().cr.modify(|_, w| w.csson().set_bit());
+}
+
+/// 关闭时钟安全系统
+pub fn disable() {
+    regs().cr.modify(|_, w| w.csson().clear_bit());
+}
+
+/// 是否已使能
+pub fn is_enabled() -> bool {
+    regs().cr.read().csson().bit_is_set()
+}
+
+/// 在 NonMaskableInt 异常中调用，清除 CSSF 并记录失效
+/// 返回本次 NMI 是否由 CSS 引起
+pub fn on_nmi() -> bool {
+    let rcc = regs();
+    if rcc.cir.read().cssf().bit_is_clear() {
+        return false;
+    }
+    rcc.cir.modify(|_, w| w.cssc().set_bit());
+    FAILED.store(true, Ordering::Release);
+    FAILURES.fetch_add(1, Ordering::Relaxed);
+    true
+}
+
+/// 取出 HSE 失效标志
+pub fn take_failure() -> bool {
+    FAILED.swap(false, Ordering::Acquire)
+}
+
+/// HSE 失效次数
+pub fn failures() -> u32 {
+    FAILURES.load(Ordering::Relaxed)
+}
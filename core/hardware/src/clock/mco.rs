@@ -0,0 +1,68 @@
+//! MCO 时钟输出
+//! PA8 输出所选时钟，GPIO 最高输出 50MHz，72MHz 系统时钟需选择 PLL/2。
+//! ```rust
+//! let pa8 = gpioa.pa8.into_alternate_push_pull(&mut gpioa.crh);
+//! let mco = Mco::new(pa8, McoSource::Sysclk);
+//! ```
+use stm32f1xx_hal::gpio::{gpioa::PA8, Alternate};
+use stm32f1xx_hal::pac;
+
+/// MCO 时钟源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub enum McoSource {
+    /// 不输出
+    None,
+    /// 系统时钟
+    Sysclk,
+    /// HSI
+    Hsi,
+    /// HSE
+    Hse,
+    /// PLL 输出的二分频
+    PllDiv2,
+}
+
+/// MCO 时钟输出
+pub struct Mco {
+    pin: PA8<Alternate>,
+    source: McoSource,
+}
+
+impl Mco {
+    /// pin: 需配置为复用推挽输出
+    pub fn new(pin: PA8<Alternate>, source: McoSource) -> Self {
+        let mut mco = Mco {
+            pin,
+            source: McoSource::None,
+        };
+        mco.set_source(source);
+        mco
+    }
+
+    /// 切换时钟源
+    pub fn set_source(&mut self, source: McoSource) {
+        self.source = source;
+        let rcc = unsafe { &*pac::RCC::ptr() };
+        rcc.cfgr.modify(|_, w| {
+            let mco = w.mco();
+            match source {
+                McoSource::None => mco.no_mco(),
+                McoSource::Sysclk => mco.sysclk(),
+                McoSource::Hsi => mco.hsi(),
+                McoSource::Hse => mco.hse(),
+                McoSource::PllDiv2 => mco.pll(),
+            }
+        });
+    }
+
+    /// 当前时钟源
+    pub fn source(&self) -> McoSource {
+        self.source
+    }
+
+    /// 停止输出并释放引脚
+    pub fn release(mut self) -> PA8<Alternate> {
+        self.set_source(McoSource::None);
+        self.pin
+    }
+}
@@ -0,0 +1,204 @@
+//! 时钟树配置
+//! - `Preset`: 常用的时钟预设
+//! - `freeze`: 按预设初始化时钟，不需要运行时切换时使用
+//! - `ClockControl`: 持有 RCC 的时钟管理器，初始化后可在运行时切换预设
+//! - `css`: 时钟安全系统，HSE 失效时在 NMI 中记录并回退到 HSI
+//! - `Mco`: PA8 输出时钟，用于示波器验证
+//!
+//! 切换预设只修改时钟树，不会重新配置外设。
+//! 依赖时钟频率的外设(延时、串口波特率、定时器预分频等)需要由调用者使用返回的 `Clocks` 重新配置。
+//! ```rust
+//! let mut flash = dp.FLASH.constrain();
+//! let mut clock = ClockControl::new(dp.RCC, &mut flash.acr, Preset::Hse72);
+//! let mut delay = cp.SYST.delay(clock.clocks());
+//!
+//! // 运行时切换到低功耗预设，并重新配置延时
+//! let clocks = clock.switch_sysclk(Preset::Hsi8, &mut flash.acr).unwrap();
+//! let mut delay = delay.release().release().delay(&clocks);
+//! ```
+pub mod css;
+pub mod mco;
+
+pub use mco::{Mco, McoSource};
+
+use stm32f1xx_hal::flash::ACR;
+use stm32f1xx_hal::pac;
+use stm32f1xx_hal::prelude::_fugit_RateExtU32;
+use stm32f1xx_hal::rcc::{Clocks, Rcc, CFGR};
+
+/// 外部晶振频率，单位：Hz
+pub const HSE_HZ: u32 = 8_000_000;
+/// HSI 频率，单位：Hz
+pub const HSI_HZ: u32 = 8_000_000;
+
+/// 等待 HSE 就绪的最大查询次数，HSE 启动时间典型值 2ms
+const HSE_STARTUP_POLLS: u32 = 200_000;
+
+/// 时钟配置错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub enum Error {
+    /// HSE 启动超时，未连接晶振或晶振损坏
+    HseTimeout,
+}
+
+/// 时钟预设
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub enum Preset {
+    /// HSI 8MHz，不使用 PLL，低功耗
+    Hsi8,
+    /// HSE 8MHz 经 PLL 倍频到 72MHz，全速运行
+    Hse72,
+    /// HSE 8MHz 经 PLL 倍频到 48MHz，USB 时钟不分频
+    Hse48,
+}
+
+impl Preset {
+    /// 是否使用 HSE
+    pub fn uses_hse(&self) -> bool {
+        !matches!(self, Preset::Hsi8)
+    }
+
+    /// 系统时钟，单位：Hz
+    pub fn sysclk_hz(&self) -> u32 {
+        match self {
+            Preset::Hsi8 => 8_000_000,
+            Preset::Hse72 => 72_000_000,
+            Preset::Hse48 => 48_000_000,
+        }
+    }
+
+    /// APB1 时钟，单位：Hz，不能超过 36MHz
+    pub fn pclk1_hz(&self) -> u32 {
+        match self {
+            Preset::Hsi8 => 8_000_000,
+            Preset::Hse72 => 36_000_000,
+            Preset::Hse48 => 24_000_000,
+        }
+    }
+
+    /// ADC 时钟，单位：Hz，不能超过 14MHz
+    pub fn adcclk_hz(&self) -> u32 {
+        match self {
+            Preset::Hsi8 => 4_000_000,
+            Preset::Hse72 | Preset::Hse48 => 12_000_000,
+        }
+    }
+
+    /// USB 是否可用，需要 48MHz 的 USB 时钟
+    pub fn usb_capable(&self) -> bool {
+        self.uses_hse()
+    }
+
+    /// 将预设写入 HAL 的时钟配置
+    pub fn configure(&self, cfgr: CFGR) -> CFGR {
+        let cfgr = if self.uses_hse() {
+            cfgr.use_hse(HSE_HZ.Hz())
+        } else {
+            cfgr
+        };
+        cfgr.sysclk(self.sysclk_hz().Hz())
+            .hclk(self.sysclk_hz().Hz())
+            .pclk1(self.pclk1_hz().Hz())
+            .pclk2(self.sysclk_hz().Hz())
+            .adcclk(self.adcclk_hz().Hz())
+    }
+}
+
+fn regs() -> &'static pac::rcc::RegisterBlock {
+    unsafe { &*pac::RCC::ptr() }
+}
+
+/// 启动 HSE 并等待就绪，超时则关闭 HSE
+fn start_hse(rcc: &pac::rcc::RegisterBlock) -> Result<(), Error> {
+    rcc.cr.modify(|_, w| w.hseon().set_bit());
+    for _ in 0..HSE_STARTUP_POLLS {
+        if rcc.cr.read().hserdy().bit_is_set() {
+            return Ok(());
+        }
+    }
+    rcc.cr.modify(|_, w| w.hseon().clear_bit());
+    Err(Error::HseTimeout)
+}
+
+/// 按预设初始化时钟
+/// 与 `rcc.cfgr.freeze` 不同，HSE 启动失败时返回错误而不是一直等待
+pub fn freeze(rcc: Rcc, acr: &mut ACR, preset: Preset) -> Result<Clocks, Error> {
+    // HAL 的 Rcc 不提供寄存器访问，调用者已交出 RCC 的所有权
+    if preset.uses_hse() {
+        start_hse(regs())?;
+    }
+    Ok(preset.configure(rcc.cfgr).freeze(acr))
+}
+
+/// 时钟管理器
+/// 持有 RCC 的所有权，初始化及运行时切换都在这里进行，其它代码无法再通过 HAL 修改时钟树。
+/// HAL 的 `CFGR` 只记录目标频率，不持有 RCC，每次配置时新建一份后冻结。
+pub struct ClockControl {
+    rcc: pac::RCC,
+    preset: Preset,
+    clocks: Clocks,
+}
+
+impl ClockControl {
+    /// 按预设初始化时钟
+    /// HSE 启动失败时回退到 HSI 8MHz，可通过 `preset` 查看实际使用的预设
+    pub fn new(rcc: pac::RCC, acr: &mut ACR, preset: Preset) -> Self {
+        let preset = if preset.uses_hse() && start_hse(&rcc).is_err() {
+            Preset::Hsi8
+        } else {
+            preset
+        };
+        let clocks = preset.configure(CFGR::default()).freeze(acr);
+        ClockControl {
+            rcc,
+            preset,
+            clocks,
+        }
+    }
+
+    /// 当前预设
+    pub fn preset(&self) -> Preset {
+        self.preset
+    }
+
+    /// 当前时钟频率
+    pub fn clocks(&self) -> &Clocks {
+        &self.clocks
+    }
+
+    /// 运行时切换系统时钟到指定预设，返回新的时钟频率
+    /// 只修改时钟树，延时、串口、定时器等外设需要调用者使用返回的 `Clocks` 重新配置。
+    /// 先将系统时钟切换到 HSI 并关闭 PLL，才能修改 PLL 倍频系数。
+    /// HSE 启动失败时保持原有时钟不变。
+    #[must_use = "依赖时钟的外设需要使用新的 Clocks 重新配置"]
+    pub fn switch_sysclk(&mut self, preset: Preset, acr: &mut ACR) -> Result<Clocks, Error> {
+        let rcc = &self.rcc;
+        if preset.uses_hse() {
+            start_hse(rcc)?;
+        }
+
+        rcc.cr.modify(|_, w| w.hsion().set_bit());
+        while rcc.cr.read().hsirdy().bit_is_clear() {}
+        rcc.cfgr.modify(|_, w| w.sw().hsi());
+        while !rcc.cfgr.read().sws().is_hsi() {}
+        rcc.cr.modify(|_, w| w.pllon().clear_bit());
+        while rcc.cr.read().pllrdy().bit_is_set() {}
+
+        // 寄存器已处于可配置状态，借用 HAL 计算分频系数及 Flash 等待周期
+        self.clocks = preset.configure(CFGR::default()).freeze(acr);
+        self.preset = preset;
+
+        if !preset.uses_hse() {
+            rcc.cr
+                .modify(|_, w| w.csson().clear_bit().hseon().clear_bit());
+        }
+        Ok(self.clocks)
+    }
+
+    /// 重新配置当前预设，用于停止模式唤醒后恢复时钟
+    /// 时钟频率不变，外设无需重新配置
+    pub fn restore(&mut self, acr: &mut ACR) -> Result<(), Error> {
+        let _ = self.switch_sysclk(self.preset, acr)?;
+        Ok(())
+    }
+}
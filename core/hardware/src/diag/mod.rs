@@ -0,0 +1,27 @@
+//! 故障诊断
+//! - `ResetCause`: 解析 RCC_CSR 中的复位原因
+//! - `report`: panic 信息及 HardFault 现场保存在不初始化的 RAM 中，复位后在下次启动时报告
+//!
+//! 使用 `report` 时应用自己定义 panic 处理函数，不再引入 `panic_probe`：
+//! ```rust
+//! #[panic_handler]
+//! fn panic(info: &PanicInfo) -> ! {
+//!     diag::report::panic(info)
+//! }
+//!
+//! #[exception]
+//! unsafe fn HardFault(frame: &ExceptionFrame) -> ! {
+//!     diag::report::hard_fault(frame)
+//! }
+//!
+//! // 启动时
+//! println!("reset: {:?}", diag::take_reset_cause());
+//! if let Some(report) = diag::report::take() {
+//!     println!("last fault: {:?}", report);
+//! }
+//! ```
+pub mod report;
+pub mod reset;
+
+pub use report::{FaultFrame, Report};
+pub use reset::{reset_cause, take_reset_cause, ResetCause};
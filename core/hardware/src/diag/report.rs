@@ -0,0 +1,272 @@
+//! 故障报告
+//! panic 信息及 HardFault 时压栈的寄存器保存在 `.uninit` 段，复位时不会被启动代码清零，
+//! 看门狗、软件或引脚复位后仍然保留，上电复位后内容随机，由标记及校验值判断是否有效。
+//! 报告读取后即清除，只会报告一次。
+use core::fmt::{self, Write};
+use core::mem::MaybeUninit;
+use core::panic::PanicInfo;
+use core::ptr::addr_of_mut;
+
+use cortex_m::peripheral::SCB;
+use cortex_m_rt::ExceptionFrame;
+use heapless::String;
+
+/// 保存的 panic 信息最大长度，超出部分截断
+pub const MESSAGE_LEN: usize = 120;
+
+/// 有效记录的标记
+const RECORD_MAGIC: u32 = 0xD1A6_F417;
+
+/// 记录类型
+const KIND_PANIC: u32 = 1;
+const KIND_HARD_FAULT: u32 = 2;
+
+/// HardFault 现场
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub struct FaultFrame {
+    pub r0: u32,
+    pub r1: u32,
+    pub r2: u32,
+    pub r3: u32,
+    pub r12: u32,
+    pub lr: u32,
+    /// 出错的指令地址
+    pub pc: u32,
+    pub xpsr: u32,
+    /// 可配置故障状态寄存器
+    pub cfsr: u32,
+    /// HardFault 状态寄存器
+    pub hfsr: u32,
+    /// 存储器管理故障地址，CFSR.MMARVALID 置位时有效
+    pub mmfar: u32,
+    /// 总线故障地址，CFSR.BFARVALID 置位时有效
+    pub bfar: u32,
+}
+
+impl FaultFrame {
+    /// 读取压栈的寄存器及故障状态寄存器
+    pub fn capture(frame: &ExceptionFrame) -> Self {
+        let scb = unsafe { &*SCB::PTR };
+        FaultFrame {
+            r0: frame.r0(),
+            r1: frame.r1(),
+            r2: frame.r2(),
+            r3: frame.r3(),
+            r12: frame.r12(),
+            lr: frame.lr(),
+            pc: frame.pc(),
+            xpsr: frame.xpsr(),
+            cfsr: scb.cfsr.read(),
+            hfsr: scb.hfsr.read(),
+            mmfar: scb.mmfar.read(),
+            bfar: scb.bfar.read(),
+        }
+    }
+
+    fn to_words(self) -> [u32; 12] {
+        [
+            self.r0, self.r1, self.r2, self.r3, self.r12, self.lr, self.pc, self.xpsr, self.cfsr,
+            self.hfsr, self.mmfar, self.bfar,
+        ]
+    }
+
+    fn from_words(words: &[u32; 12]) -> Self {
+        FaultFrame {
+            r0: words[0],
+            r1: words[1],
+            r2: words[2],
+            r3: words[3],
+            r12: words[4],
+            lr: words[5],
+            pc: words[6],
+            xpsr: words[7],
+            cfsr: words[8],
+            hfsr: words[9],
+            mmfar: words[10],
+            bfar: words[11],
+        }
+    }
+}
+
+/// 上次复位前的故障
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Report {
+    /// panic 信息，包括位置及消息
+    Panic(String<MESSAGE_LEN>),
+    /// HardFault 现场
+    HardFault(FaultFrame),
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Report::Panic(message) => write!(f, "panic: {}", message),
+            Report::HardFault(frame) => write!(
+                f,
+                "hard fault: pc={:#010x} lr={:#010x} xpsr={:#010x} cfsr={:#010x} hfsr={:#010x} \
+                 mmfar={:#010x} bfar={:#010x}",
+                frame.pc, frame.lr, frame.xpsr, frame.cfsr, frame.hfsr, frame.mmfar, frame.bfar
+            ),
+        }
+    }
+}
+
+impl defmt::Format for Report {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Report::Panic(message) => defmt::write!(f, "panic: {}", message.as_str()),
+            Report::HardFault(frame) => defmt::write!(f, "hard fault: {}", frame),
+        }
+    }
+}
+
+/// 保存在 RAM 中的记录
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Record {
+    magic: u32,
+    kind: u32,
+    len: u32,
+    message: [u8; MESSAGE_LEN],
+    frame: [u32; 12],
+    checksum: u32,
+}
+
+impl Record {
+    /// panic 记录
+    pub fn panic(args: impl fmt::Display) -> Self {
+        let mut writer = Truncate {
+            buf: [0; MESSAGE_LEN],
+            len: 0,
+        };
+        // 超出长度时截断，忽略错误
+        let _ = write!(writer, "{}", args);
+        Self::seal(KIND_PANIC, writer.len, writer.buf, [0; 12])
+    }
+
+    /// HardFault 记录
+    pub fn hard_fault(frame: FaultFrame) -> Self {
+        Self::seal(KIND_HARD_FAULT, 0, [0; MESSAGE_LEN], frame.to_words())
+    }
+
+    fn seal(kind: u32, len: usize, message: [u8; MESSAGE_LEN], frame: [u32; 12]) -> Self {
+        let mut record = Record {
+            magic: RECORD_MAGIC,
+            kind,
+            len: len as u32,
+            message,
+            frame,
+            checksum: 0,
+        };
+        record.checksum = record.compute_checksum();
+        record
+    }
+
+    /// FNV-1a 校验，覆盖除校验值外的所有字段
+    fn compute_checksum(&self) -> u32 {
+        let words = [self.magic, self.kind, self.len];
+        words
+            .iter()
+            .chain(self.frame.iter())
+            .flat_map(|word| word.to_le_bytes())
+            .chain(self.message.iter().copied())
+            .fold(0x811C_9DC5_u32, |hash, byte| {
+                (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+            })
+    }
+
+    /// 校验并解析记录
+    pub fn report(&self) -> Option<Report> {
+        if self.magic != RECORD_MAGIC || self.checksum != self.compute_checksum() {
+            return None;
+        }
+        match self.kind {
+            KIND_PANIC => {
+                let len = (self.len as usize).min(MESSAGE_LEN);
+                let text = core::str::from_utf8(&self.message[..len]).ok()?;
+                let mut message = String::new();
+                message.push_str(text).ok()?;
+                Some(Report::Panic(message))
+            }
+            KIND_HARD_FAULT => Some(Report::HardFault(FaultFrame::from_words(&self.frame))),
+            _ => None,
+        }
+    }
+
+    /// 无效记录
+    pub fn empty() -> Self {
+        Record {
+            magic: 0,
+            kind: 0,
+            len: 0,
+            message: [0; MESSAGE_LEN],
+            frame: [0; 12],
+            checksum: 0,
+        }
+    }
+}
+
+/// 写入固定长度的缓冲区，超出时在字符边界截断
+struct Truncate {
+    buf: [u8; MESSAGE_LEN],
+    len: usize,
+}
+
+impl Write for Truncate {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let space = MESSAGE_LEN - self.len;
+        let mut end = s.len().min(space);
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.buf[self.len..self.len + end].copy_from_slice(&s.as_bytes()[..end]);
+        self.len += end;
+        if end < s.len() {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// 不初始化的记录区
+#[link_section = ".uninit.DIAG_RECORD"]
+static mut RECORD: MaybeUninit<Record> = MaybeUninit::uninit();
+
+fn save(record: Record) {
+    unsafe { addr_of_mut!(RECORD).cast::<Record>().write_volatile(record) };
+}
+
+/// 保存 panic 信息
+pub fn record_panic(info: &PanicInfo) {
+    save(Record::panic(info));
+}
+
+/// 保存 HardFault 现场
+pub fn record_hard_fault(frame: &ExceptionFrame) {
+    save(Record::hard_fault(FaultFrame::capture(frame)));
+}
+
+/// 取出上次复位前保存的报告，没有有效记录时返回 None
+pub fn take() -> Option<Report> {
+    // 记录区的任意内容都是合法的整数，无效内容由标记及校验值排除
+    let record = unsafe { addr_of_mut!(RECORD).cast::<Record>().read_volatile() };
+    save(Record::empty());
+    record.report()
+}
+
+/// 在 panic 处理函数中调用：保存信息，通过 defmt 打印后软件复位
+pub fn panic(info: &PanicInfo) -> ! {
+    cortex_m::interrupt::disable();
+    record_panic(info);
+    defmt::error!("{}", defmt::Display2Format(info));
+    SCB::sys_reset()
+}
+
+/// 在 HardFault 异常中调用：保存现场，通过 defmt 打印后软件复位
+pub fn hard_fault(frame: &ExceptionFrame) -> ! {
+    let fault = FaultFrame::capture(frame);
+    save(Record::hard_fault(fault));
+    defmt::error!("hard fault: {}", fault);
+    SCB::sys_reset()
+}
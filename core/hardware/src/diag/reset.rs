@@ -0,0 +1,79 @@
+//! 复位原因
+//! RCC_CSR 中的复位标志只能由上电复位或写 RMVF 清除，多个标志可能同时置位：
+//! 看门狗、软件及低功耗复位时 NRST 引脚也会被拉低，PINRSTF 同时置位；上电复位时 PORRSTF 与 PINRSTF 同时置位。
+//! 因此按低功耗、窗口看门狗、独立看门狗、软件、上电、引脚的顺序判断。
+use stm32f1xx_hal::pac;
+
+/// LPWRRSTF 位
+const LPWRRSTF: u32 = 1 << 31;
+/// WWDGRSTF 位
+const WWDGRSTF: u32 = 1 << 30;
+/// IWDGRSTF 位
+const IWDGRSTF: u32 = 1 << 29;
+/// SFTRSTF 位
+const SFTRSTF: u32 = 1 << 28;
+/// PORRSTF 位
+const PORRSTF: u32 = 1 << 27;
+/// PINRSTF 位
+const PINRSTF: u32 = 1 << 26;
+
+/// 复位原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub enum ResetCause {
+    /// 上电或掉电复位
+    PowerOn,
+    /// NRST 引脚复位
+    Pin,
+    /// 软件复位，包括 panic 后的 `SCB::sys_reset`
+    Software,
+    /// 独立看门狗复位
+    Iwdg,
+    /// 窗口看门狗复位
+    Wwdg,
+    /// 低功耗管理复位，选项字节配置了进入停止或待机模式时复位
+    LowPower,
+    /// 没有复位标志，标志已被清除
+    Unknown,
+}
+
+impl ResetCause {
+    /// 根据 RCC_CSR 的值判断复位原因
+    pub fn from_csr(csr: u32) -> Self {
+        if csr & LPWRRSTF != 0 {
+            ResetCause::LowPower
+        } else if csr & WWDGRSTF != 0 {
+            ResetCause::Wwdg
+        } else if csr & IWDGRSTF != 0 {
+            ResetCause::Iwdg
+        } else if csr & SFTRSTF != 0 {
+            ResetCause::Software
+        } else if csr & PORRSTF != 0 {
+            ResetCause::PowerOn
+        } else if csr & PINRSTF != 0 {
+            ResetCause::Pin
+        } else {
+            ResetCause::Unknown
+        }
+    }
+
+    /// 是否由看门狗复位
+    pub fn is_watchdog(&self) -> bool {
+        matches!(self, ResetCause::Iwdg | ResetCause::Wwdg)
+    }
+}
+
+fn regs() -> &'static pac::rcc::RegisterBlock {
+    unsafe { &*pac::RCC::ptr() }
+}
+
+/// 读取复位原因，不清除标志
+pub fn reset_cause() -> ResetCause {
+    ResetCause::from_csr(regs().csr.read().bits())
+}
+
+/// 读取复位原因并清除所有复位标志，下次复位时才能得到新的原因
+pub fn take_reset_cause() -> ResetCause {
+    let cause = reset_cause();
+    regs().csr.modify(|_, w| w.rmvf().set_bit());
+    cause
+}
@@ -0,0 +1,55 @@
+//! DMA 与 CPU 复制耗时对比
+//! 使用 DWT 周期计数器计时，DMA 耗时包含配置通道的开销，数据量较小时 CPU 更快。
+//! ```rust
+//! let mut cp = cortex_m::Peripherals::take().unwrap();
+//! let result = bench::compare(&mut dma, &mut cp.DCB, &mut cp.DWT, &src, &mut dst).unwrap();
+//! println!("dma={:?} cpu={:?}", result.dma_cycles, result.cpu_cycles);
+//! ```
+use super::{Channel, Error, MemDma, Word};
+
+use cortex_m::peripheral::{DCB, DWT};
+
+/// 对比结果，单位：CPU 周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub struct BenchResult {
+    /// 数据个数
+    pub len: usize,
+    pub dma_cycles: u32,
+    pub cpu_cycles: u32,
+}
+
+impl BenchResult {
+    /// DMA 相对 CPU 的加速比，大于 1 表示 DMA 更快
+    pub fn speedup(&self) -> f32 {
+        self.cpu_cycles as f32 / self.dma_cycles.max(1) as f32
+    }
+}
+
+/// 分别用 DMA 和 CPU 将 src 复制到 dst，返回各自的耗时
+pub fn compare<CH: Channel, T: Word>(
+    dma: &mut MemDma<CH>,
+    dcb: &mut DCB,
+    dwt: &mut DWT,
+    src: &[T],
+    dst: &mut [T],
+) -> Result<BenchResult, Error> {
+    if src.len() != dst.len() {
+        return Err(Error::Length);
+    }
+    dcb.enable_trace();
+    dwt.enable_cycle_counter();
+
+    let start = DWT::cycle_count();
+    dma.copy_blocking(src, dst)?;
+    let dma_cycles = DWT::cycle_count().wrapping_sub(start);
+
+    let start = DWT::cycle_count();
+    dst.copy_from_slice(src);
+    let cpu_cycles = DWT::cycle_count().wrapping_sub(start);
+
+    Ok(BenchResult {
+        len: src.len(),
+        dma_cycles,
+        cpu_cycles,
+    })
+}
@@ -0,0 +1,264 @@
+//! DMA 存储器到存储器转运
+//! 源地址写入外设地址寄存器，目标地址写入存储器地址寄存器，MEM2MEM 模式下传输立即开始，无需外设请求。
+//! 填充时源地址不自增，重复读取同一个值。
+//! ```rust
+//! let mut dma = MemDma::new(dp.DMA1.split().1);
+//!
+//! // 阻塞复制
+//! let src = [1_u32, 2, 3, 4];
+//! let mut dst = [0_u32; 4];
+//! dma.copy_blocking(&src, &mut dst).unwrap();
+//!
+//! // 后台复制，缓冲区所有权交给 Transfer
+//! let src = singleton!(: [u8; 256] = [0x55; 256]).unwrap();
+//! let dst = singleton!(: [u8; 256] = [0; 256]).unwrap();
+//! let mut transfer = dma.copy(src, dst);
+//! while !transfer.is_done() {
+//!     // 处理其它任务
+//! }
+//! let (dma, result, src, dst) = transfer.wait();
+//! ```
+use super::Word;
+
+use core::sync::atomic::{self, Ordering};
+
+use stm32f1xx_hal::dma::dma1;
+use stm32f1xx_hal::pac;
+
+/// 单次传输的最大数据个数
+pub const MAX_LEN: usize = u16::MAX as usize;
+
+/// 传输错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub enum Error {
+    /// 源与目标长度不一致
+    Length,
+    /// 总线错误，通道已被硬件关闭
+    Transfer,
+}
+
+/// DMA1 通道
+pub trait Channel {
+    /// 通道编号，1~7
+    const NUMBER: u8;
+
+    /// 通道寄存器
+    fn ch(&mut self) -> &pac::dma1::CH;
+
+    /// 剩余数据个数
+    fn remaining(&self) -> u32;
+
+    /// 清除通道的所有中断标志，可在中断中调用
+    fn clear_flags() {
+        let dma = unsafe { &*pac::DMA1::ptr() };
+        let shift = 4 * (Self::NUMBER as u32 - 1);
+        dma.ifcr.write(|w| unsafe { w.bits(1 << shift) });
+    }
+}
+
+macro_rules! channels {
+    ($($CX:ident: $number:expr,)+) => {
+        $(
+            impl Channel for dma1::$CX {
+                const NUMBER: u8 = $number;
+
+                fn ch(&mut self) -> &pac::dma1::CH {
+                    dma1::$CX::ch(self)
+                }
+
+                fn remaining(&self) -> u32 {
+                    self.get_ndtr()
+                }
+            }
+        )+
+    };
+}
+
+channels! {
+    C1: 1,
+    C2: 2,
+    C3: 3,
+    C4: 4,
+    C5: 5,
+    C6: 6,
+    C7: 7,
+}
+
+/// 存储器到存储器 DMA
+pub struct MemDma<CH: Channel> {
+    ch: CH,
+}
+
+impl<CH: Channel> MemDma<CH> {
+    /// 接管 DMA 通道
+    pub fn new(mut ch: CH) -> Self {
+        ch.ch().cr.reset();
+        CH::clear_flags();
+        MemDma { ch }
+    }
+
+    /// 使能传输完成及传输错误中断，需在 NVIC 中开启对应的 DMA1_CHANNELx 中断
+    /// 中断服务函数中调用 `Channel::clear_flags` 清除标志
+    pub fn listen(&mut self) {
+        self.ch
+            .ch()
+            .cr
+            .modify(|_, w| w.tcie().set_bit().teie().set_bit());
+    }
+
+    /// 关闭中断
+    pub fn unlisten(&mut self) {
+        self.ch
+            .ch()
+            .cr
+            .modify(|_, w| w.tcie().clear_bit().teie().clear_bit());
+    }
+
+    /// 配置并启动传输
+    /// src_inc: 源地址是否自增，填充时为 false
+    fn start<T: Word>(&mut self, src: *const T, src_inc: bool, dst: *mut T, len: usize) {
+        assert!(len <= MAX_LEN);
+
+        let ch = self.ch.ch();
+        ch.cr.modify(|_, w| w.en().clear_bit());
+        CH::clear_flags();
+
+        ch.par.write(|w| unsafe { w.pa().bits(src as u32) });
+        ch.mar.write(|w| unsafe { w.ma().bits(dst as u32) });
+        ch.ndtr.write(|w| w.ndt().bits(len as u16));
+        ch.cr.modify(|_, w| unsafe {
+            w.mem2mem()
+                .set_bit()
+                .pl()
+                .medium()
+                .msize()
+                .bits(T::SIZE)
+                .psize()
+                .bits(T::SIZE)
+                .minc()
+                .set_bit()
+                .pinc()
+                .bit(src_inc)
+                .circ()
+                .clear_bit()
+                .dir()
+                .clear_bit()
+        });
+
+        // 启动前确保源数据已写入内存
+        atomic::compiler_fence(Ordering::Release);
+        if len > 0 {
+            ch.cr.modify(|_, w| w.en().set_bit());
+        }
+    }
+
+    /// 查询传输状态，未完成时返回 None
+    fn poll(&mut self) -> Option<Result<(), Error>> {
+        if self.ch.remaining() == 0 {
+            return Some(Ok(()));
+        }
+        // 发生传输错误时硬件关闭通道
+        if self.ch.ch().cr.read().en().bit_is_clear() {
+            return Some(Err(Error::Transfer));
+        }
+        None
+    }
+
+    /// 等待传输完成并关闭通道
+    fn finish(&mut self) -> Result<(), Error> {
+        let result = loop {
+            if let Some(result) = self.poll() {
+                break result;
+            }
+        };
+        self.ch.ch().cr.modify(|_, w| w.en().clear_bit());
+        // 完成后才允许读取目标数据
+        atomic::compiler_fence(Ordering::Acquire);
+        result
+    }
+
+    /// 阻塞复制，超过 65535 个数据时分段传输
+    pub fn copy_blocking<T: Word>(&mut self, src: &[T], dst: &mut [T]) -> Result<(), Error> {
+        if src.len() != dst.len() {
+            return Err(Error::Length);
+        }
+        for (src, dst) in src.chunks(MAX_LEN).zip(dst.chunks_mut(MAX_LEN)) {
+            self.start(src.as_ptr(), true, dst.as_mut_ptr(), src.len());
+            self.finish()?;
+        }
+        Ok(())
+    }
+
+    /// 阻塞填充，超过 65535 个数据时分段传输
+    pub fn fill_blocking<T: Word>(&mut self, value: T, dst: &mut [T]) -> Result<(), Error> {
+        for dst in dst.chunks_mut(MAX_LEN) {
+            self.start(&value, false, dst.as_mut_ptr(), dst.len());
+            self.finish()?;
+        }
+        Ok(())
+    }
+
+    /// 后台复制，长度不能超过 65535
+    /// 长度不一致时 panic
+    pub fn copy<T: Word>(mut self, src: &'static [T], dst: &'static mut [T]) -> Transfer<CH, T> {
+        assert_eq!(src.len(), dst.len());
+        self.start(src.as_ptr(), true, dst.as_mut_ptr(), dst.len());
+        Transfer {
+            dma: self,
+            src,
+            dst,
+        }
+    }
+
+    /// 后台填充，长度不能超过 65535
+    /// value 在传输期间被重复读取，因此需为 'static
+    pub fn fill<T: Word>(mut self, value: &'static T, dst: &'static mut [T]) -> Transfer<CH, T> {
+        self.start(value, false, dst.as_mut_ptr(), dst.len());
+        Transfer {
+            dma: self,
+            src: core::slice::from_ref(value),
+            dst,
+        }
+    }
+
+    /// 释放 DMA 通道
+    pub fn release(mut self) -> CH {
+        self.unlisten();
+        self.ch.ch().cr.reset();
+        self.ch
+    }
+}
+
+/// 进行中的传输
+pub struct Transfer<CH: Channel, T: Word> {
+    dma: MemDma<CH>,
+    src: &'static [T],
+    dst: &'static mut [T],
+}
+
+impl<CH: Channel, T: Word> Transfer<CH, T> {
+    /// 传输是否结束(完成或出错)
+    pub fn is_done(&mut self) -> bool {
+        self.dma.poll().is_some()
+    }
+
+    /// 剩余数据个数
+    pub fn remaining(&self) -> u32 {
+        self.dma.ch.remaining()
+    }
+
+    /// 等待传输结束，归还 DMA 及缓冲区
+    /// 填充时返回的源为只包含填充值的切片
+    #[allow(clippy::type_complexity)]
+    pub fn wait(
+        mut self,
+    ) -> (
+        MemDma<CH>,
+        Result<(), Error>,
+        &'static [T],
+        &'static mut [T],
+    ) {
+        let result = self.dma.finish();
+        (self.dma, result, self.src, self.dst)
+    }
+}
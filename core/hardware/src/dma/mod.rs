@@ -0,0 +1,37 @@
+//! DMA 存储器到存储器转运
+//! - `MemDma`: 复制及填充 u8/u16/u32 数组，支持阻塞、查询完成标志及传输完成中断
+//! - `Transfer`: 进行中的传输，持有通道及缓冲区，完成后通过 `wait` 归还
+//! - `bench`: 对比 DMA 与 CPU 复制耗时
+//!
+//! 传输期间缓冲区的所有权转移给 `Transfer`，避免在转运完成前读写目标数组。
+//! 使用借用缓冲区的 `copy_blocking`、`fill_blocking` 在返回前等待传输完成，同样是安全的。
+pub mod bench;
+pub mod mem2mem;
+
+pub use mem2mem::{Channel, Error, MemDma, Transfer};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// DMA 传输的数据宽度
+pub trait Word: sealed::Sealed + Copy + 'static {
+    /// MSIZE/PSIZE 寄存器值
+    const SIZE: u8;
+}
+
+impl sealed::Sealed for u8 {}
+impl sealed::Sealed for u16 {}
+impl sealed::Sealed for u32 {}
+
+impl Word for u8 {
+    const SIZE: u8 = 0b00;
+}
+
+impl Word for u16 {
+    const SIZE: u8 = 0b01;
+}
+
+impl Word for u32 {
+    const SIZE: u8 = 0b10;
+}
@@ -0,0 +1,130 @@
+//! 外部中断编码器
+//! A/B 相引脚均配置为双边沿触发，在中断中调用 `on_interrupt` 解码，可获得 4 倍频计数。
+//! ```rust
+//! let mut pin_a = gpiob.pb0.into_pull_up_input(&mut gpiob.crl);
+//! let mut pin_b = gpiob.pb1.into_pull_up_input(&mut gpiob.crl);
+//! pin_a.make_interrupt_source(&mut afio);
+//! pin_a.trigger_on_edge(&mut dp.EXTI, Edge::RisingFalling);
+//! pin_a.enable_interrupt(&mut dp.EXTI);
+//! pin_b.make_interrupt_source(&mut afio);
+//! pin_b.trigger_on_edge(&mut dp.EXTI, Edge::RisingFalling);
+//! pin_b.enable_interrupt(&mut dp.EXTI);
+//! let encoder = ExtiEncoder::new(pin_a, pin_b, EncoderConfig::default());
+//!
+//! // EXTI0、EXTI1 中断中
+//! cortex_m::interrupt::free(|cs| ENCODER.borrow(cs).borrow_mut().as_mut().unwrap().on_interrupt());
+//! ```
+use super::speed::{EncoderConfig, QuadratureDecoder, SpeedEstimator};
+use super::Encoder;
+
+use embedded_hal::digital::v2::InputPin;
+use stm32f1xx_hal::gpio::ExtiPin;
+
+/// 外部中断编码器
+pub struct ExtiEncoder<A, B> {
+    pin_a: A,
+    pin_b: B,
+    decoder: QuadratureDecoder,
+    /// 当前位置
+    position: i64,
+    /// 上次采样时的位置
+    last_position: i64,
+    speed: SpeedEstimator,
+}
+
+impl<A, B> ExtiEncoder<A, B>
+where
+    A: InputPin + ExtiPin,
+    <A as InputPin>::Error: core::fmt::Debug,
+    B: InputPin + ExtiPin,
+    <B as InputPin>::Error: core::fmt::Debug,
+{
+    /// 创建编码器，引脚需已配置为双边沿触发的中断源
+    pub fn new(pin_a: A, pin_b: B, config: EncoderConfig) -> Self {
+        let decoder = QuadratureDecoder::new(pin_a.is_high().unwrap(), pin_b.is_high().unwrap());
+        ExtiEncoder {
+            pin_a,
+            pin_b,
+            decoder,
+            position: 0,
+            last_position: 0,
+            speed: SpeedEstimator::new(config),
+        }
+    }
+
+    /// 清除中断标志并解码，在 A/B 相引脚的外部中断中调用
+    pub fn on_interrupt(&mut self) {
+        if self.pin_a.check_interrupt() {
+            self.pin_a.clear_interrupt_pending_bit();
+        }
+        if self.pin_b.check_interrupt() {
+            self.pin_b.clear_interrupt_pending_bit();
+        }
+        let a = self.pin_a.is_high().unwrap();
+        let b = self.pin_b.is_high().unwrap();
+        self.position += self.decoder.update(a, b) as i64;
+    }
+
+    /// 更新速度，在定时中断中以 `sample_period_ms` 周期调用
+    pub fn sample(&mut self) {
+        let delta = self.position - self.last_position;
+        self.last_position = self.position;
+        self.speed.push(delta as i32);
+    }
+
+    /// 当前位置，单位：计数
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// 当前位置，单位：转
+    pub fn revolutions(&self) -> f32 {
+        self.position as f32 / self.speed.config().counts_per_rev as f32
+    }
+
+    /// 将当前位置设为指定值
+    pub fn set_position(&mut self, position: i64) {
+        self.position = position;
+        self.last_position = position;
+    }
+
+    /// 窗口内的平均速度，单位：计数/秒
+    pub fn counts_per_sec(&self) -> f32 {
+        self.speed.counts_per_sec()
+    }
+
+    /// 窗口内的平均转速，单位：转/分钟
+    pub fn rpm(&self) -> f32 {
+        self.speed.rpm()
+    }
+
+    /// 修改编码器参数并清空速度窗口
+    pub fn set_config(&mut self, config: EncoderConfig) {
+        self.speed = SpeedEstimator::new(config);
+    }
+
+    /// 释放引脚
+    pub fn release(self) -> (A, B) {
+        (self.pin_a, self.pin_b)
+    }
+}
+
+impl<A, B> Encoder for ExtiEncoder<A, B>
+where
+    A: InputPin + ExtiPin,
+    <A as InputPin>::Error: core::fmt::Debug,
+    B: InputPin + ExtiPin,
+    <B as InputPin>::Error: core::fmt::Debug,
+{
+    fn sample(&mut self) {
+        ExtiEncoder::sample(self)
+    }
+
+    fn position(&self) -> i64 {
+        ExtiEncoder::position(self)
+    }
+
+    fn rpm(&self) -> f32 {
+        ExtiEncoder::rpm(self)
+    }
+}
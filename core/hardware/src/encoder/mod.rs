@@ -0,0 +1,22 @@
+//! 旋转编码器
+//! - `QeiEncoder`: 定时器编码器接口模式，由硬件计数
+//! - `ExtiEncoder`: 外部中断模式，适用于没有空闲定时器或引脚不支持编码器接口的场景
+//!
+//! 计数溢出后扩展为 `i64` 位置，并在周期定时中断中调用 `sample` 按采样窗口计算速度。
+pub mod exti;
+pub mod qei;
+pub mod speed;
+
+pub use exti::ExtiEncoder;
+pub use qei::QeiEncoder;
+pub use speed::{EncoderConfig, QuadratureDecoder, SpeedEstimator};
+
+/// 编码器通用接口，用于速度闭环等组件
+pub trait Encoder {
+    /// 采样计数值并更新速度，在定时中断中周期调用
+    fn sample(&mut self);
+    /// 当前位置，单位：计数
+    fn position(&self) -> i64;
+    /// 窗口内的平均转速，单位：转/分钟
+    fn rpm(&self) -> f32;
+}
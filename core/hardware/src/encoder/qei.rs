@@ -0,0 +1,109 @@
+//! 定时器编码器接口
+//! 16 位计数器在两次采样之间的变化量不超过 ±32767 时，可通过差值正确扩展溢出。
+//! ```rust
+//! static ENCODER: Mutex<RefCell<Option<QeiEncoder<Qei<TIM3, Tim3NoRemap, (PA6, PA7)>>>>> =
+//!     Mutex::new(RefCell::new(None));
+//!
+//! let qei = Timer::new(dp.TIM3, &clocks).qei((pa6, pa7), &mut afio.mapr, QeiOptions::default());
+//! let encoder = QeiEncoder::new(qei, EncoderConfig::default());
+//!
+//! // 每 10ms 的定时中断中
+//! cortex_m::interrupt::free(|cs| ENCODER.borrow(cs).borrow_mut().as_mut().unwrap().sample());
+//! ```
+use super::speed::{EncoderConfig, SpeedEstimator};
+use super::Encoder;
+
+use embedded_hal::Qei;
+
+/// 定时器编码器
+pub struct QeiEncoder<Q> {
+    qei: Q,
+    /// 上次采样时的计数值
+    last_count: u16,
+    /// 上次采样时的位置
+    position: i64,
+    speed: SpeedEstimator,
+}
+
+impl<Q> QeiEncoder<Q>
+where
+    Q: Qei<Count = u16>,
+{
+    /// 以当前计数值为零点创建编码器
+    pub fn new(qei: Q, config: EncoderConfig) -> Self {
+        let last_count = qei.count();
+        QeiEncoder {
+            qei,
+            last_count,
+            position: 0,
+            speed: SpeedEstimator::new(config),
+        }
+    }
+
+    /// 距上次采样的计数增量
+    fn delta(&self) -> i16 {
+        self.qei.count().wrapping_sub(self.last_count) as i16
+    }
+
+    /// 采样计数值并更新速度，在定时中断中以 `sample_period_ms` 周期调用
+    pub fn sample(&mut self) {
+        let count = self.qei.count();
+        let delta = count.wrapping_sub(self.last_count) as i16;
+        self.last_count = count;
+        self.position += delta as i64;
+        self.speed.push(delta as i32);
+    }
+
+    /// 当前位置，单位：计数
+    pub fn position(&self) -> i64 {
+        self.position + self.delta() as i64
+    }
+
+    /// 当前位置，单位：转
+    pub fn revolutions(&self) -> f32 {
+        self.position() as f32 / self.speed.config().counts_per_rev as f32
+    }
+
+    /// 将当前位置设为指定值
+    pub fn set_position(&mut self, position: i64) {
+        self.last_count = self.qei.count();
+        self.position = position;
+    }
+
+    /// 窗口内的平均速度，单位：计数/秒
+    pub fn counts_per_sec(&self) -> f32 {
+        self.speed.counts_per_sec()
+    }
+
+    /// 窗口内的平均转速，单位：转/分钟
+    pub fn rpm(&self) -> f32 {
+        self.speed.rpm()
+    }
+
+    /// 修改编码器参数并清空速度窗口
+    pub fn set_config(&mut self, config: EncoderConfig) {
+        self.speed = SpeedEstimator::new(config);
+    }
+
+    /// 释放定时器
+    pub fn release(self) -> Q {
+        self.qei
+    }
+}
+
+impl<Q> Encoder for QeiEncoder<Q>
+where
+    Q: Qei<Count = u16>,
+{
+    fn sample(&mut self) {
+        QeiEncoder::sample(self)
+    }
+
+    fn position(&self) -> i64 {
+        QeiEncoder::position(self)
+    }
+
+    fn rpm(&self) -> f32 {
+        QeiEncoder::rpm(self)
+    }
+}
@@ -0,0 +1,119 @@
+//! 编码器速度计算及正交解码
+
+/// 速度窗口的最大采样次数
+pub const MAX_WINDOW: usize = 32;
+
+/// 编码器参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncoderConfig {
+    /// 调用 `sample` 的周期，单位：ms
+    pub sample_period_ms: u32,
+    /// 计算速度的采样次数，取值 1~MAX_WINDOW，窗口越大速度越平滑，响应越慢
+    pub window: usize,
+    /// 每转计数值，编码器接口为 4 倍频时为线数的 4 倍
+    pub counts_per_rev: u32,
+}
+
+impl Default for EncoderConfig {
+    /// 默认 10ms 采样，100ms 窗口，20 线编码器 4 倍频
+    fn default() -> Self {
+        EncoderConfig {
+            sample_period_ms: 10,
+            window: 10,
+            counts_per_rev: 80,
+        }
+    }
+}
+
+/// 滑动窗口速度计算
+pub struct SpeedEstimator {
+    config: EncoderConfig,
+    /// 每次采样的计数增量
+    deltas: [i32; MAX_WINDOW],
+    /// 下一次写入的位置
+    index: usize,
+    /// 已写入的采样次数
+    filled: usize,
+    /// 窗口内计数增量之和
+    sum: i64,
+}
+
+impl SpeedEstimator {
+    pub fn new(config: EncoderConfig) -> Self {
+        let window = config.window.clamp(1, MAX_WINDOW);
+        SpeedEstimator {
+            config: EncoderConfig { window, ..config },
+            deltas: [0; MAX_WINDOW],
+            index: 0,
+            filled: 0,
+            sum: 0,
+        }
+    }
+
+    /// 编码器参数
+    pub fn config(&self) -> EncoderConfig {
+        self.config
+    }
+
+    /// 写入一次采样周期内的计数增量
+    pub fn push(&mut self, delta: i32) {
+        let window = self.config.window;
+        if self.filled == window {
+            self.sum -= self.deltas[self.index] as i64;
+        } else {
+            self.filled += 1;
+        }
+        self.deltas[self.index] = delta;
+        self.sum += delta as i64;
+        self.index = (self.index + 1) % window;
+    }
+
+    /// 清空窗口
+    pub fn reset(&mut self) {
+        self.deltas = [0; MAX_WINDOW];
+        self.index = 0;
+        self.filled = 0;
+        self.sum = 0;
+    }
+
+    /// 速度，单位：计数/秒
+    pub fn counts_per_sec(&self) -> f32 {
+        if self.filled == 0 {
+            return 0.0;
+        }
+        let window_ms = self.filled as u32 * self.config.sample_period_ms;
+        self.sum as f32 * 1000.0 / window_ms as f32
+    }
+
+    /// 转速，单位：转/分钟
+    pub fn rpm(&self) -> f32 {
+        self.counts_per_sec() * 60.0 / self.config.counts_per_rev as f32
+    }
+}
+
+/// 正交信号解码
+/// 根据 A/B 相电平的格雷码变化判断方向，非法跳变(抖动或丢步)计为 0
+#[derive(Debug, Default, Clone, Copy)]
+pub struct QuadratureDecoder {
+    state: u8,
+}
+
+impl QuadratureDecoder {
+    /// 由上一状态与当前状态组成的下标查表得到计数增量
+    const TABLE: [i8; 16] = [0, 1, -1, 0, -1, 0, 0, 1, 1, 0, 0, -1, 0, -1, 1, 0];
+
+    /// 以当前电平初始化
+    pub fn new(a: bool, b: bool) -> Self {
+        QuadratureDecoder {
+            state: (a as u8) << 1 | b as u8,
+        }
+    }
+
+    /// 输入当前 A/B 相电平，返回计数增量
+    pub fn update(&mut self, a: bool, b: bool) -> i8 {
+        let state = (a as u8) << 1 | b as u8;
+        let delta = Self::TABLE[(self.state << 2 | state) as usize];
+        self.state = state;
+        delta
+    }
+}
@@ -0,0 +1,8 @@
+//! I2C 总线工具
+//! - 软件模拟 I2C，实现 embedded-hal 的 `Write`、`WriteRead` 接口
+//! - I2C 总线恢复
+pub mod recovery;
+pub mod soft;
+
+pub use recovery::{BusRecovery, I2c2Recovery, NoRecovery};
+pub use soft::{SoftI2c, SoftRecovery};
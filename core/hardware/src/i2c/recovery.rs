@@ -0,0 +1,189 @@
+//! I2C 总线恢复
+//! 从机在传输过程中被打断(主机复位、接触不良等)时可能一直拉低 SDA，导致总线卡死。
+//! 主机在 SCL 上输出最多 9 个时钟脉冲，让从机移出剩余的数据位并释放 SDA，然后产生停止信号。
+use core::convert::Infallible;
+
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+use stm32f1xx_hal::i2c::{self, BlockingI2c};
+use stm32f1xx_hal::pac::{self, I2C2};
+use stm32f1xx_hal::rcc;
+
+/// I2C 总线恢复
+/// 驱动在 I2C 通信出错后调用，恢复完成后返回可继续使用的总线
+pub trait BusRecovery<I2C> {
+    fn recover(&mut self, i2c: I2C) -> I2C;
+}
+
+/// 不进行总线恢复
+pub struct NoRecovery;
+
+impl<I2C> BusRecovery<I2C> for NoRecovery {
+    fn recover(&mut self, i2c: I2C) -> I2C {
+        i2c
+    }
+}
+
+/// 输出时钟脉冲释放 SDA，并产生停止信号
+/// delay: 延时半个时钟周期
+/// 返回 SDA 是否已被释放
+pub fn clock_out<Scl, Sda, F>(scl: &mut Scl, sda: &mut Sda, mut delay: F) -> bool
+where
+    Scl: OutputPin,
+    <Scl as OutputPin>::Error: core::fmt::Debug,
+    Sda: InputPin + OutputPin,
+    <Sda as InputPin>::Error: core::fmt::Debug,
+    <Sda as OutputPin>::Error: core::fmt::Debug,
+    F: FnMut(),
+{
+    // 主机释放 SDA
+    sda.set_high().unwrap();
+    scl.set_high().unwrap();
+    delay();
+
+    for _ in 0..9 {
+        if sda.is_high().unwrap() {
+            break;
+        }
+        scl.set_low().unwrap();
+        delay();
+        scl.set_high().unwrap();
+        delay();
+    }
+    let released = sda.is_high().unwrap();
+
+    // 产生停止信号，SCL 高电平期间 SDA 由低变高
+    scl.set_low().unwrap();
+    delay();
+    sda.set_low().unwrap();
+    delay();
+    scl.set_high().unwrap();
+    delay();
+    sda.set_high().unwrap();
+    delay();
+
+    released
+}
+
+/// 直接操作寄存器的 GPIOB 开漏引脚
+/// 用于在外设占用引脚时临时切换为通用输出
+struct GpiobOpenDrain {
+    pin: u8,
+}
+
+impl GpiobOpenDrain {
+    /// 引脚配置: 通用开漏输出 50MHz
+    const OUTPUT_OPEN_DRAIN: u32 = 0b0111;
+    /// 引脚配置: 复用开漏输出 50MHz
+    const ALTERNATE_OPEN_DRAIN: u32 = 0b1111;
+
+    /// 修改 CRH 中的引脚配置，仅支持 PB8~PB15
+    fn set_mode(&self, mode: u32) {
+        let gpiob = unsafe { &*pac::GPIOB::ptr() };
+        let offset = (self.pin - 8) * 4;
+        gpiob
+            .crh
+            .modify(|r, w| unsafe { w.bits((r.bits() & !(0xF << offset)) | (mode << offset)) });
+    }
+}
+
+impl OutputPin for GpiobOpenDrain {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        let gpiob = unsafe { &*pac::GPIOB::ptr() };
+        gpiob
+            .bsrr
+            .write(|w| unsafe { w.bits(1 << (self.pin + 16)) });
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        let gpiob = unsafe { &*pac::GPIOB::ptr() };
+        gpiob.bsrr.write(|w| unsafe { w.bits(1 << self.pin) });
+        Ok(())
+    }
+}
+
+impl InputPin for GpiobOpenDrain {
+    type Error = Infallible;
+
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        let gpiob = unsafe { &*pac::GPIOB::ptr() };
+        Ok(gpiob.idr.read().bits() & (1 << self.pin) != 0)
+    }
+
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+/// 硬件 I2C2(PB10/PB11) 总线恢复
+/// 将引脚临时切换为通用开漏输出产生时钟脉冲，然后软件复位 I2C2 并按原参数重新配置寄存器。
+/// 总线对象原样返回，不需要拆分 `BlockingI2c`。
+pub struct I2c2Recovery {
+    mode: i2c::Mode,
+    clocks: rcc::Clocks,
+}
+
+impl I2c2Recovery {
+    /// mode、clocks 需要与创建总线时的参数一致
+    pub fn new(mode: i2c::Mode, clocks: rcc::Clocks) -> Self {
+        I2c2Recovery { mode, clocks }
+    }
+
+    /// 软件复位 I2C2，清除 BUSY 等错误状态，并按 mode 重新配置时序
+    /// 与 HAL 初始化时写入的寄存器一致
+    fn reinit(&self) {
+        // 调用方持有总线对象，此时没有其他代码访问 I2C2
+        let regs = unsafe { &*I2C2::ptr() };
+        regs.cr1.write(|w| w.pe().set_bit().swrst().set_bit());
+        regs.cr1.reset();
+
+        let pclk1 = self.clocks.pclk1().raw();
+        let pclk1_mhz = (pclk1 / 1_000_000) as u8;
+        regs.cr2.write(|w| unsafe { w.freq().bits(pclk1_mhz) });
+        regs.cr1.write(|w| w.pe().clear_bit());
+
+        match &self.mode {
+            i2c::Mode::Standard { frequency } => {
+                let ccr = (pclk1 / (frequency.raw() * 2)).max(4) as u16;
+                regs.trise.write(|w| w.trise().bits(pclk1_mhz + 1));
+                regs.ccr.write(|w| unsafe { w.ccr().bits(ccr) });
+            }
+            i2c::Mode::Fast {
+                frequency,
+                duty_cycle,
+            } => {
+                let (ccr, duty) = match duty_cycle {
+                    i2c::DutyCycle::Ratio2to1 => ((pclk1 / (frequency.raw() * 3)).max(1), false),
+                    i2c::DutyCycle::Ratio16to9 => ((pclk1 / (frequency.raw() * 25)).max(1), true),
+                };
+                let trise = (u32::from(pclk1_mhz) * 300 / 1000 + 1) as u8;
+                regs.trise.write(|w| w.trise().bits(trise));
+                regs.ccr.write(|w| unsafe {
+                    w.ccr().bits(ccr as u16).duty().bit(duty).f_s().set_bit()
+                });
+            }
+        }
+        regs.cr1.modify(|_, w| w.pe().set_bit());
+    }
+}
+
+impl<PINS> BusRecovery<BlockingI2c<I2C2, PINS>> for I2c2Recovery {
+    fn recover(&mut self, i2c: BlockingI2c<I2C2, PINS>) -> BlockingI2c<I2C2, PINS> {
+        let mut scl = GpiobOpenDrain { pin: 10 };
+        let mut sda = GpiobOpenDrain { pin: 11 };
+        scl.set_mode(GpiobOpenDrain::OUTPUT_OPEN_DRAIN);
+        sda.set_mode(GpiobOpenDrain::OUTPUT_OPEN_DRAIN);
+
+        // 100kHz 时钟的半个周期
+        let half_period = self.clocks.sysclk().raw() / 200_000;
+        clock_out(&mut scl, &mut sda, || cortex_m::asm::delay(half_period));
+
+        scl.set_mode(GpiobOpenDrain::ALTERNATE_OPEN_DRAIN);
+        sda.set_mode(GpiobOpenDrain::ALTERNATE_OPEN_DRAIN);
+
+        self.reinit();
+        i2c
+    }
+}
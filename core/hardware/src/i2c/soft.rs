@@ -0,0 +1,238 @@
+//! 软件模拟 I2C
+//! 使用两个开漏输出引脚模拟 I2C 时序，可用于任意引脚
+//! ```rust
+//! let mut scl = gpiob.pb10.into_open_drain_output(&mut gpiob.crh);
+//! let mut sda = gpiob.pb11.into_open_drain_output(&mut gpiob.crh);
+//! sda.set_speed(&mut gpiob.crh, gpio::IOPinSpeed::Mhz50);
+//! scl.set_speed(&mut gpiob.crh, gpio::IOPinSpeed::Mhz50);
+//! let i2c = SoftI2c::new(scl, sda, dp.TIM2.delay_us(&clocks));
+//! ```
+use super::recovery::{self, BusRecovery};
+
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+
+/// 软件 I2C 通信错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// 从机未应答
+    Nack,
+}
+
+/// 软件 I2C 总线
+pub struct SoftI2c<Scl, Sda, D> {
+    scl: Scl,
+    sda: Sda,
+    delay: D,
+}
+
+impl<Scl, Sda, D> SoftI2c<Scl, Sda, D>
+where
+    Scl: OutputPin,
+    <Scl as OutputPin>::Error: core::fmt::Debug,
+    Sda: InputPin + OutputPin,
+    <Sda as InputPin>::Error: core::fmt::Debug,
+    <Sda as OutputPin>::Error: core::fmt::Debug,
+    D: DelayUs<u32>,
+{
+    /// I2C 初始化
+    /// scl/sda: 开漏输出引脚
+    /// delay: 用于产生时序的延时，可使用独立的定时器以便 SysDelay 另作他用
+    pub fn new(scl: Scl, sda: Sda, delay: D) -> Self {
+        let mut i2c = SoftI2c { scl, sda, delay };
+        i2c.i2c_w_scl(1);
+        i2c.i2c_w_sda(1);
+        i2c
+    }
+
+    /// 释放引脚及延时
+    pub fn release(self) -> (Scl, Sda, D) {
+        (self.scl, self.sda, self.delay)
+    }
+
+    fn i2c_w_scl(&mut self, bit_value: u8) {
+        if bit_value == 0 {
+            self.scl.set_low().unwrap();
+        } else {
+            self.scl.set_high().unwrap();
+        }
+        self.delay.delay_us(10_u32);
+    }
+
+    fn i2c_w_sda(&mut self, bit_value: u8) {
+        if bit_value == 0 {
+            self.sda.set_low().unwrap();
+        } else {
+            self.sda.set_high().unwrap();
+        }
+        self.delay.delay_us(10_u32);
+    }
+
+    fn i2c_r_sda(&mut self) -> u8 {
+        let bit_value = self.sda.is_high().unwrap();
+        self.delay.delay_us(10_u32);
+        if bit_value {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// 产生 I2C 协议起始信号
+    pub fn i2c_start(&mut self) {
+        self.i2c_w_sda(1);
+        self.i2c_w_scl(1);
+        self.i2c_w_sda(0);
+        self.i2c_w_scl(0);
+    }
+
+    /// 产生 I2C 协议结束信号
+    pub fn i2c_stop(&mut self) {
+        self.i2c_w_sda(0);
+        self.i2c_w_scl(1);
+        self.i2c_w_sda(1);
+    }
+
+    /// 发送八位数据（不包含应答）
+    pub fn i2c_send_byte(&mut self, byte: u8) {
+        for i in 0..8 {
+            self.i2c_w_sda(byte & (0x80 >> i));
+            self.i2c_w_scl(1);
+            self.i2c_w_scl(0);
+        }
+    }
+
+    /// 读取八位数据（不包含应答）
+    pub fn i2c_receive_byte(&mut self) -> u8 {
+        self.i2c_w_sda(1);
+
+        let mut byte = 0x00;
+        for i in 0..8 {
+            self.i2c_w_scl(1);
+            if self.i2c_r_sda() == 1 {
+                byte |= 0x80 >> i;
+            }
+            self.i2c_w_scl(0);
+        }
+        byte
+    }
+
+    /// 发送应答信号
+    pub fn i2c_send_ack(&mut self, ack_bit: u8) {
+        self.i2c_w_sda(ack_bit);
+        self.i2c_w_scl(1);
+        self.i2c_w_scl(0);
+    }
+
+    /// 接收应答信号
+    pub fn i2c_receive_ack(&mut self) -> u8 {
+        self.i2c_w_sda(1);
+        self.i2c_w_scl(1);
+        let ack_bit = self.i2c_r_sda();
+        self.i2c_w_scl(0);
+        ack_bit
+    }
+
+    /// 发送八位数据并检查应答
+    fn send_byte_with_ack(&mut self, byte: u8) -> Result<(), Error> {
+        self.i2c_send_byte(byte);
+        if self.i2c_receive_ack() != 0 {
+            return Err(Error::Nack);
+        }
+        Ok(())
+    }
+
+    /// 发送写模式设备地址及数据
+    fn write_bytes(&mut self, address: u8, bytes: &[u8]) -> Result<(), Error> {
+        self.send_byte_with_ack(address << 1)?;
+        for byte in bytes {
+            self.send_byte_with_ack(*byte)?;
+        }
+        Ok(())
+    }
+
+    /// 发送读模式设备地址并读取数据，最后一个字节发送非应答信号
+    fn read_bytes(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Error> {
+        self.send_byte_with_ack(address << 1 | 0x01)?;
+
+        let len = buffer.len();
+        for (i, data) in buffer.iter_mut().enumerate() {
+            *data = self.i2c_receive_byte();
+            self.i2c_send_ack(if i + 1 == len { 1 } else { 0 });
+        }
+        Ok(())
+    }
+
+    /// 总线恢复
+    /// 返回 SDA 是否已被释放
+    pub fn recover_bus(&mut self) -> bool {
+        let delay = &mut self.delay;
+        recovery::clock_out(&mut self.scl, &mut self.sda, || delay.delay_us(5_u32))
+    }
+}
+
+impl<Scl, Sda, D> Write for SoftI2c<Scl, Sda, D>
+where
+    Scl: OutputPin,
+    <Scl as OutputPin>::Error: core::fmt::Debug,
+    Sda: InputPin + OutputPin,
+    <Sda as InputPin>::Error: core::fmt::Debug,
+    <Sda as OutputPin>::Error: core::fmt::Debug,
+    D: DelayUs<u32>,
+{
+    type Error = Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.i2c_start();
+        let result = self.write_bytes(address, bytes);
+        self.i2c_stop();
+        result
+    }
+}
+
+impl<Scl, Sda, D> WriteRead for SoftI2c<Scl, Sda, D>
+where
+    Scl: OutputPin,
+    <Scl as OutputPin>::Error: core::fmt::Debug,
+    Sda: InputPin + OutputPin,
+    <Sda as InputPin>::Error: core::fmt::Debug,
+    <Sda as OutputPin>::Error: core::fmt::Debug,
+    D: DelayUs<u32>,
+{
+    type Error = Error;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.i2c_start();
+        let result = self.write_bytes(address, bytes).and_then(|_| {
+            // 发送重复起始信号
+            self.i2c_start();
+            self.read_bytes(address, buffer)
+        });
+        self.i2c_stop();
+        result
+    }
+}
+
+/// 软件 I2C 总线恢复
+pub struct SoftRecovery;
+
+impl<Scl, Sda, D> BusRecovery<SoftI2c<Scl, Sda, D>> for SoftRecovery
+where
+    Scl: OutputPin,
+    <Scl as OutputPin>::Error: core::fmt::Debug,
+    Sda: InputPin + OutputPin,
+    <Sda as InputPin>::Error: core::fmt::Debug,
+    <Sda as OutputPin>::Error: core::fmt::Debug,
+    D: DelayUs<u32>,
+{
+    fn recover(&mut self, mut i2c: SoftI2c<Scl, Sda, D>) -> SoftI2c<Scl, Sda, D> {
+        i2c.recover_bus();
+        i2c
+    }
+}
@@ -0,0 +1,101 @@
+//! 非阻塞按键状态机
+//! 单个按键的消抖及事件判断由 `algorithm::key_event` 实现。
+//! 在定时器或 SysTick 中断中以固定周期调用 `tick`，在主循环中调用 `poll_event` 取出事件。
+//! 不同端口的引脚可以通过 `erase()` 转换为同一类型后放入数组。
+//! ```rust
+//! static KEYS: Mutex<RefCell<Option<Keys<ErasedPin<Input<PullUp>>, 2>>>> =
+//!     Mutex::new(RefCell::new(None));
+//!
+//! let key1 = gpiob.pb1.into_pull_up_input(&mut gpiob.crl);
+//! let key2 = gpiob.pb11.into_pull_up_input(&mut gpiob.crh);
+//! let keys = Keys::new([key1.erase(), key2.erase()], true, 10, KeyTiming::default());
+//! cortex_m::interrupt::free(|cs| KEYS.borrow(cs).replace(Some(keys)));
+//!
+//! // 每 10ms 的定时中断中
+//! cortex_m::interrupt::free(|cs| KEYS.borrow(cs).borrow_mut().as_mut().unwrap().tick());
+//!
+//! // 主循环中
+//! let event = cortex_m::interrupt::free(|cs| {
+//!     KEYS.borrow(cs).borrow_mut().as_mut().unwrap().poll_event()
+//! });
+//! ```
+use embedded_hal::digital::v2::InputPin;
+use heapless::Deque;
+
+pub use algorithm::key_event::{EventKind, KeyState, KeyTiming};
+
+/// 事件队列长度
+pub const EVENT_QUEUE_LEN: usize = 16;
+
+/// 按键事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    /// 按键序号，与创建时引脚数组的下标一致
+    pub key: usize,
+    pub kind: EventKind,
+}
+
+/// 多按键状态机
+pub struct Keys<P, const N: usize> {
+    pins: [P; N],
+    /// 低电平表示按下(上拉输入)
+    active_low: bool,
+    /// 调用 `tick` 的周期，单位：ms
+    tick_ms: u16,
+    timing: KeyTiming,
+    states: [KeyState; N],
+    events: Deque<Event, EVENT_QUEUE_LEN>,
+}
+
+impl<P, const N: usize> Keys<P, N>
+where
+    P: InputPin,
+    <P as InputPin>::Error: core::fmt::Debug,
+{
+    /// 创建按键状态机
+    /// pins: 按键引脚
+    /// active_low: 低电平表示按下，上拉输入时为 true
+    /// tick_ms: 调用 `tick` 的周期，单位：ms
+    pub fn new(pins: [P; N], active_low: bool, tick_ms: u16, timing: KeyTiming) -> Self {
+        Keys {
+            pins,
+            active_low,
+            tick_ms,
+            timing,
+            states: [KeyState::default(); N],
+            events: Deque::new(),
+        }
+    }
+
+    /// 采样所有按键并更新状态，在定时中断中周期调用
+    /// 事件队列已满时丢弃新的事件
+    pub fn tick(&mut self) {
+        for (key, (pin, state)) in self.pins.iter().zip(self.states.iter_mut()).enumerate() {
+            let raw_pressed = pin.is_low().unwrap() == self.active_low;
+            let events = &mut self.events;
+            state.update(raw_pressed, self.tick_ms, &self.timing, |kind| {
+                events.push_back(Event { key, kind }).ok();
+            });
+        }
+    }
+
+    /// 取出最早的事件
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    /// 按键当前是否按下(消抖后)
+    pub fn is_pressed(&self, key: usize) -> bool {
+        self.states[key].is_pressed()
+    }
+
+    /// 修改时间参数
+    pub fn set_timing(&mut self, timing: KeyTiming) {
+        self.timing = timing;
+    }
+
+    /// 释放按键引脚
+    pub fn release(self) -> [P; N] {
+        self.pins
+    }
+}
@@ -1,4 +1,9 @@
 //! KEY 按键工具库
+//! - `get_key_status`: 阻塞式检测单次按键
+//! - `Keys`: 非阻塞的按键状态机，在定时中断中周期调用，输出按下、松开、单击、双击、长按及连发事件
+pub mod event;
+
+pub use event::{Event, EventKind, KeyTiming, Keys};
 
 use embedded_hal::{digital::v2::InputPin, prelude::_embedded_hal_blocking_delay_DelayMs};
 use stm32f1xx_hal::timer::SysDelay;
@@ -1,12 +1,26 @@
 #![no_std]
 #![no_main]
 
-use panic_probe as _;
-
+pub mod adc;
+pub mod bkp;
+pub mod buzzer;
+pub mod clock;
+pub mod diag;
+pub mod dma;
+pub mod encoder;
 pub mod flash_store;
+pub mod i2c;
 pub mod key;
+pub mod motor;
 pub mod mpu6050;
 pub mod oled;
+pub mod pid;
+pub mod power;
+pub mod rtc;
 pub mod serial;
+pub mod servo;
 pub mod syst;
 pub mod w25q64;
+pub mod watchdog;
+
+pub use algorithm::soft_timer;
@@ -0,0 +1,257 @@
+//! TB6612 直流电机驱动
+//! - IN1/IN2 控制方向，PWM 控制速度
+//! - IN1=IN2=高电平时短路制动，IN1=IN2=低电平时停止输出(滑行)
+//! - STBY 低电平时芯片待机，两路输出均关闭
+//!
+//! 占空比为有符号数，范围 -DUTY_MAX~DUTY_MAX，负数表示反转。
+//! ```rust
+//! let pwma = gpioa.pa2.into_alternate_push_pull(&mut gpioa.crl);
+//! let mut pwm = tim2.pwm_hz::<Tim2NoRemap, _, _>(pwma, &mut afio.mapr, 10.kHz(), &clocks);
+//! let mut motor = Motor::new(ain1, ain2, pwm.split());
+//! motor.set_ramp(10);
+//! motor.set_target(500);
+//!
+//! // 每 10ms 的定时中断中
+//! motor.tick();
+//! ```
+use embedded_hal::digital::v2::OutputPin;
+use embedded_hal::PwmPin;
+
+/// 占空比满量程
+pub const DUTY_MAX: i16 = 1000;
+
+/// 占空比为 0 时的停止方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopMode {
+    /// 短路制动，电机快速停止
+    Brake,
+    /// 停止输出，电机靠惯性停止
+    Coast,
+}
+
+/// 单路电机
+pub struct Motor<In1, In2, P> {
+    in1: In1,
+    in2: In2,
+    pwm: P,
+    /// 当前占空比
+    duty: i16,
+    /// 目标占空比
+    target: i16,
+    /// 每次 `tick` 占空比的最大变化量，为 0 时不限制
+    ramp: u16,
+    stop_mode: StopMode,
+    /// 反转方向，用于电机安装方向相反的场景
+    reversed: bool,
+}
+
+impl<In1, In2, P> Motor<In1, In2, P>
+where
+    In1: OutputPin,
+    <In1 as OutputPin>::Error: core::fmt::Debug,
+    In2: OutputPin,
+    <In2 as OutputPin>::Error: core::fmt::Debug,
+    P: PwmPin<Duty = u16>,
+{
+    /// 创建电机并使能 PWM 输出，初始为停止状态
+    pub fn new(in1: In1, in2: In2, pwm: P) -> Self {
+        let mut motor = Motor {
+            in1,
+            in2,
+            pwm,
+            duty: 0,
+            target: 0,
+            ramp: 0,
+            stop_mode: StopMode::Coast,
+            reversed: false,
+        };
+        motor.pwm.enable();
+        motor.apply();
+        motor
+    }
+
+    /// 根据当前占空比设置方向引脚及 PWM
+    fn apply(&mut self) {
+        let duty = if self.reversed { -self.duty } else { self.duty };
+        if duty > 0 {
+            self.in1.set_high().unwrap();
+            self.in2.set_low().unwrap();
+        } else if duty < 0 {
+            self.in1.set_low().unwrap();
+            self.in2.set_high().unwrap();
+        } else {
+            match self.stop_mode {
+                StopMode::Brake => {
+                    self.in1.set_high().unwrap();
+                    self.in2.set_high().unwrap();
+                }
+                StopMode::Coast => {
+                    self.in1.set_low().unwrap();
+                    self.in2.set_low().unwrap();
+                }
+            }
+        }
+
+        let max_duty = self.pwm.get_max_duty() as u32;
+        let value = max_duty * duty.unsigned_abs() as u32 / DUTY_MAX as u32;
+        self.pwm.set_duty(value as u16);
+    }
+
+    /// 立即设置占空比，同时作为目标占空比
+    pub fn set_duty(&mut self, duty: i16) {
+        let duty = duty.clamp(-DUTY_MAX, DUTY_MAX);
+        self.target = duty;
+        self.duty = duty;
+        self.apply();
+    }
+
+    /// 设置目标占空比，由 `tick` 按加速度逐步逼近
+    /// 未设置加速度时立即生效
+    pub fn set_target(&mut self, duty: i16) {
+        let duty = duty.clamp(-DUTY_MAX, DUTY_MAX);
+        if self.ramp == 0 {
+            self.set_duty(duty);
+        } else {
+            self.target = duty;
+        }
+    }
+
+    /// 设置加速度，即每次 `tick` 占空比的最大变化量，为 0 时不限制
+    pub fn set_ramp(&mut self, ramp: u16) {
+        self.ramp = ramp;
+    }
+
+    /// 向目标占空比变化一步，在定时中断中周期调用
+    pub fn tick(&mut self) {
+        if self.duty == self.target {
+            return;
+        }
+        let step = if self.ramp == 0 {
+            i16::MAX
+        } else {
+            self.ramp.min(i16::MAX as u16) as i16
+        };
+        let diff = self.target - self.duty;
+        self.duty += diff.clamp(-step, step);
+        self.apply();
+    }
+
+    /// 当前占空比
+    pub fn duty(&self) -> i16 {
+        self.duty
+    }
+
+    /// 目标占空比
+    pub fn target(&self) -> i16 {
+        self.target
+    }
+
+    /// 是否已达到目标占空比
+    pub fn is_settled(&self) -> bool {
+        self.duty == self.target
+    }
+
+    /// 设置占空比为 0 时的停止方式
+    pub fn set_stop_mode(&mut self, stop_mode: StopMode) {
+        self.stop_mode = stop_mode;
+        self.apply();
+    }
+
+    /// 反转电机方向
+    pub fn set_reversed(&mut self, reversed: bool) {
+        self.reversed = reversed;
+        self.apply();
+    }
+
+    /// 短路制动，立即停止
+    pub fn brake(&mut self) {
+        self.stop_mode = StopMode::Brake;
+        self.set_duty(0);
+    }
+
+    /// 停止输出，电机靠惯性停止
+    pub fn coast(&mut self) {
+        self.stop_mode = StopMode::Coast;
+        self.set_duty(0);
+    }
+
+    /// 停止输出并释放引脚
+    pub fn release(mut self) -> (In1, In2, P) {
+        self.coast();
+        self.pwm.disable();
+        (self.in1, self.in2, self.pwm)
+    }
+}
+
+/// TB6612 双路驱动板
+pub struct Tb6612<A, B, Stby> {
+    /// A 路电机
+    pub a: A,
+    /// B 路电机
+    pub b: B,
+    stby: Stby,
+}
+
+impl<AIn1, AIn2, AP, BIn1, BIn2, BP, Stby>
+    Tb6612<Motor<AIn1, AIn2, AP>, Motor<BIn1, BIn2, BP>, Stby>
+where
+    AIn1: OutputPin,
+    <AIn1 as OutputPin>::Error: core::fmt::Debug,
+    AIn2: OutputPin,
+    <AIn2 as OutputPin>::Error: core::fmt::Debug,
+    AP: PwmPin<Duty = u16>,
+    BIn1: OutputPin,
+    <BIn1 as OutputPin>::Error: core::fmt::Debug,
+    BIn2: OutputPin,
+    <BIn2 as OutputPin>::Error: core::fmt::Debug,
+    BP: PwmPin<Duty = u16>,
+    Stby: OutputPin,
+    <Stby as OutputPin>::Error: core::fmt::Debug,
+{
+    /// 创建驱动板并退出待机
+    pub fn new(a: Motor<AIn1, AIn2, AP>, b: Motor<BIn1, BIn2, BP>, stby: Stby) -> Self {
+        let mut driver = Tb6612 { a, b, stby };
+        driver.wake();
+        driver
+    }
+
+    /// 退出待机
+    pub fn wake(&mut self) {
+        self.stby.set_high().unwrap();
+    }
+
+    /// 进入待机，两路输出均关闭
+    pub fn standby(&mut self) {
+        self.stby.set_low().unwrap();
+    }
+
+    /// 同时设置两路目标占空比
+    pub fn set_target(&mut self, a: i16, b: i16) {
+        self.a.set_target(a);
+        self.b.set_target(b);
+    }
+
+    /// 两路电机均向目标占空比变化一步
+    pub fn tick(&mut self) {
+        self.a.tick();
+        self.b.tick();
+    }
+
+    /// 两路电机均短路制动
+    pub fn brake(&mut self) {
+        self.a.brake();
+        self.b.brake();
+    }
+
+    /// 两路电机均停止输出
+    pub fn coast(&mut self) {
+        self.a.coast();
+        self.b.coast();
+    }
+
+    /// 进入待机并释放电机及 STBY 引脚
+    pub fn release(mut self) -> (Motor<AIn1, AIn2, AP>, Motor<BIn1, BIn2, BP>, Stby) {
+        self.standby();
+        (self.a, self.b, self.stby)
+    }
+}
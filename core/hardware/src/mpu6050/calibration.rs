@@ -0,0 +1,220 @@
+//! 零偏校准
+//! 在板子静止且水平放置(Z 轴朝上)时采集若干组数据求平均，
+//! 得到陀螺仪零偏和加速度计偏移。
+//! 偏移量以校准时所配置量程下的原始值表示，更换量程后需要重新校准。
+//!
+//! ```rust
+//! let store = FlashStore::new();
+//! store.init_store();
+//! let offsets = match Offsets::load(&store, 1) {
+//!     Some(offsets) => offsets,
+//!     None => {
+//!         let offsets = mpu.calibrate(200, &mut delay).unwrap();
+//!         offsets.save(&store, 1);
+//!         offsets
+//!     }
+//! };
+//! mpu.set_offsets(offsets);
+//! ```
+use super::conf::{FifoSources, Mpu6050Config};
+use super::AccelGyroData;
+use crate::flash_store::FlashStore;
+
+/// 存储标志位，用于判断是否已保存校准数据
+const OFFSETS_STORE_FLAG: u16 = 0x6050;
+/// 存储占用的半字个数: 标志位 + 6 个偏移量 + 校验和
+pub const OFFSETS_STORE_LEN: usize = 8;
+
+/// 静止判定时陀螺仪允许的最大波动，单位：°/s
+const MAX_GYRO_SPREAD_DPS: f32 = 5.0;
+
+/// 校准错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationError {
+    /// 未采集到数据
+    NoSample,
+    /// 采样期间板子发生了移动
+    Moving,
+}
+
+/// 各轴偏移量
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Offsets {
+    pub acc_x: i16,
+    pub acc_y: i16,
+    pub acc_z: i16,
+    pub gyro_x: i16,
+    pub gyro_y: i16,
+    pub gyro_z: i16,
+}
+
+impl Offsets {
+    fn to_words(self) -> [u16; 6] {
+        [
+            self.acc_x as u16,
+            self.acc_y as u16,
+            self.acc_z as u16,
+            self.gyro_x as u16,
+            self.gyro_y as u16,
+            self.gyro_z as u16,
+        ]
+    }
+
+    fn checksum(words: &[u16]) -> u16 {
+        words.iter().fold(OFFSETS_STORE_FLAG, |acc, w| acc ^ w)
+    }
+
+    /// 从原始数据中减去偏移量
+    pub fn apply(&self, data: &mut AccelGyroData) {
+        data.acc_x = data.acc_x.saturating_sub(self.acc_x);
+        data.acc_y = data.acc_y.saturating_sub(self.acc_y);
+        data.acc_z = data.acc_z.saturating_sub(self.acc_z);
+        data.gyro_x = data.gyro_x.saturating_sub(self.gyro_x);
+        data.gyro_y = data.gyro_y.saturating_sub(self.gyro_y);
+        data.gyro_z = data.gyro_z.saturating_sub(self.gyro_z);
+    }
+
+    /// 只保留写入 FIFO 的数据的偏移量，其余为 0
+    pub fn masked(&self, sources: &FifoSources) -> Offsets {
+        let pick = |enabled: bool, offset: i16| if enabled { offset } else { 0 };
+        Offsets {
+            acc_x: pick(sources.accel, self.acc_x),
+            acc_y: pick(sources.accel, self.acc_y),
+            acc_z: pick(sources.accel, self.acc_z),
+            gyro_x: pick(sources.gyro_x, self.gyro_x),
+            gyro_y: pick(sources.gyro_y, self.gyro_y),
+            gyro_z: pick(sources.gyro_z, self.gyro_z),
+        }
+    }
+
+    /// 保存偏移量到闪存
+    /// 需要先调用 `FlashStore::init_store` 加载存储内容
+    /// start: 存储的起始下标，下标 0 为存储模块的标志位，不可使用
+    pub fn save(&self, store: &FlashStore, start: usize) {
+        let words = self.to_words();
+
+        store.set_store(start, OFFSETS_STORE_FLAG);
+        for (i, word) in words.iter().enumerate() {
+            store.set_store(start + 1 + i, *word);
+        }
+        store.set_store(start + 1 + words.len(), Offsets::checksum(&words));
+
+        store.store_save();
+    }
+
+    /// 从闪存加载偏移量
+    /// 没有保存过或者数据校验失败时返回 None
+    pub fn load(store: &FlashStore, start: usize) -> Option<Offsets> {
+        if store.get_store(start) != OFFSETS_STORE_FLAG {
+            return None;
+        }
+
+        let mut words = [0_u16; 6];
+        for (i, word) in words.iter_mut().enumerate() {
+            *word = store.get_store(start + 1 + i);
+        }
+        if store.get_store(start + 1 + words.len()) != Offsets::checksum(&words) {
+            return None;
+        }
+
+        Some(Offsets {
+            acc_x: words[0] as i16,
+            acc_y: words[1] as i16,
+            acc_z: words[2] as i16,
+            gyro_x: words[3] as i16,
+            gyro_y: words[4] as i16,
+            gyro_z: words[5] as i16,
+        })
+    }
+}
+
+/// 校准数据累加器
+pub struct Calibrator {
+    config: Mpu6050Config,
+    count: i32,
+    sum: [i32; 6],
+    gyro_min: [i16; 3],
+    gyro_max: [i16; 3],
+}
+
+impl Calibrator {
+    /// 创建校准器
+    /// config: 传感器当前配置，用于计算重力加速度对应的原始值
+    pub fn new(config: Mpu6050Config) -> Self {
+        Calibrator {
+            config,
+            count: 0,
+            sum: [0; 6],
+            gyro_min: [i16::MAX; 3],
+            gyro_max: [i16::MIN; 3],
+        }
+    }
+
+    /// 添加一组采样数据
+    pub fn add_sample(&mut self, data: &AccelGyroData) {
+        let values = [
+            data.acc_x,
+            data.acc_y,
+            data.acc_z,
+            data.gyro_x,
+            data.gyro_y,
+            data.gyro_z,
+        ];
+        for (sum, value) in self.sum.iter_mut().zip(values) {
+            *sum += value as i32;
+        }
+        for (i, value) in values[3..].iter().enumerate() {
+            self.gyro_min[i] = self.gyro_min[i].min(*value);
+            self.gyro_max[i] = self.gyro_max[i].max(*value);
+        }
+        self.count += 1;
+    }
+
+    /// 已采集的数据组数
+    pub fn count(&self) -> i32 {
+        self.count
+    }
+
+    /// 计算偏移量
+    /// 加速度计 Z 轴扣除 1g 的重力加速度
+    pub fn finish(&self) -> Result<Offsets, CalibrationError> {
+        if self.count == 0 {
+            return Err(CalibrationError::NoSample);
+        }
+
+        let max_spread = (MAX_GYRO_SPREAD_DPS * self.config.gyro_range.sensitivity()) as i32;
+        for i in 0..3 {
+            if self.gyro_max[i] as i32 - self.gyro_min[i] as i32 > max_spread {
+                return Err(CalibrationError::Moving);
+            }
+        }
+
+        let avg = self.sum.map(|sum| (sum / self.count) as i16);
+        let one_g = self.config.accel_range.sensitivity() as i16;
+
+        Ok(Offsets {
+            acc_x: avg[0],
+            acc_y: avg[1],
+            acc_z: avg[2].saturating_sub(one_g),
+            gyro_x: avg[3],
+            gyro_y: avg[4],
+            gyro_z: avg[5],
+        })
+    }
+}
+
+/// 计算写入 MPU6050 偏移寄存器的陀螺仪偏移值
+/// 偏移寄存器以 ±1000°/s 量程为单位，写入值会被加到测量结果上
+pub fn gyro_offset_register(config: &Mpu6050Config, offset: i16) -> i16 {
+    let scale = super::conf::GyroRange::D1000.sensitivity() / config.gyro_range.sensitivity();
+    -((offset as f32 * scale) as i16)
+}
+
+/// 计算写入 MPU6050 偏移寄存器的加速度计偏移值
+/// 偏移寄存器以 ±16g 量程为单位，最低位为温度补偿保留位需要保持原值
+/// factory: 从寄存器中读取的出厂偏移值
+pub fn accel_offset_register(config: &Mpu6050Config, factory: i16, offset: i16) -> i16 {
+    let scale = super::conf::AccelRange::G16.sensitivity() / config.accel_range.sensitivity();
+    let value = factory.wrapping_sub((offset as f32 * scale) as i16);
+    (value & !1) | (factory & 1)
+}
@@ -1,5 +1,19 @@
 //!配置
+// AD0 引脚接低电平时的从机地址
 pub const DEFAULT_SLAVE_ADDR: u8 = 0x68;
+// AD0 引脚接高电平时的从机地址
+pub const ALT_SLAVE_ADDR: u8 = 0x69;
+// WHO_AM_I 寄存器的值，与 AD0 引脚电平无关
+pub const MPU6050_DEVICE_ID: u8 = 0x68;
+
+// 加速度计偏移寄存器(出厂校准值，以±16g量程为单位)
+pub const MPU6050_XA_OFFS_H: u8 = 0x06;
+pub const MPU6050_YA_OFFS_H: u8 = 0x08;
+pub const MPU6050_ZA_OFFS_H: u8 = 0x0A;
+// 陀螺仪偏移寄存器(以±1000deg/s量程为单位)
+pub const MPU6050_XG_OFFS_USRH: u8 = 0x13;
+pub const MPU6050_YG_OFFS_USRH: u8 = 0x15;
+pub const MPU6050_ZG_OFFS_USRH: u8 = 0x17;
 
 // 采样率分频，典型值：0x07(125Hz)
 pub const MPU6050_SMPLRT_DIV: u8 = 0x19;
@@ -10,6 +24,25 @@ pub const MPU6050_GYRO_CONFIG: u8 = 0x1B;
 // 加速计自检、测量范围及高通滤波频率，典型值：0x01(不自检，2G，5Hz)
 pub const MPU6050_ACCEL_CONFIG: u8 = 0x1C;
 
+// 自由落体检测阈值(1LSB=2mg)及持续时间(1LSB=1ms)
+pub const MPU6050_FF_THR: u8 = 0x1D;
+pub const MPU6050_FF_DUR: u8 = 0x1E;
+// 运动检测阈值(1LSB=2mg)及持续时间(1LSB=1ms)
+pub const MPU6050_MOT_THR: u8 = 0x1F;
+pub const MPU6050_MOT_DUR: u8 = 0x20;
+// 静止检测阈值(1LSB=2mg)及持续时间(1LSB=64ms)
+pub const MPU6050_ZRMOT_THR: u8 = 0x21;
+pub const MPU6050_ZRMOT_DUR: u8 = 0x22;
+// FIFO 数据源使能
+pub const MPU6050_FIFO_EN: u8 = 0x23;
+
+// 中断引脚配置
+pub const MPU6050_INT_PIN_CFG: u8 = 0x37;
+// 中断使能
+pub const MPU6050_INT_ENABLE: u8 = 0x38;
+// 中断状态，读取后清除
+pub const MPU6050_INT_STATUS: u8 = 0x3A;
+
 // 存储最近的X轴、Y轴、Z轴加速度感应器的测量值
 pub const MPU6050_ACCEL_XOUT_H: u8 = 0x3B;
 pub const MPU6050_ACCEL_XOUT_L: u8 = 0x3C;
@@ -30,8 +63,483 @@ pub const MPU6050_GYRO_YOUT_L: u8 = 0x46;
 pub const MPU6050_GYRO_ZOUT_H: u8 = 0x47;
 pub const MPU6050_GYRO_ZOUT_L: u8 = 0x48;
 
+// 运动检测状态
+pub const MPU6050_MOT_DETECT_STATUS: u8 = 0x61;
+// 运动检测控制
+pub const MPU6050_MOT_DETECT_CTRL: u8 = 0x69;
+// 用户控制，FIFO 使能及复位
+pub const MPU6050_USER_CTRL: u8 = 0x6A;
+
 // 电源管理，典型值：0x00(正常启用)
 pub const MPU6050_PWR_MGMT_1: u8 = 0x6B;
 pub const MPU6050_PWR_MGMT_2: u8 = 0x6C;
+// FIFO 数据个数及读写寄存器
+pub const MPU6050_FIFO_COUNTH: u8 = 0x72;
+pub const MPU6050_FIFO_COUNTL: u8 = 0x73;
+pub const MPU6050_FIFO_R_W: u8 = 0x74;
 // IIC地址寄存器(默认数值0x68，只读)
 pub const MPU6050_WHO_AM_I: u8 = 0x75;
+
+/// FIFO 缓冲区大小，单位：字节
+pub const MPU6050_FIFO_SIZE: u16 = 1024;
+
+// USER_CTRL 寄存器位
+pub const USER_CTRL_FIFO_EN_BIT: u8 = 1 << 6;
+pub const USER_CTRL_FIFO_RESET_BIT: u8 = 1 << 2;
+
+// PWR_MGMT_1 寄存器位
+pub const PWR1_DEVICE_RESET_BIT: u8 = 1 << 7;
+pub const PWR1_SLEEP_BIT: u8 = 1 << 6;
+pub const PWR1_CYCLE_BIT: u8 = 1 << 5;
+pub const PWR1_TEMP_DIS_BIT: u8 = 1 << 3;
+// PWR_MGMT_2 寄存器位，陀螺仪 X/Y/Z 轴待机
+pub const PWR2_STBY_GYRO_BITS: u8 = 0x07;
+
+/// 从机地址，由 AD0 引脚电平决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlaveAddr {
+    /// AD0 接低电平，0x68
+    Ad0Low,
+    /// AD0 接高电平，0x69
+    Ad0High,
+}
+
+impl SlaveAddr {
+    /// 7 位从机地址
+    pub fn addr(self) -> u8 {
+        match self {
+            SlaveAddr::Ad0Low => DEFAULT_SLAVE_ADDR,
+            SlaveAddr::Ad0High => ALT_SLAVE_ADDR,
+        }
+    }
+}
+
+/// 加速度计量程
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccelRange {
+    /// ±2g
+    G2 = 0,
+    /// ±4g
+    G4 = 1,
+    /// ±8g
+    G8 = 2,
+    /// ±16g
+    G16 = 3,
+}
+
+impl AccelRange {
+    /// ACCEL_CONFIG 寄存器中的 AFS_SEL 位
+    pub fn bits(self) -> u8 {
+        (self as u8) << 3
+    }
+
+    /// 灵敏度，单位：LSB/g
+    pub fn sensitivity(self) -> f32 {
+        match self {
+            AccelRange::G2 => 16384.0,
+            AccelRange::G4 => 8192.0,
+            AccelRange::G8 => 4096.0,
+            AccelRange::G16 => 2048.0,
+        }
+    }
+}
+
+/// 加速度计数字高通滤波器(DHPF)，仅用于运动检测
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccelHpf {
+    /// 关闭
+    Reset = 0,
+    /// 5Hz
+    Hz5 = 1,
+    /// 2.5Hz
+    Hz2_5 = 2,
+    /// 1.25Hz
+    Hz1_25 = 3,
+    /// 0.63Hz
+    Hz0_63 = 4,
+    /// 保持当前采样值，之后输出与其的差值
+    Hold = 7,
+}
+
+impl AccelHpf {
+    /// ACCEL_CONFIG 寄存器中的 ACCEL_HPF 位
+    pub fn bits(self) -> u8 {
+        self as u8
+    }
+}
+
+/// 陀螺仪量程
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GyroRange {
+    /// ±250°/s
+    D250 = 0,
+    /// ±500°/s
+    D500 = 1,
+    /// ±1000°/s
+    D1000 = 2,
+    /// ±2000°/s
+    D2000 = 3,
+}
+
+impl GyroRange {
+    /// GYRO_CONFIG 寄存器中的 FS_SEL 位
+    pub fn bits(self) -> u8 {
+        (self as u8) << 3
+    }
+
+    /// 灵敏度，单位：LSB/(°/s)
+    pub fn sensitivity(self) -> f32 {
+        match self {
+            GyroRange::D250 => 131.0,
+            GyroRange::D500 => 65.5,
+            GyroRange::D1000 => 32.8,
+            GyroRange::D2000 => 16.4,
+        }
+    }
+}
+
+/// 数字低通滤波器(DLPF)带宽
+/// 带宽为加速度计/陀螺仪的近似值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DlpfBandwidth {
+    /// 260Hz/256Hz，关闭滤波，陀螺仪输出频率为 8kHz
+    Hz260 = 0,
+    /// 184Hz/188Hz
+    Hz184 = 1,
+    /// 94Hz/98Hz
+    Hz94 = 2,
+    /// 44Hz/42Hz
+    Hz44 = 3,
+    /// 21Hz/20Hz
+    Hz21 = 4,
+    /// 10Hz/10Hz
+    Hz10 = 5,
+    /// 5Hz/5Hz
+    Hz5 = 6,
+}
+
+impl DlpfBandwidth {
+    /// CONFIG 寄存器中的 DLPF_CFG 位
+    pub fn bits(self) -> u8 {
+        self as u8
+    }
+
+    /// 陀螺仪输出频率，单位：Hz
+    pub fn gyro_output_rate(self) -> u32 {
+        match self {
+            DlpfBandwidth::Hz260 => 8000,
+            _ => 1000,
+        }
+    }
+}
+
+/// 时钟源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockSource {
+    /// 内部 8MHz 振荡器
+    Internal = 0,
+    /// X 轴陀螺仪 PLL，推荐使用
+    PllGyroX = 1,
+    /// Y 轴陀螺仪 PLL
+    PllGyroY = 2,
+    /// Z 轴陀螺仪 PLL
+    PllGyroZ = 3,
+    /// 外部 32.768kHz PLL
+    PllExt32K = 4,
+    /// 外部 19.2MHz PLL
+    PllExt19M = 5,
+    /// 停止时钟
+    Stop = 7,
+}
+
+impl ClockSource {
+    /// PWR_MGMT_1 寄存器中的 CLKSEL 位
+    pub fn bits(self) -> u8 {
+        self as u8
+    }
+}
+
+/// 循环模式下的唤醒频率
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WakeFrequency {
+    /// 1.25Hz
+    Hz1_25 = 0,
+    /// 5Hz
+    Hz5 = 1,
+    /// 20Hz
+    Hz20 = 2,
+    /// 40Hz
+    Hz40 = 3,
+}
+
+impl WakeFrequency {
+    /// PWR_MGMT_2 寄存器中的 LP_WAKE_CTRL 位
+    pub fn bits(self) -> u8 {
+        (self as u8) << 6
+    }
+}
+
+/// 电源模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerMode {
+    /// 正常工作
+    Normal,
+    /// 休眠模式
+    Sleep,
+    /// 循环模式，周期性唤醒采样加速度，陀螺仪处于待机状态
+    Cycle(WakeFrequency),
+}
+
+/// MPU6050 配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mpu6050Config {
+    /// 加速度计量程
+    pub accel_range: AccelRange,
+    /// 加速度计高通滤波器
+    pub accel_hpf: AccelHpf,
+    /// 陀螺仪量程
+    pub gyro_range: GyroRange,
+    /// 数字低通滤波器带宽
+    pub dlpf: DlpfBandwidth,
+    /// 采样率分频，采样率 = 陀螺仪输出频率 / (1 + 分频值)
+    pub sample_rate_div: u8,
+    /// 时钟源
+    pub clock_source: ClockSource,
+    /// 电源模式
+    pub power_mode: PowerMode,
+    /// 是否关闭温度传感器
+    pub temp_disable: bool,
+}
+
+impl Default for Mpu6050Config {
+    /// 默认配置: 100Hz 采样率，5Hz 低通滤波，±16g，±2000°/s
+    fn default() -> Self {
+        Mpu6050Config {
+            accel_range: AccelRange::G16,
+            accel_hpf: AccelHpf::Reset,
+            gyro_range: GyroRange::D2000,
+            dlpf: DlpfBandwidth::Hz5,
+            sample_rate_div: 0x09,
+            clock_source: ClockSource::PllGyroX,
+            power_mode: PowerMode::Normal,
+            temp_disable: false,
+        }
+    }
+}
+
+impl Mpu6050Config {
+    /// 采样率，单位：Hz
+    pub fn sample_rate(&self) -> u32 {
+        self.dlpf.gyro_output_rate() / (1 + self.sample_rate_div as u32)
+    }
+
+    /// PWR_MGMT_1 寄存器值
+    pub fn pwr_mgmt_1(&self) -> u8 {
+        let mut value = self.clock_source.bits();
+        match self.power_mode {
+            PowerMode::Normal => {}
+            PowerMode::Sleep => value |= PWR1_SLEEP_BIT,
+            PowerMode::Cycle(_) => value |= PWR1_CYCLE_BIT,
+        }
+        if self.temp_disable {
+            value |= PWR1_TEMP_DIS_BIT;
+        }
+        value
+    }
+
+    /// PWR_MGMT_2 寄存器值
+    pub fn pwr_mgmt_2(&self) -> u8 {
+        match self.power_mode {
+            PowerMode::Cycle(freq) => freq.bits() | PWR2_STBY_GYRO_BITS,
+            _ => 0x00,
+        }
+    }
+
+    /// 按写入顺序生成的寄存器地址及其值
+    pub fn registers(&self) -> [(u8, u8); 6] {
+        [
+            (MPU6050_PWR_MGMT_1, self.pwr_mgmt_1()),
+            (MPU6050_PWR_MGMT_2, self.pwr_mgmt_2()),
+            (MPU6050_SMPLRT_DIV, self.sample_rate_div),
+            (MPU6050_CONFIG, self.dlpf.bits()),
+            (MPU6050_GYRO_CONFIG, self.gyro_range.bits()),
+            (
+                MPU6050_ACCEL_CONFIG,
+                self.accel_range.bits() | self.accel_hpf.bits(),
+            ),
+        ]
+    }
+}
+
+/// 中断引脚配置
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InterruptPinConfig {
+    /// 低电平有效，默认高电平有效
+    pub active_low: bool,
+    /// 开漏输出，默认推挽输出
+    pub open_drain: bool,
+    /// 保持中断电平直到清除，默认输出 50us 脉冲
+    pub latch: bool,
+    /// 任意读操作清除中断，默认只有读取 INT_STATUS 才清除
+    pub clear_on_any_read: bool,
+}
+
+impl InterruptPinConfig {
+    /// INT_PIN_CFG 寄存器值
+    pub fn bits(&self) -> u8 {
+        let mut value = 0;
+        if self.active_low {
+            value |= 1 << 7;
+        }
+        if self.open_drain {
+            value |= 1 << 6;
+        }
+        if self.latch {
+            value |= 1 << 5;
+        }
+        if self.clear_on_any_read {
+            value |= 1 << 4;
+        }
+        value
+    }
+}
+
+/// 中断源，用于中断使能及中断状态
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Interrupts {
+    /// 自由落体检测
+    pub free_fall: bool,
+    /// 运动检测
+    pub motion: bool,
+    /// 静止检测
+    pub zero_motion: bool,
+    /// FIFO 溢出
+    pub fifo_overflow: bool,
+    /// 数据就绪
+    pub data_ready: bool,
+}
+
+impl Interrupts {
+    /// INT_ENABLE/INT_STATUS 寄存器值
+    pub fn bits(&self) -> u8 {
+        let mut value = 0;
+        if self.free_fall {
+            value |= 1 << 7;
+        }
+        if self.motion {
+            value |= 1 << 6;
+        }
+        if self.zero_motion {
+            value |= 1 << 5;
+        }
+        if self.fifo_overflow {
+            value |= 1 << 4;
+        }
+        if self.data_ready {
+            value |= 1 << 0;
+        }
+        value
+    }
+
+    /// 解析 INT_ENABLE/INT_STATUS 寄存器值
+    pub fn from_bits(value: u8) -> Self {
+        Interrupts {
+            free_fall: value & (1 << 7) != 0,
+            motion: value & (1 << 6) != 0,
+            zero_motion: value & (1 << 5) != 0,
+            fifo_overflow: value & (1 << 4) != 0,
+            data_ready: value & (1 << 0) != 0,
+        }
+    }
+}
+
+/// 写入 FIFO 的数据源
+/// 每组数据按寄存器地址顺序排列：加速度、温度、X/Y/Z 轴角速度
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FifoSources {
+    pub accel: bool,
+    pub temp: bool,
+    pub gyro_x: bool,
+    pub gyro_y: bool,
+    pub gyro_z: bool,
+}
+
+impl FifoSources {
+    /// 加速度、温度及角速度全部写入 FIFO
+    pub fn all() -> Self {
+        FifoSources {
+            accel: true,
+            temp: true,
+            gyro_x: true,
+            gyro_y: true,
+            gyro_z: true,
+        }
+    }
+
+    /// FIFO_EN 寄存器值
+    pub fn bits(&self) -> u8 {
+        let mut value = 0;
+        if self.temp {
+            value |= 1 << 7;
+        }
+        if self.gyro_x {
+            value |= 1 << 6;
+        }
+        if self.gyro_y {
+            value |= 1 << 5;
+        }
+        if self.gyro_z {
+            value |= 1 << 4;
+        }
+        if self.accel {
+            value |= 1 << 3;
+        }
+        value
+    }
+
+    /// 每组数据的字节数
+    pub fn packet_len(&self) -> usize {
+        let mut len = 0;
+        if self.accel {
+            len += 6;
+        }
+        for enabled in [self.temp, self.gyro_x, self.gyro_y, self.gyro_z] {
+            if enabled {
+                len += 2;
+            }
+        }
+        len
+    }
+
+    /// 解析一组 FIFO 数据，未写入 FIFO 的数据为 0
+    pub fn decode(&self, packet: &[u8]) -> super::AccelGyroData {
+        let mut data = super::AccelGyroData::default();
+        let mut words = packet
+            .chunks_exact(2)
+            .map(|w| (w[0] as i16) << 8 | w[1] as i16);
+
+        if self.accel {
+            data.acc_x = words.next().unwrap_or_default();
+            data.acc_y = words.next().unwrap_or_default();
+            data.acc_z = words.next().unwrap_or_default();
+        }
+        if self.temp {
+            data.temp = words.next().unwrap_or_default();
+        }
+        if self.gyro_x {
+            data.gyro_x = words.next().unwrap_or_default();
+        }
+        if self.gyro_y {
+            data.gyro_y = words.next().unwrap_or_default();
+        }
+        if self.gyro_z {
+            data.gyro_z = words.next().unwrap_or_default();
+        }
+        data
+    }
+}
+
+/// 运动检测阈值换算，单位：mg，1LSB=2mg
+pub fn detect_threshold(threshold_mg: u16) -> u8 {
+    (threshold_mg / 2).min(u8::MAX as u16) as u8
+}
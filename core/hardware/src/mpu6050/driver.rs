@@ -0,0 +1,347 @@
+//! MPU6050 驱动
+//! 适用于任意实现了 embedded-hal `Write + WriteRead` 的 I2C 总线，
+//! 包括硬件 I2C(`BlockingI2c`)和软件模拟 I2C(`SoftI2c`)。
+//! ```rust
+//! let mode = i2c::Mode::standard(100.kHz());
+//! let i2c = BlockingI2c::i2c2(i2c2, (scl, sda), mode, clocks, 1000, 10, 1000, 1000);
+//! // Mode 不可复制，恢复需要单独构造一份
+//! let recovery = I2c2Recovery::new(i2c::Mode::standard(100.kHz()), clocks);
+//! let mut mpu = Mpu6050::with_recovery(i2c, SlaveAddr::Ad0Low, recovery);
+//! mpu.init(Mpu6050Config::default()).unwrap();
+//! ```
+use super::calibration::{self, CalibrationError, Calibrator, Offsets};
+use super::conf::*;
+use super::{AccelGyroData, ScaledData};
+use crate::i2c::{BusRecovery, NoRecovery};
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+/// MPU6050 错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E> {
+    /// I2C 通信错误
+    I2c(E),
+    /// WHO_AM_I 寄存器的值不正确，不是 MPU6050
+    InvalidDevice(u8),
+    /// 校准失败
+    Calibration(CalibrationError),
+}
+
+/// MPU6050 芯片
+pub struct Mpu6050<I2C, R = NoRecovery> {
+    /// 总线恢复期间会被临时取出
+    i2c: Option<I2C>,
+    address: u8,
+    recovery: R,
+    config: Mpu6050Config,
+    offsets: Offsets,
+    fifo_sources: FifoSources,
+}
+
+impl<I2C, E> Mpu6050<I2C, NoRecovery>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    /// 创建 MPU6050 驱动，通信出错时不进行总线恢复
+    pub fn new(i2c: I2C, address: SlaveAddr) -> Self {
+        Mpu6050::with_recovery(i2c, address, NoRecovery)
+    }
+}
+
+impl<I2C, E, R> Mpu6050<I2C, R>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    R: BusRecovery<I2C>,
+{
+    /// 创建 MPU6050 驱动，通信出错后使用 recovery 恢复总线
+    pub fn with_recovery(i2c: I2C, address: SlaveAddr, recovery: R) -> Self {
+        Mpu6050 {
+            i2c: Some(i2c),
+            address: address.addr(),
+            recovery,
+            config: Mpu6050Config::default(),
+            offsets: Offsets::default(),
+            fifo_sources: FifoSources::default(),
+        }
+    }
+
+    /// 释放 I2C 总线
+    pub fn release(self) -> I2C {
+        self.i2c.unwrap()
+    }
+
+    /// 初始化 MPU6050
+    /// 检查设备 ID 后唤醒芯片并写入配置
+    pub fn init(&mut self, config: Mpu6050Config) -> Result<(), Error<E>> {
+        let id = self.get_id()?;
+        if id != MPU6050_DEVICE_ID {
+            return Err(Error::InvalidDevice(id));
+        }
+        self.set_config(config)
+    }
+
+    /// 通信出错时恢复总线
+    fn check<T>(&mut self, result: Result<T, E>) -> Result<T, Error<E>> {
+        result.map_err(|e| {
+            if let Some(i2c) = self.i2c.take() {
+                self.i2c = Some(self.recovery.recover(i2c));
+            }
+            Error::I2c(e)
+        })
+    }
+
+    /// 写寄存器
+    pub fn write_reg(&mut self, reg_address: u8, data: u8) -> Result<(), Error<E>> {
+        let address = self.address;
+        let result = self
+            .i2c
+            .as_mut()
+            .unwrap()
+            .write(address, &[reg_address, data]);
+        self.check(result)
+    }
+
+    /// 连续写多个寄存器，data 为起始寄存器地址及写入的数据
+    fn write_regs(&mut self, data: &[u8]) -> Result<(), Error<E>> {
+        let address = self.address;
+        let result = self.i2c.as_mut().unwrap().write(address, data);
+        self.check(result)
+    }
+
+    /// 从寄存器开始连续读取数据
+    pub fn read_regs(&mut self, reg_address: u8, buffer: &mut [u8]) -> Result<(), Error<E>> {
+        let address = self.address;
+        let result = self
+            .i2c
+            .as_mut()
+            .unwrap()
+            .write_read(address, &[reg_address], buffer);
+        self.check(result)
+    }
+
+    /// 写入 MPU6050 配置
+    pub fn set_config(&mut self, config: Mpu6050Config) -> Result<(), Error<E>> {
+        for (reg_address, data) in config.registers() {
+            self.write_reg(reg_address, data)?;
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    /// 获取当前的 MPU6050 配置
+    pub fn config(&self) -> Mpu6050Config {
+        self.config
+    }
+
+    /// 切换电源模式
+    pub fn set_power_mode(&mut self, power_mode: PowerMode) -> Result<(), Error<E>> {
+        let config = Mpu6050Config {
+            power_mode,
+            ..self.config
+        };
+        self.write_reg(MPU6050_PWR_MGMT_1, config.pwr_mgmt_1())?;
+        self.write_reg(MPU6050_PWR_MGMT_2, config.pwr_mgmt_2())?;
+        self.config = config;
+        Ok(())
+    }
+
+    /// 获取 MPU6050 ID
+    pub fn get_id(&mut self) -> Result<u8, Error<E>> {
+        let mut buffer: [u8; 1] = [0; 1];
+        self.read_regs(MPU6050_WHO_AM_I, &mut buffer)?;
+        Ok(buffer[0])
+    }
+
+    /// 获取 MPU6050 数据
+    /// 读取加速度、温度和角速度数据
+    pub fn get_data(&mut self) -> Result<AccelGyroData, Error<E>> {
+        // 从mpu6050中读取14个字节的数据，包括加速度、温度和角速度
+        let mut buffer: [u8; 14] = [0; 14];
+        self.read_regs(MPU6050_ACCEL_XOUT_H, &mut buffer)?;
+
+        let mut data = FifoSources::all().decode(&buffer);
+        // 扣除校准偏移量
+        self.offsets.apply(&mut data);
+        Ok(data)
+    }
+
+    /// 获取物理单位的 MPU6050 数据
+    /// 按当前配置的量程换算
+    pub fn get_scaled_data(&mut self) -> Result<ScaledData, Error<E>> {
+        let data = self.get_data()?;
+        Ok(data.to_scaled(&self.config))
+    }
+
+    /// 设置软件校准偏移量，读取数据时自动扣除
+    pub fn set_offsets(&mut self, offsets: Offsets) {
+        self.offsets = offsets;
+    }
+
+    /// 获取当前的软件校准偏移量
+    pub fn offsets(&self) -> Offsets {
+        self.offsets
+    }
+
+    /// 零偏校准
+    /// 板子需要静止且水平放置，按采样周期连续采集 samples 组数据求平均
+    pub fn calibrate<D>(&mut self, samples: u16, delay: &mut D) -> Result<Offsets, Error<E>>
+    where
+        D: DelayMs<u32>,
+    {
+        // 采集不含偏移量的原始数据
+        let offsets = self.offsets;
+        self.offsets = Offsets::default();
+
+        let period_ms = (1000 / self.config.sample_rate()).max(1);
+        let mut calibrator = Calibrator::new(self.config);
+        for _ in 0..samples {
+            match self.get_data() {
+                Ok(data) => calibrator.add_sample(&data),
+                Err(e) => {
+                    self.offsets = offsets;
+                    return Err(e);
+                }
+            }
+            delay.delay_ms(period_ms);
+        }
+
+        self.offsets = offsets;
+        calibrator.finish().map_err(Error::Calibration)
+    }
+
+    /// 将偏移量写入 MPU6050 的偏移寄存器，由芯片直接输出校准后的数据
+    /// 写入后应将软件偏移量清零
+    pub fn write_offset_registers(&mut self, offsets: &Offsets) -> Result<(), Error<E>> {
+        // 读取加速度计的出厂偏移值
+        let mut buffer: [u8; 6] = [0; 6];
+        self.read_regs(MPU6050_XA_OFFS_H, &mut buffer)?;
+
+        let accel = [offsets.acc_x, offsets.acc_y, offsets.acc_z];
+        let mut data: [u8; 7] = [MPU6050_XA_OFFS_H, 0, 0, 0, 0, 0, 0];
+        for (i, offset) in accel.iter().enumerate() {
+            let factory = (buffer[i * 2] as i16) << 8 | buffer[i * 2 + 1] as i16;
+            let value = calibration::accel_offset_register(&self.config, factory, *offset);
+            data[1 + i * 2..3 + i * 2].copy_from_slice(&value.to_be_bytes());
+        }
+        self.write_regs(&data)?;
+
+        let gyro = [offsets.gyro_x, offsets.gyro_y, offsets.gyro_z];
+        let mut data: [u8; 7] = [MPU6050_XG_OFFS_USRH, 0, 0, 0, 0, 0, 0];
+        for (i, offset) in gyro.iter().enumerate() {
+            let value = calibration::gyro_offset_register(&self.config, *offset);
+            data[1 + i * 2..3 + i * 2].copy_from_slice(&value.to_be_bytes());
+        }
+        self.write_regs(&data)
+    }
+
+    /// 配置中断引脚的电平、输出方式及清除方式
+    pub fn set_interrupt_pin(&mut self, config: InterruptPinConfig) -> Result<(), Error<E>> {
+        self.write_reg(MPU6050_INT_PIN_CFG, config.bits())
+    }
+
+    /// 使能中断源
+    pub fn enable_interrupts(&mut self, interrupts: Interrupts) -> Result<(), Error<E>> {
+        self.write_reg(MPU6050_INT_ENABLE, interrupts.bits())
+    }
+
+    /// 读取中断状态，读取后中断标志被清除
+    pub fn interrupt_status(&mut self) -> Result<Interrupts, Error<E>> {
+        let mut buffer: [u8; 1] = [0; 1];
+        self.read_regs(MPU6050_INT_STATUS, &mut buffer)?;
+        Ok(Interrupts::from_bits(buffer[0]))
+    }
+
+    /// 配置运动检测
+    /// 需要同时配置加速度计高通滤波器
+    /// threshold_mg: 加速度变化阈值，单位：mg
+    /// duration_ms: 持续时间，单位：ms
+    pub fn set_motion_detection(
+        &mut self,
+        threshold_mg: u16,
+        duration_ms: u8,
+    ) -> Result<(), Error<E>> {
+        self.write_reg(MPU6050_MOT_THR, detect_threshold(threshold_mg))?;
+        self.write_reg(MPU6050_MOT_DUR, duration_ms)
+    }
+
+    /// 配置静止检测
+    /// threshold_mg: 加速度变化阈值，单位：mg
+    /// duration_ms: 持续时间，单位：ms，精度为 64ms
+    pub fn set_zero_motion_detection(
+        &mut self,
+        threshold_mg: u16,
+        duration_ms: u16,
+    ) -> Result<(), Error<E>> {
+        let duration = (duration_ms / 64).min(u8::MAX as u16) as u8;
+        self.write_reg(MPU6050_ZRMOT_THR, detect_threshold(threshold_mg))?;
+        self.write_reg(MPU6050_ZRMOT_DUR, duration)
+    }
+
+    /// 配置自由落体检测
+    /// threshold_mg: 三轴加速度均低于该阈值时判定为自由落体，单位：mg
+    /// duration_ms: 持续时间，单位：ms
+    pub fn set_free_fall_detection(
+        &mut self,
+        threshold_mg: u16,
+        duration_ms: u8,
+    ) -> Result<(), Error<E>> {
+        self.write_reg(MPU6050_FF_THR, detect_threshold(threshold_mg))?;
+        self.write_reg(MPU6050_FF_DUR, duration_ms)
+    }
+
+    /// 使能 FIFO，按采样率将选定的数据写入 FIFO
+    pub fn enable_fifo(&mut self, sources: FifoSources) -> Result<(), Error<E>> {
+        self.write_reg(MPU6050_FIFO_EN, sources.bits())?;
+        self.modify_user_ctrl(0, USER_CTRL_FIFO_EN_BIT | USER_CTRL_FIFO_RESET_BIT)?;
+        self.fifo_sources = sources;
+        Ok(())
+    }
+
+    /// 关闭 FIFO
+    pub fn disable_fifo(&mut self) -> Result<(), Error<E>> {
+        self.write_reg(MPU6050_FIFO_EN, 0x00)?;
+        self.modify_user_ctrl(USER_CTRL_FIFO_EN_BIT, 0)?;
+        self.fifo_sources = FifoSources::default();
+        Ok(())
+    }
+
+    /// 清空 FIFO，溢出后需要清空才能继续使用
+    pub fn reset_fifo(&mut self) -> Result<(), Error<E>> {
+        self.modify_user_ctrl(0, USER_CTRL_FIFO_EN_BIT | USER_CTRL_FIFO_RESET_BIT)
+    }
+
+    /// 读-改-写 USER_CTRL，保留 I2C_MST_EN、I2C_IF_DIS 等其它位
+    fn modify_user_ctrl(&mut self, clear: u8, set: u8) -> Result<(), Error<E>> {
+        let mut buffer: [u8; 1] = [0; 1];
+        self.read_regs(MPU6050_USER_CTRL, &mut buffer)?;
+        self.write_reg(MPU6050_USER_CTRL, (buffer[0] & !clear) | set)
+    }
+
+    /// FIFO 中的字节数
+    pub fn fifo_count(&mut self) -> Result<u16, Error<E>> {
+        let mut buffer: [u8; 2] = [0; 2];
+        self.read_regs(MPU6050_FIFO_COUNTH, &mut buffer)?;
+        Ok(u16::from_be_bytes(buffer))
+    }
+
+    /// 批量读取 FIFO 中累积的数据
+    /// 返回读取的数据组数
+    pub fn read_fifo(&mut self, samples: &mut [AccelGyroData]) -> Result<usize, Error<E>> {
+        let packet_len = self.fifo_sources.packet_len();
+        if packet_len == 0 {
+            return Ok(0);
+        }
+
+        let count = (self.fifo_count()? as usize / packet_len).min(samples.len());
+        // 只扣除写入 FIFO 的数据的校准偏移量
+        let offsets = self.offsets.masked(&self.fifo_sources);
+        let mut buffer: [u8; 14] = [0; 14];
+        for sample in samples[..count].iter_mut() {
+            self.read_regs(MPU6050_FIFO_R_W, &mut buffer[..packet_len])?;
+            *sample = self.fifo_sources.decode(&buffer[..packet_len]);
+            offsets.apply(sample);
+        }
+        Ok(count)
+    }
+}
@@ -0,0 +1,44 @@
+//! 姿态解算
+//! 滤波算法位于 algorithm 库，可在主机上测试；这里按传感器量程换算原始数据后输入滤波器。
+//! ```rust
+//! let mut fusion = Fusion::new(config, MadgwickFilter::new(0.01, 0.1));
+//! let data = mpu6050.get_data()?;
+//! let angles = fusion.update(&data);
+//! ```
+pub use algorithm::fusion::*;
+
+use super::conf::Mpu6050Config;
+use super::AccelGyroData;
+
+/// 输入原始数据的姿态滤波器
+pub struct Fusion<F> {
+    config: Mpu6050Config,
+    filter: F,
+}
+
+impl<F: AttitudeFilter> Fusion<F> {
+    /// config: 传感器配置，用于换算量程
+    pub fn new(config: Mpu6050Config, filter: F) -> Self {
+        Fusion { config, filter }
+    }
+
+    /// 输入原始数据并更新姿态角
+    pub fn update(&mut self, data: &AccelGyroData) -> EulerAngles {
+        self.filter.update(&data.to_scaled(&self.config))
+    }
+
+    /// 当前姿态角
+    pub fn angles(&self) -> EulerAngles {
+        self.filter.angles()
+    }
+
+    /// 重置姿态
+    pub fn reset(&mut self) {
+        self.filter.reset();
+    }
+
+    /// 内部滤波器，用于读取四元数等
+    pub fn filter(&self) -> &F {
+        &self.filter
+    }
+}
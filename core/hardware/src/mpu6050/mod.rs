@@ -1,9 +1,16 @@
-//! 软件I2C读写MPU6050
+//! I2C读写MPU6050
 //! MPU6050 是一个6轴姿态传感器，可以测量芯片自身X、Y、Z轴的加速度、角速度参数，
 //! 通过数据融合，可进一步得到姿态角，常应用于平衡车、飞行器等需要检测自身姿态的场景。
+pub mod calibration;
 pub mod conf;
-pub mod mpu6050_hal;
-pub mod mpu6050_reg;
+pub mod driver;
+pub mod fusion;
+
+pub use driver::{Error, Mpu6050};
+
+pub use algorithm::fusion::{ScaledData, STANDARD_GRAVITY};
+
+use conf::{AccelRange, GyroRange, Mpu6050Config};
 
 /// 加速度和角速度数据
 #[derive(Default)]
@@ -14,4 +21,53 @@ pub struct AccelGyroData {
     pub gyro_x: i16,
     pub gyro_y: i16,
     pub gyro_z: i16,
+    /// 芯片温度原始值
+    pub temp: i16,
+}
+
+impl AccelGyroData {
+    /// 加速度，单位：g
+    pub fn accel_g(&self, range: AccelRange) -> [f32; 3] {
+        let sensitivity = range.sensitivity();
+        [
+            self.acc_x as f32 / sensitivity,
+            self.acc_y as f32 / sensitivity,
+            self.acc_z as f32 / sensitivity,
+        ]
+    }
+
+    /// 加速度，单位：m/s²
+    pub fn accel_ms2(&self, range: AccelRange) -> [f32; 3] {
+        self.accel_g(range).map(|v| v * STANDARD_GRAVITY)
+    }
+
+    /// 角速度，单位：°/s
+    pub fn gyro_dps(&self, range: GyroRange) -> [f32; 3] {
+        let sensitivity = range.sensitivity();
+        [
+            self.gyro_x as f32 / sensitivity,
+            self.gyro_y as f32 / sensitivity,
+            self.gyro_z as f32 / sensitivity,
+        ]
+    }
+
+    /// 芯片温度，单位：°C
+    pub fn temperature(&self) -> f32 {
+        self.temp as f32 / 340.0 + 36.53
+    }
+
+    /// 按配置的量程转换为物理单位
+    pub fn to_scaled(&self, config: &Mpu6050Config) -> ScaledData {
+        let [acc_x, acc_y, acc_z] = self.accel_g(config.accel_range);
+        let [gyro_x, gyro_y, gyro_z] = self.gyro_dps(config.gyro_range);
+        ScaledData {
+            acc_x,
+            acc_y,
+            acc_z,
+            gyro_x,
+            gyro_y,
+            gyro_z,
+            temp: self.temperature(),
+        }
+    }
 }
@@ -0,0 +1,122 @@
+//! Q16.16 定点 PID
+//! 参数在创建时由浮点换算为离散形式(积分、微分系数已乘/除采样周期)，运行时只有整数运算。
+use super::PidGains;
+
+/// 小数位数
+pub const FRAC_BITS: u32 = 16;
+
+/// 浮点数转换为 Q16.16
+pub fn to_fixed(value: f32) -> i32 {
+    (value * (1 << FRAC_BITS) as f32) as i32
+}
+
+/// Q16.16 转换为浮点数
+pub fn from_fixed(value: i32) -> f32 {
+    value as f32 / (1 << FRAC_BITS) as f32
+}
+
+/// 定点 PID 控制器
+/// 输入输出均为整数，例如编码器计数增量及 PWM 占空比
+#[derive(Debug, Clone)]
+pub struct FixedPid {
+    /// 离散参数，Q16.16
+    kp: i32,
+    ki: i32,
+    kd: i32,
+    output_min: i32,
+    output_max: i32,
+    /// 微分一阶低通滤波，系数为 1/2^shift，为 0 时不滤波
+    derivative_shift: u8,
+    /// 积分项，Q16.16
+    integral: i64,
+    /// 滤波后的测量值变化量，Q16.16
+    derivative: i64,
+    last_measurement: Option<i32>,
+}
+
+impl FixedPid {
+    /// 创建控制器，默认输出范围为 i16 范围，不进行微分滤波
+    /// dt: 调用 `update` 的周期，单位：s
+    pub fn new(gains: PidGains, dt: f32) -> Self {
+        let mut pid = FixedPid {
+            kp: 0,
+            ki: 0,
+            kd: 0,
+            output_min: i16::MIN as i32,
+            output_max: i16::MAX as i32,
+            derivative_shift: 0,
+            integral: 0,
+            derivative: 0,
+            last_measurement: None,
+        };
+        pid.set_gains(gains, dt);
+        pid
+    }
+
+    /// 修改 PID 参数
+    pub fn set_gains(&mut self, gains: PidGains, dt: f32) {
+        self.kp = to_fixed(gains.kp);
+        self.ki = to_fixed(gains.ki * dt);
+        self.kd = to_fixed(gains.kd / dt);
+    }
+
+    /// PID 参数
+    pub fn gains(&self, dt: f32) -> PidGains {
+        PidGains {
+            kp: from_fixed(self.kp),
+            ki: from_fixed(self.ki) / dt,
+            kd: from_fixed(self.kd) * dt,
+        }
+    }
+
+    /// 设置输出范围，积分项同样限制在该范围内
+    pub fn set_output_limits(&mut self, min: i32, max: i32) {
+        self.output_min = min;
+        self.output_max = max;
+        self.integral = self.integral.clamp(Self::q(min), Self::q(max));
+    }
+
+    /// 设置微分滤波，系数为 1/2^shift
+    pub fn set_derivative_filter(&mut self, shift: u8) {
+        self.derivative_shift = shift.min(15);
+    }
+
+    /// 清除积分及微分状态
+    pub fn reset(&mut self) {
+        self.integral = 0;
+        self.derivative = 0;
+        self.last_measurement = None;
+    }
+
+    /// 整数转换为 Q16.16
+    fn q(value: i32) -> i64 {
+        (value as i64) << FRAC_BITS
+    }
+
+    /// 计算控制输出，以固定周期调用
+    pub fn update(&mut self, setpoint: i32, measurement: i32) -> i32 {
+        let error = setpoint as i64 - measurement as i64;
+        let p = self.kp as i64 * error;
+
+        // 对测量值求导并滤波
+        if let Some(last) = self.last_measurement {
+            let rate = Self::q(last) - Self::q(measurement);
+            self.derivative += (rate - self.derivative) >> self.derivative_shift;
+        }
+        self.last_measurement = Some(measurement);
+        let d = (self.kd as i64 * self.derivative) >> FRAC_BITS;
+
+        // 抗积分饱和
+        let min = Self::q(self.output_min);
+        let max = Self::q(self.output_max);
+        let integral = self.integral + self.ki as i64 * error;
+        let unclamped = p + integral + d;
+        let saturated_high = unclamped > max && error > 0;
+        let saturated_low = unclamped < min && error < 0;
+        if !saturated_high && !saturated_low {
+            self.integral = integral.clamp(min, max);
+        }
+
+        ((p + self.integral + d).clamp(min, max) >> FRAC_BITS) as i32
+    }
+}
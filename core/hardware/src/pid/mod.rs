@@ -0,0 +1,115 @@
+//! PID 控制器
+//! - `Pid`: 浮点 PID
+//! - `FixedPid`: Q16.16 定点 PID，适用于没有 FPU 且对中断耗时敏感的场景
+//! - `SpeedLoop`: 编码器测速与电机 PWM 组成的速度闭环
+//! - `tuning`: 通过串口在线调整参数
+//!
+//! 微分项对测量值求导，避免目标值突变时产生冲击；
+//! 积分项在输出饱和且误差方向使输出继续饱和时停止累加(抗积分饱和)。
+pub mod fixed;
+pub mod speed_loop;
+pub mod tuning;
+
+pub use fixed::FixedPid;
+pub use speed_loop::SpeedLoop;
+pub use tuning::{LineBuffer, TuneCommand};
+
+/// PID 参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PidGains {
+    /// 比例系数
+    pub kp: f32,
+    /// 积分系数，单位：1/s
+    pub ki: f32,
+    /// 微分系数，单位：s
+    pub kd: f32,
+}
+
+/// 浮点 PID 控制器
+#[derive(Debug, Clone)]
+pub struct Pid {
+    gains: PidGains,
+    /// 调用 `update` 的周期，单位：s
+    dt: f32,
+    output_min: f32,
+    output_max: f32,
+    /// 微分滤波系数，取值 0~1，为 1 时不滤波
+    derivative_alpha: f32,
+    /// 积分项(已乘积分系数)
+    integral: f32,
+    /// 滤波后的测量值变化率
+    derivative: f32,
+    /// 上次测量值
+    last_measurement: Option<f32>,
+}
+
+impl Pid {
+    /// 创建控制器，默认输出范围 -1.0~1.0，不进行微分滤波
+    /// dt: 调用 `update` 的周期，单位：s
+    pub fn new(gains: PidGains, dt: f32) -> Self {
+        Pid {
+            gains,
+            dt,
+            output_min: -1.0,
+            output_max: 1.0,
+            derivative_alpha: 1.0,
+            integral: 0.0,
+            derivative: 0.0,
+            last_measurement: None,
+        }
+    }
+
+    /// 设置输出范围，积分项同样限制在该范围内
+    pub fn set_output_limits(&mut self, min: f32, max: f32) {
+        self.output_min = min;
+        self.output_max = max;
+        self.integral = self.integral.clamp(min, max);
+    }
+
+    /// 设置微分一阶低通滤波系数，取值 0~1，越小滤波越强
+    pub fn set_derivative_filter(&mut self, alpha: f32) {
+        self.derivative_alpha = alpha.clamp(0.0, 1.0);
+    }
+
+    /// PID 参数
+    pub fn gains(&self) -> PidGains {
+        self.gains
+    }
+
+    /// 修改 PID 参数，积分项已乘积分系数，运行中修改不会造成输出跳变
+    pub fn set_gains(&mut self, gains: PidGains) {
+        self.gains = gains;
+    }
+
+    /// 清除积分及微分状态
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.derivative = 0.0;
+        self.last_measurement = None;
+    }
+
+    /// 计算控制输出，以固定周期调用
+    pub fn update(&mut self, setpoint: f32, measurement: f32) -> f32 {
+        let error = setpoint - measurement;
+        let p = self.gains.kp * error;
+
+        // 对测量值求导并滤波
+        if let Some(last) = self.last_measurement {
+            let rate = -(measurement - last) / self.dt;
+            self.derivative += self.derivative_alpha * (rate - self.derivative);
+        }
+        self.last_measurement = Some(measurement);
+        let d = self.gains.kd * self.derivative;
+
+        // 抗积分饱和: 输出饱和且误差会使其更饱和时不累加
+        let integral = self.integral + self.gains.ki * error * self.dt;
+        let unclamped = p + integral + d;
+        let saturated_high = unclamped > self.output_max && error > 0.0;
+        let saturated_low = unclamped < self.output_min && error < 0.0;
+        if !saturated_high && !saturated_low {
+            self.integral = integral.clamp(self.output_min, self.output_max);
+        }
+
+        (p + self.integral + d).clamp(self.output_min, self.output_max)
+    }
+}
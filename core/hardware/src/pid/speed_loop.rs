@@ -0,0 +1,128 @@
+//! 电机速度闭环
+//! 在定时中断中以固定周期调用 `update`：采样编码器转速，经 PID 计算后设置电机占空比。
+//! ```rust
+//! let encoder = QeiEncoder::new(qei, EncoderConfig { sample_period_ms: 10, ..Default::default() });
+//! let motor = Motor::new(ain1, ain2, pwm.split());
+//! let gains = PidGains { kp: 2.0, ki: 20.0, kd: 0.0 };
+//! let mut speed_loop = SpeedLoop::new(encoder, motor, gains, 0.01);
+//! speed_loop.set_target_rpm(120.0);
+//!
+//! // 每 10ms 的定时中断中
+//! speed_loop.update();
+//! ```
+use super::tuning::TuneCommand;
+use super::{Pid, PidGains};
+use crate::encoder::Encoder;
+use crate::motor::{Motor, DUTY_MAX};
+
+use embedded_hal::digital::v2::OutputPin;
+use embedded_hal::PwmPin;
+
+/// 速度闭环
+pub struct SpeedLoop<E, In1, In2, P> {
+    encoder: E,
+    motor: Motor<In1, In2, P>,
+    pid: Pid,
+    /// 目标转速，单位：转/分钟
+    target_rpm: f32,
+    /// 闭环是否运行
+    enabled: bool,
+}
+
+impl<E, In1, In2, P> SpeedLoop<E, In1, In2, P>
+where
+    E: Encoder,
+    In1: OutputPin,
+    <In1 as OutputPin>::Error: core::fmt::Debug,
+    In2: OutputPin,
+    <In2 as OutputPin>::Error: core::fmt::Debug,
+    P: PwmPin<Duty = u16>,
+{
+    /// 创建速度闭环，PID 输出范围为电机占空比范围
+    /// dt: 调用 `update` 的周期，单位：s，需与编码器采样周期一致
+    pub fn new(encoder: E, motor: Motor<In1, In2, P>, gains: PidGains, dt: f32) -> Self {
+        let mut pid = Pid::new(gains, dt);
+        pid.set_output_limits(-DUTY_MAX as f32, DUTY_MAX as f32);
+        SpeedLoop {
+            encoder,
+            motor,
+            pid,
+            target_rpm: 0.0,
+            enabled: true,
+        }
+    }
+
+    /// 采样转速并更新电机占空比，在定时中断中周期调用
+    pub fn update(&mut self) {
+        self.encoder.sample();
+        if !self.enabled {
+            return;
+        }
+        let output = self.pid.update(self.target_rpm, self.encoder.rpm());
+        self.motor.set_duty(output as i16);
+    }
+
+    /// 设置目标转速，单位：转/分钟
+    pub fn set_target_rpm(&mut self, rpm: f32) {
+        self.target_rpm = rpm;
+    }
+
+    /// 目标转速，单位：转/分钟
+    pub fn target_rpm(&self) -> f32 {
+        self.target_rpm
+    }
+
+    /// 当前转速，单位：转/分钟
+    pub fn rpm(&self) -> f32 {
+        self.encoder.rpm()
+    }
+
+    /// 当前占空比
+    pub fn duty(&self) -> i16 {
+        self.motor.duty()
+    }
+
+    /// 启动或停止闭环，停止时电机滑行并清除 PID 状态
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.motor.coast();
+            self.pid.reset();
+        }
+        self.enabled = enabled;
+    }
+
+    /// PID 参数
+    pub fn gains(&self) -> PidGains {
+        self.pid.gains()
+    }
+
+    /// PID 控制器，用于调整输出范围、微分滤波等
+    pub fn pid_mut(&mut self) -> &mut Pid {
+        &mut self.pid
+    }
+
+    /// 执行调参指令
+    pub fn apply(&mut self, command: TuneCommand) {
+        let mut gains = self.pid.gains();
+        match command {
+            TuneCommand::Kp(kp) => gains.kp = kp,
+            TuneCommand::Ki(ki) => gains.ki = ki,
+            TuneCommand::Kd(kd) => gains.kd = kd,
+            TuneCommand::Target(rpm) => self.target_rpm = rpm,
+            TuneCommand::Start => self.set_enabled(true),
+            TuneCommand::Stop => self.set_enabled(false),
+            TuneCommand::Query => {}
+        }
+        self.pid.set_gains(gains);
+    }
+
+    /// 编码器
+    pub fn encoder(&self) -> &E {
+        &self.encoder
+    }
+
+    /// 释放编码器及电机
+    pub fn release(self) -> (E, Motor<In1, In2, P>) {
+        (self.encoder, self.motor)
+    }
+}
@@ -0,0 +1,64 @@
+//! 串口在线调参
+//! 每行一条指令，以 `\n` 结束，不区分大小写，参数与名称之间可用空格或 `=` 分隔:
+//! - `kp 1.5` / `ki=20` / `kd 0.01`: 修改 PID 参数
+//! - `sp 120`: 修改目标值
+//! - `start` / `stop`: 启动或停止闭环
+//! - `?`: 查询当前参数
+//!
+//! ```rust
+//! // 串口接收中断中
+//! if line.push(byte) {
+//!     if let Some(command) = TuneCommand::parse(line.as_str()) {
+//!         speed_loop.apply(command);
+//!     }
+//!     line.clear();
+//! }
+//! ```
+pub use crate::serial::LineBuffer;
+
+/// 调参指令
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TuneCommand {
+    Kp(f32),
+    Ki(f32),
+    Kd(f32),
+    /// 目标值
+    Target(f32),
+    Start,
+    Stop,
+    /// 查询当前参数
+    Query,
+}
+
+impl TuneCommand {
+    /// 解析一行指令，无法识别时返回 None
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let mut parts = line.splitn(2, |c: char| c == '=' || c.is_ascii_whitespace());
+        let name = parts.next()?;
+        let value = parts.next().map(|v| v.trim().trim_start_matches('='));
+
+        if name.eq_ignore_ascii_case("?") {
+            return Some(TuneCommand::Query);
+        }
+        if name.eq_ignore_ascii_case("start") {
+            return Some(TuneCommand::Start);
+        }
+        if name.eq_ignore_ascii_case("stop") {
+            return Some(TuneCommand::Stop);
+        }
+
+        let value = value?.trim().parse::<f32>().ok()?;
+        if name.eq_ignore_ascii_case("kp") {
+            Some(TuneCommand::Kp(value))
+        } else if name.eq_ignore_ascii_case("ki") {
+            Some(TuneCommand::Ki(value))
+        } else if name.eq_ignore_ascii_case("kd") {
+            Some(TuneCommand::Kd(value))
+        } else if name.eq_ignore_ascii_case("sp") {
+            Some(TuneCommand::Target(value))
+        } else {
+            None
+        }
+    }
+}
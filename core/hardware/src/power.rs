@@ -0,0 +1,287 @@
+//! 低功耗管理
+//! - 睡眠: CPU 停止，外设继续运行，任意已使能的中断唤醒
+//! - 停止: 1.8V 域时钟全部停止，只能由 EXTI 线(引脚、RTC 闹钟)唤醒，唤醒后系统时钟为 HSI，由 `ClockControl` 恢复原预设
+//! - 待机: 1.8V 域断电，只能由 WKUP 引脚(PA0)上升沿、RTC 闹钟、NRST 或 IWDG 唤醒，唤醒后从复位开始执行
+//!
+//! 进入低功耗模式时屏蔽中断，唤醒后先记录唤醒原因、恢复时钟，再执行中断服务函数。
+//! 唤醒源对应的中断需在 NVIC 中使能，引脚触发边沿由应用配置。
+//! 串口在停止模式下没有时钟，可将 RX 引脚配置为下降沿外部中断唤醒，唤醒的首字节会丢失。
+//! ```rust
+//! let mut clock = ClockControl::new(dp.RCC, &mut flash.acr, Preset::Hse72);
+//! let mut low_power = LowPower::new();
+//!
+//! // 启动时检查是否从待机模式唤醒
+//! if let Boot::Standby(source) = power::check_boot() {}
+//!
+//! let source = low_power
+//!     .stop(&mut cp.SCB, &mut clock, &mut flash.acr, &[WakeSource::Pin(14)])
+//!     .unwrap();
+//!
+//! low_power.standby(&mut cp.SCB, &[WakeSource::WkupPin, WakeSource::RtcAlarm]);
+//! ```
+use core::convert::Infallible;
+
+use cortex_m::interrupt::InterruptNumber;
+use cortex_m::peripheral::{NVIC, SCB};
+use stm32f1xx_hal::flash::ACR;
+use stm32f1xx_hal::pac::{self, Interrupt};
+
+use crate::clock::{self, ClockControl};
+
+/// RTC 闹钟所在的 EXTI 线
+const EXTI_RTC_ALARM: u8 = 17;
+/// 等待 RTC 寄存器同步的最大查询次数，同步需要若干个 RTCCLK 周期
+const RTC_SYNC_POLLS: u32 = 100_000;
+
+/// 低功耗模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub enum Mode {
+    Sleep,
+    Stop,
+    Standby,
+}
+
+/// 唤醒源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WakeSource {
+    /// 外部中断引脚，EXTI0~EXTI15，睡眠及停止模式
+    Pin(u8),
+    /// RTC 闹钟，所有模式
+    RtcAlarm,
+    /// WKUP 引脚(PA0)上升沿，仅待机模式
+    WkupPin,
+    /// 串口中断，仅睡眠模式
+    Usart(Interrupt),
+}
+
+impl defmt::Format for WakeSource {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            WakeSource::Pin(line) => defmt::write!(f, "Pin({})", line),
+            WakeSource::RtcAlarm => defmt::write!(f, "RtcAlarm"),
+            WakeSource::WkupPin => defmt::write!(f, "WkupPin"),
+            WakeSource::Usart(interrupt) => defmt::write!(f, "Usart({})", interrupt.number()),
+        }
+    }
+}
+
+impl WakeSource {
+    /// 是否能从指定模式唤醒
+    pub fn supports(&self, mode: Mode) -> bool {
+        match self {
+            WakeSource::Pin(line) => *line < 16 && mode != Mode::Standby,
+            WakeSource::RtcAlarm => true,
+            WakeSource::WkupPin => mode == Mode::Standby,
+            WakeSource::Usart(interrupt) => {
+                mode == Mode::Sleep
+                    && matches!(
+                        interrupt,
+                        Interrupt::USART1 | Interrupt::USART2 | Interrupt::USART3
+                    )
+            }
+        }
+    }
+
+    /// 唤醒后是否由该源触发，中断服务函数执行前调用
+    fn is_pending(&self) -> bool {
+        match self {
+            WakeSource::Pin(line) => exti_pending(*line),
+            WakeSource::RtcAlarm => {
+                exti_pending(EXTI_RTC_ALARM) || rtc_regs().crl.read().alrf().bit_is_set()
+            }
+            WakeSource::WkupPin => pwr_regs().csr.read().wuf().bit_is_set(),
+            WakeSource::Usart(interrupt) => NVIC::is_pending(*interrupt),
+        }
+    }
+}
+
+/// 低功耗错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub enum Error {
+    /// 唤醒源不支持该模式
+    Unsupported(WakeSource),
+    /// 停止模式唤醒后恢复时钟失败，系统时钟仍为 HSI
+    Clock(clock::Error),
+}
+
+/// 启动状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub enum Boot {
+    /// 上电或普通复位
+    Normal,
+    /// 从待机模式唤醒，唤醒源为 None 时由 NRST 或 IWDG 唤醒
+    Standby(Option<WakeSource>),
+}
+
+fn pwr_regs() -> &'static pac::pwr::RegisterBlock {
+    unsafe { &*pac::PWR::ptr() }
+}
+
+fn rtc_regs() -> &'static pac::rtc::RegisterBlock {
+    unsafe { &*pac::RTC::ptr() }
+}
+
+fn exti_regs() -> &'static pac::exti::RegisterBlock {
+    unsafe { &*pac::EXTI::ptr() }
+}
+
+fn exti_pending(line: u8) -> bool {
+    exti_regs().pr.read().bits() & (1 << line) != 0
+}
+
+/// 使能 PWR 时钟
+fn enable_pwr_clock() {
+    let rcc = unsafe { &*pac::RCC::ptr() };
+    rcc.apb1enr.modify(|_, w| w.pwren().set_bit());
+}
+
+/// 等待 RTC 寄存器同步，清除 RSF 并等待硬件重新置位
+/// 复位或唤醒后 APB1 接口读到的 RTC 寄存器可能是旧值，同步后才能读取标志
+/// RTC 未使能或 RTC 时钟未运行导致超时时返回 false
+fn sync_rtc_registers() -> bool {
+    let rcc = unsafe { &*pac::RCC::ptr() };
+    if rcc.bdcr.read().rtcen().bit_is_clear() {
+        return false;
+    }
+    rcc.apb1enr.modify(|_, w| w.bkpen().set_bit());
+
+    // 清除 RSF 需要解除备份域写保护，完成后恢复原状态
+    let pwr = pwr_regs();
+    let protected = pwr.cr.read().dbp().bit_is_clear();
+    pwr.cr.modify(|_, w| w.dbp().set_bit());
+    let rtc = rtc_regs();
+    rtc.crl.modify(|_, w| w.rsf().clear_bit());
+    let synced = (0..RTC_SYNC_POLLS).any(|_| rtc.crl.read().rsf().bit_is_set());
+    if protected {
+        pwr.cr.modify(|_, w| w.dbp().clear_bit());
+    }
+    synced
+}
+
+/// 检查本次启动是否从待机模式唤醒，并清除 SBF 及 WUF
+/// 需在启动后尽早调用，RTC 闹钟标志被清除前才能区分唤醒源
+pub fn check_boot() -> Boot {
+    enable_pwr_clock();
+    let pwr = pwr_regs();
+    let csr = pwr.csr.read();
+    if csr.sbf().bit_is_clear() {
+        return Boot::Normal;
+    }
+
+    let alarm = sync_rtc_registers() && rtc_regs().crl.read().alrf().bit_is_set();
+    let source = if alarm {
+        Some(WakeSource::RtcAlarm)
+    } else if csr.wuf().bit_is_set() {
+        Some(WakeSource::WkupPin)
+    } else {
+        None
+    };
+    pwr.cr.modify(|_, w| w.csbf().set_bit().cwuf().set_bit());
+    Boot::Standby(source)
+}
+
+/// 低功耗管理
+pub struct LowPower {
+    /// 停止模式下电压调节器是否进入低功耗模式
+    low_power_regulator: bool,
+}
+
+impl Default for LowPower {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LowPower {
+    pub fn new() -> Self {
+        enable_pwr_clock();
+        LowPower {
+            low_power_regulator: true,
+        }
+    }
+
+    /// 停止模式下电压调节器是否进入低功耗模式，功耗更低但唤醒时间更长
+    pub fn set_low_power_regulator(&mut self, enable: bool) {
+        self.low_power_regulator = enable;
+    }
+
+    /// 进入睡眠模式，返回唤醒源，由其它中断唤醒时返回 None
+    pub fn sleep(
+        &mut self,
+        scb: &mut SCB,
+        sources: &[WakeSource],
+    ) -> Result<Option<WakeSource>, Error> {
+        check_sources(sources, Mode::Sleep)?;
+        scb.clear_sleepdeep();
+        Ok(cortex_m::interrupt::free(|_| {
+            cortex_m::asm::dsb();
+            cortex_m::asm::wfi();
+            find_source(sources)
+        }))
+    }
+
+    /// 进入停止模式，唤醒后按 `clock` 的当前预设恢复时钟，返回唤醒源
+    pub fn stop(
+        &mut self,
+        scb: &mut SCB,
+        clock: &mut ClockControl,
+        acr: &mut ACR,
+        sources: &[WakeSource],
+    ) -> Result<Option<WakeSource>, Error> {
+        check_sources(sources, Mode::Stop)?;
+        if sources.contains(&WakeSource::RtcAlarm) {
+            let exti = exti_regs();
+            exti.rtsr
+                .modify(|r, w| unsafe { w.bits(r.bits() | 1 << EXTI_RTC_ALARM) });
+            exti.imr
+                .modify(|r, w| unsafe { w.bits(r.bits() | 1 << EXTI_RTC_ALARM) });
+        }
+
+        let low_power_regulator = self.low_power_regulator;
+        cortex_m::interrupt::free(|_| {
+            pwr_regs().cr.modify(|_, w| {
+                w.cwuf()
+                    .set_bit()
+                    .pdds()
+                    .stop_mode()
+                    .lpds()
+                    .bit(low_power_regulator)
+            });
+            scb.set_sleepdeep();
+            cortex_m::asm::dsb();
+            cortex_m::asm::wfi();
+            scb.clear_sleepdeep();
+
+            let source = find_source(sources);
+            clock.restore(acr).map_err(Error::Clock)?;
+            Ok(source)
+        })
+    }
+
+    /// 进入待机模式，唤醒后从复位开始执行，只在唤醒源不支持时返回
+    pub fn standby(&mut self, scb: &mut SCB, sources: &[WakeSource]) -> Result<Infallible, Error> {
+        check_sources(sources, Mode::Standby)?;
+        let pwr = pwr_regs();
+        pwr.csr
+            .modify(|_, w| w.ewup().bit(sources.contains(&WakeSource::WkupPin)));
+        pwr.cr
+            .modify(|_, w| w.cwuf().set_bit().pdds().standby_mode());
+        scb.set_sleepdeep();
+        cortex_m::asm::dsb();
+        loop {
+            cortex_m::asm::wfi();
+        }
+    }
+}
+
+fn check_sources(sources: &[WakeSource], mode: Mode) -> Result<(), Error> {
+    match sources.iter().find(|source| !source.supports(mode)) {
+        Some(source) => Err(Error::Unsupported(*source)),
+        None => Ok(()),
+    }
+}
+
+/// 第一个已触发的唤醒源
+fn find_source(sources: &[WakeSource]) -> Option<WakeSource> {
+    sources.iter().copied().find(WakeSource::is_pending)
+}
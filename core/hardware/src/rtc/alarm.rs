@@ -0,0 +1,70 @@
+//! 重复闹钟
+//! 闹钟时间为本地时间，由 `Calendar` 按时区换算后写入 RTC 闹钟寄存器。
+use super::datetime::{Weekday, SECONDS_PER_DAY};
+
+/// 重复方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub enum Repeat {
+    /// 每天
+    Daily,
+    /// 每周的指定几天，bit0 为星期一，bit6 为星期日
+    Weekly(u8),
+}
+
+/// 闹钟
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub struct Alarm {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub repeat: Repeat,
+    pub enabled: bool,
+}
+
+impl Alarm {
+    /// 每天的指定时间
+    pub fn daily(hour: u8, minute: u8, second: u8) -> Self {
+        Alarm {
+            hour,
+            minute,
+            second,
+            repeat: Repeat::Daily,
+            enabled: true,
+        }
+    }
+
+    /// 每周指定几天的指定时间
+    pub fn weekly(days: &[Weekday], hour: u8, minute: u8, second: u8) -> Self {
+        let mask = days.iter().fold(0, |mask, day| mask | 1 << day.index());
+        Alarm {
+            hour,
+            minute,
+            second,
+            repeat: Repeat::Weekly(mask),
+            enabled: true,
+        }
+    }
+
+    /// 是否在指定星期触发
+    pub fn matches(&self, weekday: Weekday) -> bool {
+        match self.repeat {
+            Repeat::Daily => true,
+            Repeat::Weekly(mask) => mask & (1 << weekday.index()) != 0,
+        }
+    }
+
+    /// 本地时间戳 local 之后(不含)下一次触发的本地时间戳
+    /// 未启用、时间无效或没有选择星期时返回 None
+    pub fn next_after(&self, local: u32) -> Option<u32> {
+        if !self.enabled || self.hour >= 24 || self.minute >= 60 || self.second >= 60 {
+            return None;
+        }
+        let time_of_day = self.hour as u32 * 3600 + self.minute as u32 * 60 + self.second as u32;
+        let today = local / SECONDS_PER_DAY;
+        (0..=7).find_map(|offset| {
+            let day = today + offset;
+            let at = day.checked_mul(SECONDS_PER_DAY)?.checked_add(time_of_day)?;
+            (at > local && self.matches(Weekday::from_days(day))).then_some(at)
+        })
+    }
+}
@@ -0,0 +1,252 @@
+//! RTC 日历
+//! RTC 计数器保存 UTC 时间戳，读写本地时间时按时区偏移换算。
+//! 时区偏移及"时间有效"标记保存在备份寄存器中，VBAT 掉电后标记丢失，需要重新校时。
+//! 闹钟使用 RTC 闹钟中断(EXTI 线 17)，每次只写入最近的一个闹钟时间，触发后自动安排下一次。
+//! ```rust
+//! let mut backup_domain = rcc.bkp.constrain(dp.BKP, &mut pwr);
+//! let rtc = Rtc::new(dp.RTC, &mut backup_domain);
+//! let mut calendar: Calendar<4> = Calendar::new(rtc, backup_domain);
+//! if !calendar.is_time_valid() {
+//!     calendar.set_local(DateTime::new(2024, 5, 1, 12, 30, 0).unwrap());
+//! }
+//! calendar.add_alarm(Alarm::daily(7, 0, 0)).unwrap();
+//! calendar.listen_alarm(&dp.EXTI);
+//!
+//! #[interrupt]
+//! fn RTCALARM() {
+//!     let fired = calendar.on_alarm(&exti);
+//! }
+//! ```
+use super::alarm::Alarm;
+use super::calibration;
+use super::command::TimeCommand;
+use super::datetime::DateTime;
+use super::{BKP_TIME_VALID, BKP_UTC_OFFSET, TIME_VALID_MAGIC};
+
+use heapless::Vec;
+use stm32f1xx_hal::backup_domain::BackupDomain;
+use stm32f1xx_hal::pac::EXTI;
+use stm32f1xx_hal::rtc::Rtc;
+
+/// 时区偏移的范围，单位：分钟
+const MAX_OFFSET_MINUTES: i16 = 14 * 60;
+
+/// 日历错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub enum Error {
+    /// 闹钟数量已满
+    Full,
+    /// 闹钟不存在
+    NotFound,
+    /// 时区偏移超出 ±14 小时
+    Offset,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    alarm: Alarm,
+    /// 下一次触发的 UTC 时间戳
+    next: Option<u32>,
+}
+
+/// RTC 日历
+/// N: 闹钟最大数量，不超过 32
+pub struct Calendar<const N: usize> {
+    rtc: Rtc,
+    bkp: BackupDomain,
+    /// 时区偏移，单位：分钟
+    offset_minutes: i16,
+    alarms: Vec<Slot, N>,
+}
+
+impl<const N: usize> Calendar<N> {
+    /// `on_alarm` 以 u32 位掩码返回触发的闹钟，闹钟数量不能超过 32
+    const ALARM_COUNT_CHECK: () = assert!(N <= 32, "闹钟数量不能超过 32");
+
+    /// 时间有效时从备份寄存器恢复时区偏移，并应用保存的晶振校正值
+    pub fn new(rtc: Rtc, bkp: BackupDomain) -> Self {
+        let () = Self::ALARM_COUNT_CHECK;
+        let mut calendar = Calendar {
+            rtc,
+            bkp,
+            offset_minutes: 0,
+            alarms: Vec::new(),
+        };
+        if calendar.is_time_valid() {
+            let offset = calendar.bkp.read_data_register_low(BKP_UTC_OFFSET) as i16;
+            calendar.offset_minutes = offset.clamp(-MAX_OFFSET_MINUTES, MAX_OFFSET_MINUTES);
+        }
+        calibration::restore(&mut calendar.bkp);
+        calendar
+    }
+
+    /// 是否已校时，且 VBAT 未掉电
+    pub fn is_time_valid(&self) -> bool {
+        self.bkp.read_data_register_low(BKP_TIME_VALID) == TIME_VALID_MAGIC
+    }
+
+    /// 当前 UTC 时间戳
+    pub fn utc_timestamp(&self) -> u32 {
+        self.rtc.current_time()
+    }
+
+    /// 当前本地时间戳
+    pub fn local_timestamp(&self) -> u32 {
+        self.to_local(self.utc_timestamp())
+    }
+
+    /// 当前本地时间
+    pub fn now(&self) -> DateTime {
+        DateTime::from_timestamp(self.local_timestamp())
+    }
+
+    /// 当前 UTC 时间
+    pub fn now_utc(&self) -> DateTime {
+        DateTime::from_timestamp(self.utc_timestamp())
+    }
+
+    fn to_local(&self, utc: u32) -> u32 {
+        utc.saturating_add_signed(self.offset_minutes as i32 * 60)
+    }
+
+    fn to_utc(&self, local: u32) -> u32 {
+        local.saturating_add_signed(-(self.offset_minutes as i32) * 60)
+    }
+
+    /// 按 UTC 时间戳校时，并标记时间有效
+    pub fn set_utc(&mut self, timestamp: u32) {
+        self.rtc.set_time(timestamp);
+        self.bkp
+            .write_data_register_low(BKP_UTC_OFFSET, self.offset_minutes as u16);
+        self.bkp
+            .write_data_register_low(BKP_TIME_VALID, TIME_VALID_MAGIC);
+        self.schedule();
+    }
+
+    /// 按本地时间校时
+    pub fn set_local(&mut self, datetime: DateTime) {
+        self.set_utc(self.to_utc(datetime.timestamp()));
+    }
+
+    /// 时区偏移，单位：分钟
+    pub fn utc_offset(&self) -> i16 {
+        self.offset_minutes
+    }
+
+    /// 修改时区偏移，UTC 时间不变，闹钟按新的本地时间重新安排
+    pub fn set_utc_offset(&mut self, minutes: i16) -> Result<(), Error> {
+        if !(-MAX_OFFSET_MINUTES..=MAX_OFFSET_MINUTES).contains(&minutes) {
+            return Err(Error::Offset);
+        }
+        self.offset_minutes = minutes;
+        self.bkp
+            .write_data_register_low(BKP_UTC_OFFSET, minutes as u16);
+        self.schedule();
+        Ok(())
+    }
+
+    /// 执行串口校时指令，查询指令不做任何修改
+    pub fn apply(&mut self, command: TimeCommand) -> Result<(), Error> {
+        match command {
+            TimeCommand::SetLocal(datetime) => self.set_local(datetime),
+            TimeCommand::SetUnix(timestamp) => self.set_utc(timestamp),
+            TimeCommand::SetOffset(minutes) => self.set_utc_offset(minutes)?,
+            TimeCommand::Query => {}
+        }
+        Ok(())
+    }
+
+    /// 添加闹钟，返回闹钟序号
+    pub fn add_alarm(&mut self, alarm: Alarm) -> Result<usize, Error> {
+        self.alarms
+            .push(Slot { alarm, next: None })
+            .map_err(|_| Error::Full)?;
+        self.schedule();
+        Ok(self.alarms.len() - 1)
+    }
+
+    /// 修改闹钟
+    pub fn set_alarm(&mut self, index: usize, alarm: Alarm) -> Result<(), Error> {
+        self.alarms.get_mut(index).ok_or(Error::NotFound)?.alarm = alarm;
+        self.schedule();
+        Ok(())
+    }
+
+    /// 启用或关闭闹钟
+    pub fn enable_alarm(&mut self, index: usize, enabled: bool) -> Result<(), Error> {
+        self.alarms
+            .get_mut(index)
+            .ok_or(Error::NotFound)?
+            .alarm
+            .enabled = enabled;
+        self.schedule();
+        Ok(())
+    }
+
+    /// 闹钟
+    pub fn alarm(&self, index: usize) -> Option<&Alarm> {
+        self.alarms.get(index).map(|slot| &slot.alarm)
+    }
+
+    /// 最近一次闹钟的本地时间
+    pub fn next_alarm(&self) -> Option<DateTime> {
+        let next = self.alarms.iter().filter_map(|slot| slot.next).min()?;
+        Some(DateTime::from_timestamp(self.to_local(next)))
+    }
+
+    /// 从当前时间起重新计算各闹钟的下一次触发时间，并写入最近的一个
+    fn schedule(&mut self) {
+        let local = self.local_timestamp();
+        let mut earliest: Option<u32> = None;
+        for index in 0..self.alarms.len() {
+            let next = self.alarms[index]
+                .alarm
+                .next_after(local)
+                .map(|at| self.to_utc(at));
+            self.alarms[index].next = next;
+            if let Some(next) = next {
+                earliest = Some(earliest.map_or(next, |e| e.min(next)));
+            }
+        }
+        match earliest {
+            Some(at) => self.rtc.set_alarm(at),
+            // 没有闹钟时写入最大值，在 2106 年之前不会触发
+            None => self.rtc.set_alarm(u32::MAX),
+        }
+    }
+
+    /// 使能闹钟中断，EXTI 线 17 上升沿触发 RTCALARM 中断
+    /// 需在 NVIC 中开启 RTCALARM 中断
+    pub fn listen_alarm(&mut self, exti: &EXTI) {
+        exti.rtsr.modify(|_, w| w.tr17().set_bit());
+        exti.imr.modify(|_, w| w.mr17().set_bit());
+        self.rtc.listen_alarm();
+    }
+
+    /// 关闭闹钟中断
+    pub fn unlisten_alarm(&mut self, exti: &EXTI) {
+        self.rtc.unlisten_alarm();
+        exti.imr.modify(|_, w| w.mr17().clear_bit());
+    }
+
+    /// 在 RTCALARM 中断中调用，返回已触发闹钟的位掩码，bit0 为第一个闹钟
+    pub fn on_alarm(&mut self, exti: &EXTI) -> u32 {
+        exti.pr.write(|w| w.pr17().set_bit());
+        self.rtc.clear_alarm_flag();
+
+        let now = self.utc_timestamp();
+        let fired = self
+            .alarms
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.next.is_some_and(|next| next <= now))
+            .fold(0, |mask, (index, _)| mask | 1 << index);
+        self.schedule();
+        fired
+    }
+
+    /// 释放 RTC 及备份域
+    pub fn release(self) -> (Rtc, BackupDomain) {
+        (self.rtc, self.bkp)
+    }
+}
@@ -0,0 +1,181 @@
+//! RTC 晶振校准
+//! BKP_RTCCR 的 CAL 值表示每 2^20 个 RTC 时钟周期忽略的脉冲数，只能让 RTC 变慢，每步约 0.954ppm，最大 121ppm。
+//! RTC 偏慢时减小预分频值让 RTC 变快(每减 1 约 30.5ppm)，再用 CAL 补偿多出的部分。
+//! 校正值保存在备份寄存器 DR8，`Rtc::new` 会重写预分频值，每次启动后需调用 `restore`。
+//!
+//! 测量以 HSE 为基准，用 DWT 周期计数器统计若干个 RTC 秒的 CPU 周期数，
+//! 系统时钟必须由 HSE 经 PLL 产生，测量精度受限于 HSE 晶振本身的精度(通常 ±20ppm)。
+//! CCO 开启后侵入检测引脚 PC13 输出 RTCCLK/64(LSE 时为 512Hz)，可用频率计验证。
+//! ```rust
+//! calibration::apply(&mut backup_domain, Correction::default());
+//! let ppm = calibration::measure_ppm(&mut rtc, &mut cp.DCB, &mut cp.DWT, clocks.sysclk().raw(), 10);
+//! let correction = Correction::from_ppm(ppm);
+//! calibration::apply(&mut backup_domain, correction);
+//! calibration::store(&mut backup_domain, correction);
+//!
+//! // 下次启动时
+//! calibration::restore(&mut backup_domain);
+//! ```
+
+use super::BKP_RTC_CORRECTION;
+
+use cortex_m::peripheral::{DCB, DWT};
+use stm32f1xx_hal::backup_domain::BackupDomain;
+use stm32f1xx_hal::pac;
+use stm32f1xx_hal::rtc::Rtc;
+
+/// LSE 频率，单位：Hz
+pub const LSE_HZ: u32 = 32_768;
+/// CAL 每步对应的频率偏差，单位：ppm
+pub const PPM_PER_STEP: f32 = 1_000_000.0 / 1_048_576.0;
+/// 预分频值每减 1 对应的频率偏差，单位：ppm
+pub const PPM_PER_PRESCALER: f32 = 1_000_000.0 / LSE_HZ as f32;
+/// CAL 最大值
+pub const MAX_CAL: u8 = 127;
+
+/// 备份寄存器中校正值有效的标志位
+const STORED_FLAG: u16 = 1 << 15;
+
+/// RTC 校正值
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub struct Correction {
+    /// 预分频值的减小量，让 RTC 变快
+    pub prescaler_decrease: u8,
+    /// CAL 值，让 RTC 变慢
+    pub cal: u8,
+}
+
+impl Correction {
+    /// 根据测得的 RTC 偏差计算校正值
+    /// ppm: 正数表示 RTC 偏快
+    pub fn from_ppm(ppm: f32) -> Self {
+        let mut prescaler_decrease = 0;
+        let mut residual = ppm;
+        if ppm < 0.0 {
+            prescaler_decrease = libm::ceilf(-ppm / PPM_PER_PRESCALER).min(127.0) as u8;
+            residual = ppm + prescaler_decrease as f32 * PPM_PER_PRESCALER;
+        }
+        let cal = libm::roundf(residual / PPM_PER_STEP).clamp(0.0, MAX_CAL as f32) as u8;
+        Correction {
+            prescaler_decrease,
+            cal,
+        }
+    }
+
+    /// 校正后 RTC 频率的变化量，单位：ppm，正数表示变快
+    pub fn ppm(&self) -> f32 {
+        self.prescaler_decrease as f32 * PPM_PER_PRESCALER - self.cal as f32 * PPM_PER_STEP
+    }
+
+    /// 打包为备份寄存器的值，bit15 为有效标志
+    pub fn to_bits(&self) -> u16 {
+        STORED_FLAG | ((self.prescaler_decrease as u16 & 0x7F) << 8) | (self.cal as u16 & 0x7F)
+    }
+
+    /// 从备份寄存器的值解包，未保存过时返回 None
+    pub fn from_bits(bits: u16) -> Option<Self> {
+        if bits & STORED_FLAG == 0 {
+            return None;
+        }
+        Some(Correction {
+            prescaler_decrease: ((bits >> 8) & 0x7F) as u8,
+            cal: (bits & 0x7F) as u8,
+        })
+    }
+}
+
+fn bkp_regs() -> &'static pac::bkp::RegisterBlock {
+    unsafe { &*pac::BKP::ptr() }
+}
+
+fn rtc_regs() -> &'static pac::rtc::RegisterBlock {
+    unsafe { &*pac::RTC::ptr() }
+}
+
+/// 写入 CAL 值，需要备份域写权限
+pub fn set_calibration(_bkp: &mut BackupDomain, cal: u8) {
+    bkp_regs()
+        .rtccr
+        .modify(|_, w| unsafe { w.cal().bits(cal.min(MAX_CAL)) });
+}
+
+/// 当前 CAL 值
+pub fn calibration() -> u8 {
+    bkp_regs().rtccr.read().cal().bits()
+}
+
+/// 在侵入检测引脚 PC13 上输出 RTCCLK/64，此时不能使用侵入检测
+pub fn set_clock_output(_bkp: &mut BackupDomain, enable: bool) {
+    bkp_regs().rtccr.modify(|_, w| w.cco().bit(enable));
+}
+
+/// 修改 RTC 预分频值，RTC 时钟周期数为 prescaler + 1
+fn set_prescaler(prescaler: u32) {
+    let rtc = rtc_regs();
+    while rtc.crl.read().rtoff().bit_is_clear() {}
+    rtc.crl.modify(|_, w| w.cnf().set_bit());
+    rtc.prlh
+        .write(|w| unsafe { w.bits((prescaler >> 16) & 0x0F) });
+    rtc.prll.write(|w| unsafe { w.bits(prescaler & 0xFFFF) });
+    rtc.crl.modify(|_, w| w.cnf().clear_bit());
+    while rtc.crl.read().rtoff().bit_is_clear() {}
+}
+
+/// 应用校正值，写入预分频值及 CAL
+pub fn apply(bkp: &mut BackupDomain, correction: Correction) {
+    set_prescaler(LSE_HZ - 1 - correction.prescaler_decrease as u32);
+    set_calibration(bkp, correction.cal);
+}
+
+/// 保存校正值到备份寄存器
+pub fn store(bkp: &mut BackupDomain, correction: Correction) {
+    bkp.write_data_register_low(BKP_RTC_CORRECTION, correction.to_bits());
+}
+
+/// 读取保存的校正值
+pub fn load(bkp: &BackupDomain) -> Option<Correction> {
+    Correction::from_bits(bkp.read_data_register_low(BKP_RTC_CORRECTION))
+}
+
+/// 应用保存的校正值，没有保存过时返回 None
+pub fn restore(bkp: &mut BackupDomain) -> Option<Correction> {
+    let correction = load(bkp)?;
+    apply(bkp, correction);
+    Some(correction)
+}
+
+/// 等待下一个 RTC 秒边沿
+fn wait_second() {
+    let rtc = rtc_regs();
+    rtc.crl.modify(|_, w| w.secf().clear_bit());
+    while rtc.crl.read().secf().bit_is_clear() {}
+}
+
+/// 以 HSE 为基准测量 RTC 的频率偏差，阻塞 seconds + 1 秒
+/// sysclk_hz: 由 HSE 产生的系统时钟，单位：Hz
+/// 返回偏差，单位：ppm，正数表示 RTC 偏快
+pub fn measure_ppm(
+    _rtc: &mut Rtc,
+    dcb: &mut DCB,
+    dwt: &mut DWT,
+    sysclk_hz: u32,
+    seconds: u32,
+) -> f32 {
+    let seconds = seconds.max(1);
+    dcb.enable_trace();
+    dwt.enable_cycle_counter();
+
+    // 周期计数器约 59 秒溢出一次，逐秒累加
+    wait_second();
+    let mut last = DWT::cycle_count();
+    let mut measured = 0_u64;
+    for _ in 0..seconds {
+        wait_second();
+        let now = DWT::cycle_count();
+        measured += now.wrapping_sub(last) as u64;
+        last = now;
+    }
+
+    let expected = sysclk_hz as u64 * seconds as u64;
+    (expected as i64 - measured as i64) as f32 * 1_000_000.0 / measured as f32
+}
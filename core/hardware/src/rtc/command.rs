@@ -0,0 +1,58 @@
+//! 串口校时
+//! 每行一条指令，以 `\n` 结束，不区分大小写:
+//! - `time 2024-05-01 12:30:00`: 按本地时间设置
+//! - `unix 1714566600`: 按 UTC 时间戳设置，主机可执行 `date -u +"unix %s"` 生成
+//! - `tz +08:00` / `tz -05:30`: 设置时区
+//! - `?`: 查询当前时间
+use super::datetime::DateTime;
+
+/// 校时指令
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeCommand {
+    /// 本地时间
+    SetLocal(DateTime),
+    /// UTC 时间戳
+    SetUnix(u32),
+    /// 时区偏移，单位：分钟
+    SetOffset(i16),
+    /// 查询当前时间
+    Query,
+}
+
+impl TimeCommand {
+    /// 解析一行指令，无法识别时返回 None
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line == "?" {
+            return Some(TimeCommand::Query);
+        }
+        let (name, value) = line.split_once(|c: char| c.is_ascii_whitespace())?;
+        let value = value.trim();
+
+        if name.eq_ignore_ascii_case("time") {
+            DateTime::parse(value).map(TimeCommand::SetLocal)
+        } else if name.eq_ignore_ascii_case("unix") {
+            value.parse().ok().map(TimeCommand::SetUnix)
+        } else if name.eq_ignore_ascii_case("tz") {
+            parse_offset(value).map(TimeCommand::SetOffset)
+        } else {
+            None
+        }
+    }
+}
+
+/// 解析 `+HH:MM` 形式的时区偏移，单位：分钟
+pub fn parse_offset(text: &str) -> Option<i16> {
+    let (sign, rest) = match text.as_bytes().first()? {
+        b'+' => (1, &text[1..]),
+        b'-' => (-1, &text[1..]),
+        _ => (1, text),
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours = hours.parse::<i16>().ok()?;
+    let minutes = minutes.parse::<i16>().ok()?;
+    if !(0..=14).contains(&hours) || !(0..60).contains(&minutes) {
+        return None;
+    }
+    Some(sign * (hours * 60 + minutes))
+}
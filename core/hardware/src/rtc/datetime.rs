@@ -0,0 +1,176 @@
+//! 日期时间
+//! 与 Unix 时间戳(1970-01-01 00:00:00 起的秒数)互相转换，u32 时间戳可表示到 2106 年。
+use core::fmt;
+
+/// 每天的秒数
+pub const SECONDS_PER_DAY: u32 = 86_400;
+
+/// 星期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    /// 1970-01-01 起的天数对应的星期，当天为星期四
+    pub fn from_days(days: u32) -> Self {
+        match (days + 3) % 7 {
+            0 => Weekday::Monday,
+            1 => Weekday::Tuesday,
+            2 => Weekday::Wednesday,
+            3 => Weekday::Thursday,
+            4 => Weekday::Friday,
+            5 => Weekday::Saturday,
+            _ => Weekday::Sunday,
+        }
+    }
+
+    /// 星期一为 0，星期日为 6
+    pub fn index(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// 是否为闰年
+pub fn is_leap_year(year: u16) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+/// 每月的天数，月份无效时返回 0
+pub fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+/// 日期时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, defmt::Format)]
+pub struct DateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl DateTime {
+    /// 创建日期时间，超出 1970~2105 年或字段无效时返回 None
+    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Option<Self> {
+        let valid = (1970..=2105).contains(&year)
+            && day >= 1
+            && day <= days_in_month(year, month)
+            && hour < 24
+            && minute < 60
+            && second < 60;
+        valid.then_some(DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    /// 由时间戳计算日期时间
+    pub fn from_timestamp(timestamp: u32) -> Self {
+        let days = timestamp / SECONDS_PER_DAY;
+        let seconds = timestamp % SECONDS_PER_DAY;
+        let (year, month, day) = civil_from_days(days);
+        DateTime {
+            year,
+            month,
+            day,
+            hour: (seconds / 3600) as u8,
+            minute: (seconds / 60 % 60) as u8,
+            second: (seconds % 60) as u8,
+        }
+    }
+
+    /// 时间戳
+    pub fn timestamp(&self) -> u32 {
+        let days = days_from_civil(self.year, self.month, self.day);
+        days * SECONDS_PER_DAY
+            + self.hour as u32 * 3600
+            + self.minute as u32 * 60
+            + self.second as u32
+    }
+
+    /// 星期
+    pub fn weekday(&self) -> Weekday {
+        Weekday::from_days(days_from_civil(self.year, self.month, self.day))
+    }
+
+    /// 解析 `YYYY-MM-DD HH:MM:SS`，日期与时间之间也可以用 `T` 分隔
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (date, time) = text.split_once([' ', 'T'])?;
+        let mut date = date.split('-').map(|v| v.parse::<u16>().ok());
+        let mut time = time.trim().split(':').map(|v| v.parse::<u8>().ok());
+
+        let year = date.next()??;
+        let month = date.next()??;
+        let day = date.next()??;
+        let hour = time.next()??;
+        let minute = time.next()??;
+        let second = time.next()??;
+        if date.next().is_some() || time.next().is_some() {
+            return None;
+        }
+        Self::new(
+            year,
+            u8::try_from(month).ok()?,
+            u8::try_from(day).ok()?,
+            hour,
+            minute,
+            second,
+        )
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+/// 1970-01-01 起的天数，公历算法，以 3 月为一年的开始
+fn days_from_civil(year: u16, month: u8, day: u8) -> u32 {
+    let year = year as u32 - u32::from(month <= 2);
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let month = month as u32;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day as u32 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// 天数换算为年、月、日
+fn civil_from_days(days: u32) -> (u16, u8, u8) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u8;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u8;
+    let year = year_of_era + era * 400 + u32::from(month <= 2);
+    (year as u16, month, day)
+}
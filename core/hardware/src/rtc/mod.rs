@@ -0,0 +1,28 @@
+//! RTC 实时时钟
+//! - `DateTime`: 日期时间与 Unix 时间戳互相转换
+//! - `Alarm`: 每天或每周重复的闹钟
+//! - `Calendar`: 基于 `stm32f1xx_hal::rtc::Rtc` 的日历，支持时区、闹钟中断及备份寄存器中的有效标记
+//! - `TimeCommand`: 通过串口从主机校时
+//! - `calibration`: 以 HSE 为基准测量 RTC 偏差，计算并保存校正值
+//!
+//! 备份寄存器 DR8~DR10 被日历及校准占用，其它模块应避免使用。
+pub mod alarm;
+pub mod calendar;
+pub mod calibration;
+pub mod command;
+pub mod datetime;
+
+pub use alarm::{Alarm, Repeat};
+pub use calendar::Calendar;
+pub use calibration::Correction;
+pub use command::TimeCommand;
+pub use datetime::{DateTime, Weekday};
+
+/// 校正值所在的备份寄存器(DR8)
+pub const BKP_RTC_CORRECTION: usize = 7;
+/// 时区偏移所在的备份寄存器(DR9)
+pub const BKP_UTC_OFFSET: usize = 8;
+/// "时间有效"标记所在的备份寄存器(DR10)
+pub const BKP_TIME_VALID: usize = 9;
+/// 已校时标记
+pub const TIME_VALID_MAGIC: u16 = 0x5AA5;
@@ -1,4 +1,5 @@
 //! 串行接口常用工具集
+//! - `LineBuffer`: 在接收中断中逐字节拼接一行文本指令
 
 use core::u32;
 use heapless::String;
@@ -101,3 +102,40 @@ where
     }
     s
 }
+
+/// 行接收缓冲区
+/// 超出长度的字符被丢弃，`\r` 被忽略
+#[derive(Debug, Default)]
+pub struct LineBuffer<const N: usize> {
+    line: String<N>,
+}
+
+impl<const N: usize> LineBuffer<N> {
+    pub fn new() -> Self {
+        LineBuffer {
+            line: String::new(),
+        }
+    }
+
+    /// 写入一个字节，收到 `\n` 时返回 true
+    pub fn push(&mut self, byte: u8) -> bool {
+        match byte {
+            b'\n' => true,
+            b'\r' => false,
+            _ => {
+                self.line.push(byte as char).ok();
+                false
+            }
+        }
+    }
+
+    /// 已接收的内容
+    pub fn as_str(&self) -> &str {
+        self.line.as_str()
+    }
+
+    /// 清空缓冲区
+    pub fn clear(&mut self) {
+        self.line.clear();
+    }
+}
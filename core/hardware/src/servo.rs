@@ -0,0 +1,212 @@
+//! 舵机
+//! 根据脉冲宽度范围及角度范围计算占空比，占空比由定时器实际的最大占空比和周期换算，
+//! 修改定时器周期或系统时钟后无需重新计算系数。
+//! ```rust
+//! let pins = (pa0, pa1);
+//! let mut pwm = tim2.pwm_hz::<Tim2NoRemap, _, _>(pins, &mut afio.mapr, 50.Hz(), &clocks);
+//! let channels = [
+//!     (Channel::C1, ServoConfig::default()),
+//!     (Channel::C2, ServoConfig::default()),
+//! ];
+//! let mut servos = Servos::new(pwm, channels);
+//! servos.set_angle(0, 90.0);
+//! servos.move_to(1, 180.0, 1000);
+//!
+//! // 每 10ms 的定时中断中
+//! servos.tick(10);
+//! ```
+use embedded_hal::Pwm;
+use stm32f1xx_hal::time::Hertz;
+
+/// 舵机参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ServoConfig {
+    /// 最小角度对应的脉冲宽度，单位：us
+    pub min_pulse_us: u16,
+    /// 最大角度对应的脉冲宽度，单位：us
+    pub max_pulse_us: u16,
+    /// 最小角度，单位：度
+    pub min_angle: f32,
+    /// 最大角度，单位：度
+    pub max_angle: f32,
+}
+
+impl Default for ServoConfig {
+    /// 常见 180 度舵机: 0.5ms~2.5ms 对应 0~180 度
+    fn default() -> Self {
+        ServoConfig {
+            min_pulse_us: 500,
+            max_pulse_us: 2500,
+            min_angle: 0.0,
+            max_angle: 180.0,
+        }
+    }
+}
+
+impl ServoConfig {
+    /// 将角度限制在角度范围内
+    pub fn clamp(&self, angle: f32) -> f32 {
+        angle.clamp(self.min_angle, self.max_angle)
+    }
+
+    /// 角度对应的脉冲宽度，单位：us
+    /// 最小角度等于最大角度时固定输出最小角度对应的脉冲宽度
+    pub fn pulse_us(&self, angle: f32) -> u32 {
+        let range = self.max_angle - self.min_angle;
+        if range <= 0.0 {
+            return self.min_pulse_us as u32;
+        }
+        let ratio = (self.clamp(angle) - self.min_angle) / range;
+        let span = self.max_pulse_us as f32 - self.min_pulse_us as f32;
+        (self.min_pulse_us as f32 + span * ratio) as u32
+    }
+}
+
+/// 插值运动
+#[derive(Debug, Clone, Copy)]
+struct Motion {
+    from: f32,
+    to: f32,
+    /// 运动时间，单位：ms
+    duration_ms: u32,
+    /// 已运动时间，单位：ms
+    elapsed_ms: u32,
+}
+
+/// 单个舵机通道的状态
+#[derive(Debug, Clone, Copy)]
+struct ServoChannel<C> {
+    channel: C,
+    config: ServoConfig,
+    angle: f32,
+    motion: Option<Motion>,
+}
+
+/// 同一定时器上的多路舵机
+pub struct Servos<PWM, const N: usize>
+where
+    PWM: Pwm,
+{
+    pwm: PWM,
+    servos: [ServoChannel<PWM::Channel>; N],
+}
+
+impl<PWM, const N: usize> Servos<PWM, N>
+where
+    PWM: Pwm<Duty = u16, Time = Hertz>,
+    PWM::Channel: Copy,
+{
+    /// 创建舵机并使能通道，初始角度为各通道角度范围的中点
+    /// 定时器周期一般为 20ms(50Hz)
+    pub fn new(pwm: PWM, channels: [(PWM::Channel, ServoConfig); N]) -> Self {
+        let servos = channels.map(|(channel, config)| ServoChannel {
+            channel,
+            config,
+            angle: (config.min_angle + config.max_angle) / 2.0,
+            motion: None,
+        });
+        let mut servos = Servos { pwm, servos };
+        for index in 0..N {
+            servos.pwm.enable(servos.servos[index].channel);
+            servos.apply(index);
+        }
+        servos
+    }
+
+    /// 脉冲宽度对应的占空比
+    pub fn duty(&self, pulse_us: u32) -> u16 {
+        let max_duty = self.pwm.get_max_duty() as u64;
+        let freq = self.pwm.get_period().raw() as u64;
+        let duty = pulse_us as u64 * max_duty * freq / 1_000_000;
+        duty.min(max_duty) as u16
+    }
+
+    /// 根据当前角度设置占空比
+    fn apply(&mut self, index: usize) {
+        let servo = &self.servos[index];
+        let duty = self.duty(servo.config.pulse_us(servo.angle));
+        self.pwm.set_duty(servo.channel, duty);
+    }
+
+    /// 立即转动到指定角度，并取消正在进行的插值运动
+    pub fn set_angle(&mut self, index: usize, angle: f32) {
+        let servo = &mut self.servos[index];
+        servo.angle = servo.config.clamp(angle);
+        servo.motion = None;
+        self.apply(index);
+    }
+
+    /// 当前角度
+    pub fn angle(&self, index: usize) -> f32 {
+        self.servos[index].angle
+    }
+
+    /// 在指定时间内从当前角度匀速转动到目标角度，由 `tick` 推进
+    pub fn move_to(&mut self, index: usize, angle: f32, duration_ms: u32) {
+        if duration_ms == 0 {
+            self.set_angle(index, angle);
+            return;
+        }
+        let servo = &mut self.servos[index];
+        servo.motion = Some(Motion {
+            from: servo.angle,
+            to: servo.config.clamp(angle),
+            duration_ms,
+            elapsed_ms: 0,
+        });
+    }
+
+    /// 是否正在插值运动
+    pub fn is_moving(&self, index: usize) -> bool {
+        self.servos[index].motion.is_some()
+    }
+
+    /// 推进所有插值运动，在定时中断中周期调用
+    /// dt_ms: 距离上次调用的时间，单位：ms
+    pub fn tick(&mut self, dt_ms: u32) {
+        for index in 0..N {
+            let servo = &mut self.servos[index];
+            let Some(mut motion) = servo.motion else {
+                continue;
+            };
+            motion.elapsed_ms = motion.elapsed_ms.saturating_add(dt_ms);
+            if motion.elapsed_ms >= motion.duration_ms {
+                servo.angle = motion.to;
+                servo.motion = None;
+            } else {
+                let ratio = motion.elapsed_ms as f32 / motion.duration_ms as f32;
+                servo.angle = motion.from + (motion.to - motion.from) * ratio;
+                servo.motion = Some(motion);
+            }
+            self.apply(index);
+        }
+    }
+
+    /// 修改通道参数
+    pub fn set_config(&mut self, index: usize, config: ServoConfig) {
+        let servo = &mut self.servos[index];
+        servo.config = config;
+        servo.angle = config.clamp(servo.angle);
+        self.apply(index);
+    }
+
+    /// 修改 PWM 周期后重新计算所有通道的占空比
+    pub fn refresh(&mut self) {
+        for index in 0..N {
+            self.apply(index);
+        }
+    }
+
+    /// PWM 定时器，可用于修改周期，修改后需调用 `refresh`
+    pub fn pwm_mut(&mut self) -> &mut PWM {
+        &mut self.pwm
+    }
+
+    /// 关闭所有通道并释放定时器
+    pub fn release(mut self) -> PWM {
+        for index in 0..N {
+            self.pwm.disable(self.servos[index].channel);
+        }
+        self.pwm
+    }
+}
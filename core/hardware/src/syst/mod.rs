@@ -1,5 +1,7 @@
 //!系统工具库
 
 pub mod delay;
+pub mod mono;
 
 pub use delay::*;
+pub use mono::{Deadline, Duration, Instant, MonoDelay, SysMono};
@@ -0,0 +1,225 @@
+//! SysTick 单调时钟
+//! SysTick 每 1ms 产生一次中断累加毫秒数，读取时结合当前计数值得到微秒精度的时间。
+//! 时间为 64 位微秒数，不会溢出。SysTick 被占用后可使用 `SysMono::delay` 作为阻塞延时。
+//! ```rust
+//! let mono = SysMono::new(cp.SYST, &clocks);
+//! let mut delay = SysMono::delay();
+//!
+//! let start = SysMono::now();
+//! delay.delay_ms(10_u32);
+//! let elapsed = SysMono::elapsed(start);
+//!
+//! let mut deadline = Deadline::after(Duration::millis(500));
+//! loop {
+//!     if deadline.is_expired() {
+//!         deadline.restart(Duration::millis(500));
+//!     }
+//! }
+//!
+//! #[exception]
+//! fn SysTick() {
+//!     SysMono::on_tick();
+//! }
+//! ```
+use core::cell::Cell;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use cortex_m::interrupt::Mutex;
+use cortex_m::peripheral::syst::SystClkSource;
+use cortex_m::peripheral::{SCB, SYST};
+use embedded_hal::blocking::delay::{DelayMs, DelayUs};
+use stm32f1xx_hal::rcc::Clocks;
+
+/// 时间点，单位：us
+pub type Instant = fugit::TimerInstantU64<1_000_000>;
+/// 时间间隔，单位：us
+pub type Duration = fugit::TimerDurationU64<1_000_000>;
+
+/// 已经过的毫秒数
+static MILLIS: Mutex<Cell<u64>> = Mutex::new(Cell::new(0));
+/// 每毫秒的 SysTick 计数，为 0 表示未初始化
+static TICKS_PER_MS: AtomicU32 = AtomicU32::new(0);
+
+/// SysTick 单调时钟
+pub struct SysMono {
+    syst: SYST,
+}
+
+impl SysMono {
+    /// 以 HCLK 为时钟源，每 1ms 产生一次 SysTick 中断
+    /// 需要在 SysTick 异常中调用 `on_tick`
+    pub fn new(mut syst: SYST, clocks: &Clocks) -> Self {
+        let ticks_per_ms = clocks.hclk().raw() / 1000;
+        TICKS_PER_MS.store(ticks_per_ms, Ordering::Relaxed);
+        cortex_m::interrupt::free(|cs| MILLIS.borrow(cs).set(0));
+
+        syst.disable_counter();
+        syst.set_clock_source(SystClkSource::Core);
+        syst.set_reload(ticks_per_ms - 1);
+        syst.clear_current();
+        syst.enable_interrupt();
+        syst.enable_counter();
+        SysMono { syst }
+    }
+
+    /// 在 SysTick 异常中调用
+    pub fn on_tick() {
+        cortex_m::interrupt::free(|cs| {
+            let millis = MILLIS.borrow(cs);
+            millis.set(millis.get() + 1);
+        });
+    }
+
+    /// 当前时间，未初始化时为 0
+    pub fn now() -> Instant {
+        let ticks_per_ms = TICKS_PER_MS.load(Ordering::Relaxed);
+        if ticks_per_ms == 0 {
+            return Instant::from_ticks(0);
+        }
+        let micros = cortex_m::interrupt::free(|cs| {
+            let mut millis = MILLIS.borrow(cs).get();
+            let mut current = SYST::get_current();
+            // 计数器已重装但中断尚未处理，重新读取计数值
+            if SCB::is_pendst_pending() {
+                millis += 1;
+                current = SYST::get_current();
+            }
+            let ticks = ticks_per_ms - 1 - current.min(ticks_per_ms - 1);
+            millis * 1000 + (ticks as u64 * 1000 / ticks_per_ms as u64)
+        });
+        Instant::from_ticks(micros)
+    }
+
+    /// 已经过的毫秒数
+    pub fn millis() -> u64 {
+        Self::now().ticks() / 1000
+    }
+
+    /// 已经过的微秒数
+    pub fn micros() -> u64 {
+        Self::now().ticks()
+    }
+
+    /// 自 since 起经过的时间
+    pub fn elapsed(since: Instant) -> Duration {
+        Self::now()
+            .checked_duration_since(since)
+            .unwrap_or(Duration::from_ticks(0))
+    }
+
+    /// 自 since 起是否已超过 timeout
+    pub fn has_elapsed(since: Instant, timeout: Duration) -> bool {
+        Self::elapsed(since) >= timeout
+    }
+
+    /// 在 timeout 内等待条件成立，超时返回 false
+    pub fn wait_for<F>(timeout: Duration, mut ready: F) -> bool
+    where
+        F: FnMut() -> bool,
+    {
+        let deadline = Deadline::after(timeout);
+        loop {
+            if ready() {
+                return true;
+            }
+            if deadline.is_expired() {
+                return false;
+            }
+        }
+    }
+
+    /// 基于单调时钟的阻塞延时，可以同时存在多个
+    pub fn delay() -> MonoDelay {
+        MonoDelay
+    }
+
+    /// 停止 SysTick 并释放
+    pub fn release(mut self) -> SYST {
+        self.syst.disable_interrupt();
+        self.syst.disable_counter();
+        TICKS_PER_MS.store(0, Ordering::Relaxed);
+        self.syst
+    }
+}
+
+/// 非阻塞的截止时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadline {
+    at: Instant,
+}
+
+impl Deadline {
+    /// 从现在起经过 duration 后到期
+    pub fn after(duration: Duration) -> Self {
+        Deadline {
+            at: SysMono::now() + duration,
+        }
+    }
+
+    /// 在指定时间点到期
+    pub fn at(at: Instant) -> Self {
+        Deadline { at }
+    }
+
+    /// 到期时间
+    pub fn instant(&self) -> Instant {
+        self.at
+    }
+
+    /// 是否已到期
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(SysMono::now())
+    }
+
+    /// 在 now 时是否已到期
+    pub fn is_expired_at(&self, now: Instant) -> bool {
+        now >= self.at
+    }
+
+    /// 剩余时间，已到期时为 0
+    pub fn remaining(&self) -> Duration {
+        self.at
+            .checked_duration_since(SysMono::now())
+            .unwrap_or(Duration::from_ticks(0))
+    }
+
+    /// 以上次到期时间为基准顺延 period，用于周期任务，避免累积误差
+    pub fn restart(&mut self, period: Duration) {
+        self.at += period;
+    }
+}
+
+/// 基于单调时钟的阻塞延时
+#[derive(Debug, Clone, Copy)]
+pub struct MonoDelay;
+
+impl MonoDelay {
+    fn wait(&self, duration: Duration) {
+        let deadline = Deadline::after(duration);
+        while !deadline.is_expired() {}
+    }
+}
+
+impl DelayUs<u32> for MonoDelay {
+    fn delay_us(&mut self, us: u32) {
+        self.wait(Duration::micros(us as u64));
+    }
+}
+
+impl DelayUs<u16> for MonoDelay {
+    fn delay_us(&mut self, us: u16) {
+        self.wait(Duration::micros(us as u64));
+    }
+}
+
+impl DelayMs<u32> for MonoDelay {
+    fn delay_ms(&mut self, ms: u32) {
+        self.wait(Duration::millis(ms as u64));
+    }
+}
+
+impl DelayMs<u16> for MonoDelay {
+    fn delay_ms(&mut self, ms: u16) {
+        self.wait(Duration::millis(ms as u64));
+    }
+}
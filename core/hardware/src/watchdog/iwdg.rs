@@ -0,0 +1,112 @@
+//! 独立看门狗
+//! 超时时间 = 4 * 2^PR * (RL + 1) / LSI，LSI 典型值 40kHz(30~60kHz)，范围约 0.1ms ~ 26214.4ms。
+//! LSI 精度较低，超时时间应留出足够余量。
+//! ```rust
+//! let mut iwdg = Iwdg::start(dp.IWDG, IwdgConfig::from_ms(1000).unwrap());
+//!
+//! loop {
+//!     iwdg.feed();
+//! }
+//! ```
+
+use embedded_hal::watchdog::Watchdog;
+use stm32f1xx_hal::pac::IWDG;
+
+use super::Error;
+
+/// LSI 典型频率，单位：Hz
+pub const LSI_HZ: u32 = 40_000;
+/// 重装值最大值
+pub const MAX_RELOAD: u16 = 0x0FFF;
+/// 预分频寄存器最大值，对应 256 分频
+pub const MAX_PRESCALER: u8 = 6;
+
+/// 独立看门狗配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub struct IwdgConfig {
+    /// 预分频寄存器值，分频系数为 4 * 2^prescaler
+    pub prescaler: u8,
+    /// 重装值
+    pub reload: u16,
+}
+
+impl IwdgConfig {
+    /// 根据超时时间计算配置，选择能满足要求的最小分频以获得最高分辨率
+    pub fn from_ms(timeout_ms: u32) -> Result<Self, Error> {
+        let lsi_ticks = timeout_ms as u64 * LSI_HZ as u64 / 1000;
+        for prescaler in 0..=MAX_PRESCALER {
+            let divider = 4_u64 << prescaler;
+            let ticks = (lsi_ticks + divider / 2) / divider;
+            if ticks == 0 {
+                return Err(Error::TooShort);
+            }
+            if ticks <= MAX_RELOAD as u64 + 1 {
+                return Ok(IwdgConfig {
+                    prescaler,
+                    reload: (ticks - 1) as u16,
+                });
+            }
+        }
+        Err(Error::TooLong)
+    }
+
+    /// 分频系数
+    pub fn divider(&self) -> u32 {
+        4 << self.prescaler
+    }
+
+    /// 按 LSI 典型频率计算的超时时间，单位：us
+    pub fn timeout_us(&self) -> u32 {
+        ((self.reload as u64 + 1) * self.divider() as u64 * 1_000_000 / LSI_HZ as u64) as u32
+    }
+}
+
+/// 独立看门狗
+pub struct Iwdg {
+    iwdg: IWDG,
+    config: IwdgConfig,
+}
+
+impl Iwdg {
+    /// 启动看门狗及 LSI，再写入配置
+    /// PR 及 RLR 的更新依赖 LSI，需先启动才能等到 PVU、RVU 清零
+    pub fn start(iwdg: IWDG, config: IwdgConfig) -> Self {
+        iwdg.kr.write(|w| w.key().start());
+        // 解除 PR 及 RLR 的写保护
+        iwdg.kr.write(|w| w.key().enable());
+        iwdg.pr.write(|w| {
+            let pr = w.pr();
+            match config.prescaler {
+                0 => pr.divide_by4(),
+                1 => pr.divide_by8(),
+                2 => pr.divide_by16(),
+                3 => pr.divide_by32(),
+                4 => pr.divide_by64(),
+                5 => pr.divide_by128(),
+                _ => pr.divide_by256(),
+            }
+        });
+        iwdg.rlr.write(|w| w.rl().bits(config.reload));
+        while iwdg.sr.read().pvu().bit_is_set() || iwdg.sr.read().rvu().bit_is_set() {}
+
+        let mut watchdog = Iwdg { iwdg, config };
+        watchdog.feed();
+        watchdog
+    }
+
+    /// 喂狗，重新装载计数值
+    pub fn feed(&mut self) {
+        self.iwdg.kr.write(|w| w.key().reset());
+    }
+
+    /// 当前配置
+    pub fn config(&self) -> IwdgConfig {
+        self.config
+    }
+}
+
+impl Watchdog for Iwdg {
+    fn feed(&mut self) {
+        Iwdg::feed(self);
+    }
+}
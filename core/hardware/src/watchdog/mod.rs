@@ -0,0 +1,26 @@
+//! 看门狗
+//! - `Iwdg`: 独立看门狗，由 LSI(约 40kHz)驱动，按毫秒计算预分频及重装值
+//! - `Wwdg`: 窗口看门狗，由 PCLK1 驱动，按毫秒计算分频、计数及窗口值，支持提前唤醒中断
+//! - `Supervisor`: 多个任务或主循环各阶段都签到后才喂狗，任一任务卡死都会触发复位
+//!
+//! 两种看门狗启动后都只能由复位关闭。
+pub mod iwdg;
+pub mod supervisor;
+pub mod wwdg;
+
+pub use iwdg::{Iwdg, IwdgConfig};
+pub use supervisor::{Supervisor, TaskId};
+pub use wwdg::{Wwdg, WwdgConfig};
+
+/// 看门狗错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub enum Error {
+    /// 超时时间小于一个计数周期
+    TooShort,
+    /// 超时时间超过最大计数范围
+    TooLong,
+    /// 窗口时间不小于超时时间
+    Window,
+    /// 监控任务数量已满
+    Full,
+}
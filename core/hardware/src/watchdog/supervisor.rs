@@ -0,0 +1,127 @@
+//! 看门狗监控
+//! 每个任务在一个喂狗周期内至少签到一次，全部签到后才喂狗并开始下一个周期。
+//! 任一任务卡死时看门狗得不到喂狗而复位，复位前可通过 `missing` 查看未签到的任务。
+//! ```rust
+//! let mut supervisor = Supervisor::<3>::new();
+//! let sensor = supervisor.register("sensor").unwrap();
+//! let display = supervisor.register("display").unwrap();
+//!
+//! loop {
+//!     read_sensor();
+//!     supervisor.check_in(sensor);
+//!     update_display();
+//!     supervisor.check_in(display);
+//!
+//!     supervisor.feed(&mut iwdg);
+//! }
+//! ```
+use embedded_hal::watchdog::Watchdog;
+use heapless::Vec;
+
+use super::Error;
+
+/// 任务句柄，即注册顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskId(u8);
+
+impl TaskId {
+    /// 任务序号
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// 看门狗监控
+/// N: 任务最大数量，不超过 32
+pub struct Supervisor<const N: usize> {
+    names: Vec<&'static str, N>,
+    /// 本周期已签到的任务，按位表示
+    checked: u32,
+    /// 已喂狗次数
+    feeds: u32,
+}
+
+impl<const N: usize> Default for Supervisor<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Supervisor<N> {
+    pub const fn new() -> Self {
+        assert!(N <= 32, "最多支持 32 个任务");
+        Supervisor {
+            names: Vec::new(),
+            checked: 0,
+            feeds: 0,
+        }
+    }
+
+    /// 注册任务
+    pub fn register(&mut self, name: &'static str) -> Result<TaskId, Error> {
+        let id = TaskId(self.names.len() as u8);
+        self.names.push(name).map_err(|_| Error::Full)?;
+        Ok(id)
+    }
+
+    /// 任务数量
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// 是否没有任务
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// 任务名称
+    pub fn name(&self, id: TaskId) -> Option<&'static str> {
+        self.names.get(id.index()).copied()
+    }
+
+    /// 任务签到
+    pub fn check_in(&mut self, id: TaskId) {
+        if id.index() < self.names.len() {
+            self.checked |= 1 << id.index();
+        }
+    }
+
+    /// 本周期所有任务的签到位
+    fn all(&self) -> u32 {
+        if self.names.len() == 32 {
+            u32::MAX
+        } else {
+            (1 << self.names.len()) - 1
+        }
+    }
+
+    /// 是否所有任务都已签到
+    pub fn all_checked_in(&self) -> bool {
+        self.checked == self.all()
+    }
+
+    /// 本周期未签到的任务
+    pub fn missing(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.names
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| self.checked & (1 << index) == 0)
+            .map(|(_, name)| *name)
+    }
+
+    /// 所有任务都已签到时喂狗并开始下一个周期，返回是否已喂狗
+    pub fn feed<W: Watchdog>(&mut self, watchdog: &mut W) -> bool {
+        if !self.all_checked_in() {
+            return false;
+        }
+        watchdog.feed();
+        self.checked = 0;
+        self.feeds = self.feeds.wrapping_add(1);
+        true
+    }
+
+    /// 已喂狗次数
+    pub fn feeds(&self) -> u32 {
+        self.feeds
+    }
+}
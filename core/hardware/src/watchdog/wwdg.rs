@@ -0,0 +1,198 @@
+//! 窗口看门狗
+//! 计数器 T[6:0] 以 PCLK1/4096/2^WDGTB 递减，从 0x40 减到 0x3F 时复位；
+//! 计数值大于窗口值 W 时喂狗也会复位，即距上次喂狗的时间必须在窗口时间与超时时间之间。
+//! 使能提前唤醒中断后，计数到 0x40 时产生 WWDG 中断，此时距离复位只剩一个计数周期。
+//! ```rust
+//! let config = WwdgConfig::from_ms(clocks.pclk1().raw(), 50, 30).unwrap();
+//! let mut wwdg = Wwdg::start(dp.WWDG, config);
+//! wwdg::set_early_wakeup_hook(|| defmt::println!("WWDG timeout"));
+//! wwdg.listen_early_wakeup();
+//! unsafe { NVIC::unmask(interrupt::WWDG) };
+//!
+//! #[interrupt]
+//! fn WWDG() {
+//!     wwdg::on_early_wakeup();
+//! }
+//! ```
+
+use core::cell::Cell;
+
+use cortex_m::interrupt::Mutex;
+use embedded_hal::watchdog::Watchdog;
+use stm32f1xx_hal::pac::{self, WWDG};
+
+use super::Error;
+
+/// 计数器最小值，再减 1 即复位
+pub const COUNTER_MIN: u8 = 0x40;
+/// 计数器最大值
+pub const COUNTER_MAX: u8 = 0x7F;
+/// WDGTB 最大值，对应 8 分频
+pub const MAX_PRESCALER: u8 = 3;
+
+/// 提前唤醒回调
+type Hook = Option<fn()>;
+
+/// 提前唤醒回调，在 WWDG 中断中执行
+static EARLY_WAKEUP_HOOK: Mutex<Cell<Hook>> = Mutex::new(Cell::new(None));
+
+/// 窗口看门狗配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, defmt::Format)]
+pub struct WwdgConfig {
+    /// WDGTB 值，分频系数为 4096 * 2^prescaler
+    pub prescaler: u8,
+    /// 喂狗时装载的计数值
+    pub counter: u8,
+    /// 窗口值，计数值不大于窗口值时才能喂狗
+    pub window: u8,
+}
+
+impl WwdgConfig {
+    /// 根据超时时间及窗口时间计算配置，选择能满足要求的最小分频以获得最高分辨率
+    /// pclk1_hz: APB1 时钟
+    /// timeout_ms: 喂狗后到复位的时间
+    /// window_ms: 喂狗后多久才允许再次喂狗，为 0 时不限制
+    pub fn from_ms(pclk1_hz: u32, timeout_ms: u32, window_ms: u32) -> Result<Self, Error> {
+        if window_ms != 0 && window_ms >= timeout_ms {
+            return Err(Error::Window);
+        }
+        let max_ticks = (COUNTER_MAX - COUNTER_MIN + 1) as u64;
+        for prescaler in 0..=MAX_PRESCALER {
+            let ticks = to_ticks(pclk1_hz, prescaler, timeout_ms);
+            if ticks == 0 {
+                return Err(Error::TooShort);
+            }
+            if ticks > max_ticks {
+                continue;
+            }
+
+            let counter = COUNTER_MIN - 1 + ticks as u8;
+            let window = if window_ms == 0 {
+                COUNTER_MAX
+            } else {
+                let open_ticks = to_ticks(pclk1_hz, prescaler, window_ms);
+                if open_ticks >= ticks {
+                    return Err(Error::Window);
+                }
+                counter - open_ticks as u8
+            };
+            return Ok(WwdgConfig {
+                prescaler,
+                counter,
+                window,
+            });
+        }
+        Err(Error::TooLong)
+    }
+
+    /// 一个计数周期，单位：ns
+    pub fn tick_ns(&self, pclk1_hz: u32) -> u32 {
+        ((4096_u64 << self.prescaler) * 1_000_000_000 / pclk1_hz as u64) as u32
+    }
+
+    /// 喂狗后到复位的时间，单位：us
+    pub fn timeout_us(&self, pclk1_hz: u32) -> u32 {
+        (self.counter - COUNTER_MIN + 1) as u32 * self.tick_ns(pclk1_hz) / 1000
+    }
+
+    /// 喂狗后到允许再次喂狗的时间，单位：us
+    pub fn window_us(&self, pclk1_hz: u32) -> u32 {
+        self.counter.saturating_sub(self.window) as u32 * self.tick_ns(pclk1_hz) / 1000
+    }
+}
+
+/// 时间换算为计数周期数，四舍五入
+fn to_ticks(pclk1_hz: u32, prescaler: u8, ms: u32) -> u64 {
+    let divider = 4096_u64 << prescaler;
+    let pclk_ticks = ms as u64 * pclk1_hz as u64 / 1000;
+    (pclk_ticks + divider / 2) / divider
+}
+
+/// 窗口看门狗
+pub struct Wwdg {
+    wwdg: WWDG,
+    config: WwdgConfig,
+}
+
+impl Wwdg {
+    /// 使能时钟，写入配置并启动
+    pub fn start(wwdg: WWDG, config: WwdgConfig) -> Self {
+        let rcc = unsafe { &*pac::RCC::ptr() };
+        rcc.apb1enr.modify(|_, w| w.wwdgen().set_bit());
+
+        wwdg.cfr.modify(|_, w| {
+            let wdgtb = w.wdgtb();
+            let w = match config.prescaler {
+                0 => wdgtb.div1(),
+                1 => wdgtb.div2(),
+                2 => wdgtb.div4(),
+                _ => wdgtb.div8(),
+            };
+            w.w().bits(config.window)
+        });
+        wwdg.cr
+            .write(|w| w.wdga().set_bit().t().bits(config.counter));
+        Wwdg { wwdg, config }
+    }
+
+    /// 喂狗，在窗口外调用会立即复位
+    pub fn feed(&mut self) {
+        self.wwdg
+            .cr
+            .write(|w| w.wdga().set_bit().t().bits(self.config.counter));
+    }
+
+    /// 只在窗口内喂狗，返回是否已喂狗
+    pub fn try_feed(&mut self) -> bool {
+        if !self.is_window_open() {
+            return false;
+        }
+        self.feed();
+        true
+    }
+
+    /// 当前计数值
+    pub fn counter(&self) -> u8 {
+        self.wwdg.cr.read().t().bits()
+    }
+
+    /// 是否允许喂狗
+    pub fn is_window_open(&self) -> bool {
+        self.counter() <= self.config.window
+    }
+
+    /// 使能提前唤醒中断，只能由复位关闭
+    pub fn listen_early_wakeup(&mut self) {
+        self.wwdg.cfr.modify(|_, w| w.ewi().set_bit());
+    }
+
+    /// 当前配置
+    pub fn config(&self) -> WwdgConfig {
+        self.config
+    }
+}
+
+impl Watchdog for Wwdg {
+    fn feed(&mut self) {
+        Wwdg::feed(self);
+    }
+}
+
+/// 设置提前唤醒回调，用于复位前记录日志或保存数据，应尽快返回
+pub fn set_early_wakeup_hook(hook: fn()) {
+    cortex_m::interrupt::free(|cs| EARLY_WAKEUP_HOOK.borrow(cs).set(Some(hook)));
+}
+
+/// 在 WWDG 中断中调用，清除 EWIF 并执行回调
+/// 返回是否为提前唤醒中断
+pub fn on_early_wakeup() -> bool {
+    let wwdg = unsafe { &*WWDG::ptr() };
+    if wwdg.sr.read().ewif().bit_is_clear() {
+        return false;
+    }
+    wwdg.sr.write(|w| w.ewif().clear_bit());
+    if let Some(hook) = cortex_m::interrupt::free(|cs| EARLY_WAKEUP_HOOK.borrow(cs).get()) {
+        hook();
+    }
+    true
+}